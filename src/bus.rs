@@ -0,0 +1,916 @@
+mod i2c;
+pub use i2c::{decode_i2c, I2cByte, I2cTransaction};
+
+mod logic;
+pub use logic::{LogicSignal, Threshold};
+
+mod spi;
+pub use spi::{decode_spi, SpiConfig, SpiWindow, SpiWord, SPI_BIT_ORDER_CHOICES, SPI_MODE_CHOICES};
+
+mod uart;
+pub use uart::{
+    decode_uart, detect_baud, UartConfig, UartDecoding, UartFrame, UartParity, STANDARD_BAUD_RATES,
+    UART_PARITY_CHOICES,
+};
+
+use async_trait::async_trait;
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::std::attribute::boolean::BooleanAccessorModel;
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::std::attribute::r#enum::StringAccessorModel;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer,
+};
+use serde_json::json;
+use std::sync::Arc;
+use strum_macros::FromRepr;
+use tokio::sync::Mutex;
+
+use crate::si as std_att_si;
+use crate::si::{FloatAccessorModel, SiFormat};
+use crate::waveform::{Waveform, WaveformAccessorModel};
+
+/// Choices of the UART stop bits
+///
+pub const UART_STOP_BITS_CHOICES: [&str; 3] = ["1", "1.5", "2"];
+
+/// Choices of the UART data bits
+///
+pub const UART_DATA_BITS_CHOICES: [&str; 5] = ["5", "6", "7", "8", "9"];
+
+/// Choice of the SPI chip select when it is not captured
+///
+pub const SPI_NO_CS: &str = "NONE";
+
+/// Protocols decoded on the host
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HostProtocol {
+    Uart,
+    I2c,
+    Spi,
+}
+
+/// Logic conversion of a decoder input
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThresholdSettings {
+    /// Use the middle of the signal swing instead of `threshold`
+    ///
+    pub auto: bool,
+
+    /// Manual threshold
+    ///
+    pub threshold: Threshold,
+}
+
+impl Default for ThresholdSettings {
+    fn default() -> Self {
+        Self {
+            auto: true,
+            threshold: Threshold::new(1.5, 0.2),
+        }
+    }
+}
+
+impl ThresholdSettings {
+    /// Threshold a waveform with these settings
+    ///
+    pub fn apply(&self, waveform: &Waveform) -> Result<LogicSignal, String> {
+        let threshold = match self.auto {
+            true => Threshold::auto(&waveform.samples)
+                .ok_or("Flat signal, no automatic threshold".to_string())?,
+            false => self.threshold,
+        };
+        Ok(LogicSignal::from_waveform(waveform, threshold))
+    }
+}
+
+/// Settings of the host decoders, shared by their attributes
+///
+pub struct HostDecoders {
+    /// Names of the sources and their waveform index on the interface
+    ///
+    sources: Vec<(&'static str, usize)>,
+
+    /// Source of the UART line, index in `sources`
+    ///
+    uart_source: usize,
+
+    ///
+    ///
+    uart_threshold: ThresholdSettings,
+
+    ///
+    ///
+    uart: UartConfig,
+
+    /// Source of SCL
+    ///
+    i2c_scl: usize,
+
+    /// Source of SDA
+    ///
+    i2c_sda: usize,
+
+    ///
+    ///
+    i2c_threshold: ThresholdSettings,
+
+    /// Source of SCK
+    ///
+    spi_sck: usize,
+
+    /// Source of the decoded data line
+    ///
+    spi_data: usize,
+
+    /// Source of the chip select, None if it is not captured
+    ///
+    spi_cs: Option<usize>,
+
+    ///
+    ///
+    spi_threshold: ThresholdSettings,
+
+    ///
+    ///
+    spi: SpiConfig,
+}
+
+impl HostDecoders {
+    ///
+    ///
+    pub fn new(sources: Vec<(&'static str, usize)>) -> Self {
+        let second = sources.len().saturating_sub(1).min(1);
+        Self {
+            sources,
+            uart_source: 0,
+            uart_threshold: ThresholdSettings::default(),
+            uart: UartConfig::default(),
+            i2c_scl: 0,
+            i2c_sda: second,
+            i2c_threshold: ThresholdSettings::default(),
+            spi_sck: 0,
+            spi_data: second,
+            spi_cs: None,
+            spi_threshold: ThresholdSettings::default(),
+            spi: SpiConfig::default(),
+        }
+    }
+
+    /// Names of the sources
+    ///
+    pub fn source_names(&self) -> Vec<&'static str> {
+        self.sources.iter().map(|(n, _)| *n).collect()
+    }
+
+    ///
+    ///
+    fn source_name(&self, source: usize) -> String {
+        self.sources
+            .get(source)
+            .map(|(n, _)| n.to_string())
+            .unwrap_or_default()
+    }
+
+    ///
+    ///
+    fn source_of(&self, name: &str) -> Result<usize, Error> {
+        self.sources
+            .iter()
+            .position(|(n, _)| n.eq_ignore_ascii_case(name))
+            .ok_or(Error::InvalidArgument(format!(
+                "Invalid source {:?}, expected one of {:?}",
+                name,
+                self.source_names()
+            )))
+    }
+
+    /// Waveform index of a source on the interface
+    ///
+    pub fn waveform_index(&self, source: usize) -> usize {
+        self.sources.get(source).map(|(_, i)| *i).unwrap_or(0)
+    }
+
+    /// Sources captured by a protocol, in the order of its lines
+    ///
+    fn lines(&self, protocol: HostProtocol) -> Vec<usize> {
+        match protocol {
+            HostProtocol::Uart => vec![self.uart_source],
+            HostProtocol::I2c => vec![self.i2c_scl, self.i2c_sda],
+            HostProtocol::Spi => {
+                let mut lines = vec![self.spi_sck, self.spi_data];
+                lines.extend(self.spi_cs);
+                lines
+            }
+        }
+    }
+
+    /// Decode the lines of a protocol, captured in the order of `lines`
+    ///
+    fn decode(
+        &self,
+        protocol: HostProtocol,
+        waveforms: &[Waveform],
+    ) -> Result<serde_json::Value, String> {
+        let lines = self.lines(protocol);
+        let mut unique = lines.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        if unique.len() != lines.len() {
+            return Err("Each line must have its own source".to_string());
+        }
+
+        match protocol {
+            HostProtocol::Uart => {
+                let signal = self.uart_threshold.apply(&waveforms[0])?;
+                let mut value = decode_uart(&signal, &self.uart)?.to_json();
+                value["source"] = json!(self.source_name(self.uart_source));
+                Ok(value)
+            }
+            HostProtocol::I2c => {
+                let scl = self.i2c_threshold.apply(&waveforms[0])?;
+                let sda = self.i2c_threshold.apply(&waveforms[1])?;
+                let transactions = decode_i2c(&scl, &sda)?;
+                Ok(json!({
+                    "scl": self.source_name(self.i2c_scl),
+                    "sda": self.source_name(self.i2c_sda),
+                    "transactions": transactions.iter().map(|t| t.to_json()).collect::<Vec<_>>(),
+                }))
+            }
+            HostProtocol::Spi => {
+                let sck = self.spi_threshold.apply(&waveforms[0])?;
+                let data = self.spi_threshold.apply(&waveforms[1])?;
+                let cs = match waveforms.get(2) {
+                    Some(w) => Some(self.spi_threshold.apply(w)?),
+                    None => None,
+                };
+                let windows = decode_spi(&sck, &data, cs.as_ref(), &self.spi)?;
+                Ok(json!({
+                    "sck": self.source_name(self.spi_sck),
+                    "data": self.source_name(self.spi_data),
+                    "cs": self.spi_cs.map(|c| self.source_name(c)),
+                    "windows": windows.iter().map(|w| w.to_json()).collect::<Vec<_>>(),
+                }))
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum BooleanIndex {
+    //
+    UartAutoThreshold,
+    UartInverted,
+    //
+    I2cAutoThreshold,
+    //
+    SpiAutoThreshold,
+    SpiCsActiveHigh,
+}
+
+#[async_trait]
+///
+///
+impl BooleanAccessorModel for HostDecoders {
+    ///
+    ///
+    async fn get_boolean_at(&mut self, index: usize) -> Result<bool, Error> {
+        let idx = BooleanIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        Ok(match idx {
+            BooleanIndex::UartAutoThreshold => self.uart_threshold.auto,
+            BooleanIndex::UartInverted => self.uart.inverted,
+            BooleanIndex::I2cAutoThreshold => self.i2c_threshold.auto,
+            BooleanIndex::SpiAutoThreshold => self.spi_threshold.auto,
+            BooleanIndex::SpiCsActiveHigh => self.spi.cs_active_high,
+        })
+    }
+
+    ///
+    ///
+    async fn set_boolean_at(&mut self, index: usize, value: bool) -> Result<(), Error> {
+        let idx = BooleanIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        match idx {
+            BooleanIndex::UartAutoThreshold => self.uart_threshold.auto = value,
+            BooleanIndex::UartInverted => self.uart.inverted = value,
+            BooleanIndex::I2cAutoThreshold => self.i2c_threshold.auto = value,
+            BooleanIndex::SpiAutoThreshold => self.spi_threshold.auto = value,
+            BooleanIndex::SpiCsActiveHigh => self.spi.cs_active_high = value,
+        }
+        Ok(())
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum StringIndex {
+    //
+    UartSource,
+    UartParity,
+    UartStopBits,
+    UartDataBits,
+    //
+    I2cScl,
+    I2cSda,
+    //
+    SpiSck,
+    SpiData,
+    SpiCs,
+    SpiMode,
+    SpiBitOrder,
+}
+
+#[async_trait]
+///
+///
+impl StringAccessorModel for HostDecoders {
+    ///
+    ///
+    async fn get_string_at(&mut self, index: usize) -> Result<String, Error> {
+        let idx = StringIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        Ok(match idx {
+            StringIndex::UartSource => self.source_name(self.uart_source),
+            StringIndex::UartParity => self.uart.parity.name().to_string(),
+            StringIndex::UartStopBits => self.uart.stop_bits.to_string(),
+            StringIndex::UartDataBits => self.uart.data_bits.to_string(),
+            StringIndex::I2cScl => self.source_name(self.i2c_scl),
+            StringIndex::I2cSda => self.source_name(self.i2c_sda),
+            StringIndex::SpiSck => self.source_name(self.spi_sck),
+            StringIndex::SpiData => self.source_name(self.spi_data),
+            StringIndex::SpiCs => match self.spi_cs {
+                Some(cs) => self.source_name(cs),
+                None => SPI_NO_CS.to_string(),
+            },
+            StringIndex::SpiMode => self.spi.mode.to_string(),
+            StringIndex::SpiBitOrder => match self.spi.msb_first {
+                true => SPI_BIT_ORDER_CHOICES[0].to_string(),
+                false => SPI_BIT_ORDER_CHOICES[1].to_string(),
+            },
+        })
+    }
+
+    ///
+    ///
+    async fn set_string_at(&mut self, index: usize, value: &String) -> Result<(), Error> {
+        let idx = StringIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        let invalid = |choices: &[&str]| {
+            Error::InvalidArgument(format!(
+                "Invalid value {:?}, expected one of {:?}",
+                value, choices
+            ))
+        };
+        match idx {
+            StringIndex::UartSource => self.uart_source = self.source_of(value)?,
+            StringIndex::UartParity => {
+                self.uart.parity = UartParity::from_name(value).map_err(Error::InvalidArgument)?
+            }
+            StringIndex::UartStopBits => {
+                if !UART_STOP_BITS_CHOICES.contains(&value.as_str()) {
+                    return Err(invalid(&UART_STOP_BITS_CHOICES));
+                }
+                self.uart.stop_bits = value
+                    .parse()
+                    .map_err(|_| invalid(&UART_STOP_BITS_CHOICES))?
+            }
+            StringIndex::UartDataBits => {
+                if !UART_DATA_BITS_CHOICES.contains(&value.as_str()) {
+                    return Err(invalid(&UART_DATA_BITS_CHOICES));
+                }
+                self.uart.data_bits = value
+                    .parse()
+                    .map_err(|_| invalid(&UART_DATA_BITS_CHOICES))?
+            }
+            StringIndex::I2cScl => self.i2c_scl = self.source_of(value)?,
+            StringIndex::I2cSda => self.i2c_sda = self.source_of(value)?,
+            StringIndex::SpiSck => self.spi_sck = self.source_of(value)?,
+            StringIndex::SpiData => self.spi_data = self.source_of(value)?,
+            StringIndex::SpiCs => {
+                self.spi_cs = match value.eq_ignore_ascii_case(SPI_NO_CS) {
+                    true => None,
+                    false => Some(self.source_of(value)?),
+                }
+            }
+            StringIndex::SpiMode => {
+                if !SPI_MODE_CHOICES.contains(&value.as_str()) {
+                    return Err(invalid(&SPI_MODE_CHOICES));
+                }
+                self.spi.mode = value.parse().map_err(|_| invalid(&SPI_MODE_CHOICES))?
+            }
+            StringIndex::SpiBitOrder => {
+                self.spi.msb_first = match value.to_uppercase().as_str() {
+                    "MSB" => true,
+                    "LSB" => false,
+                    _ => return Err(invalid(&SPI_BIT_ORDER_CHOICES)),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum FloatIndex {
+    //
+    UartBaud,
+    UartThreshold,
+    UartHysteresis,
+    //
+    I2cThreshold,
+    I2cHysteresis,
+    //
+    SpiThreshold,
+    SpiHysteresis,
+    SpiWordBits,
+}
+
+#[async_trait]
+///
+///
+impl FloatAccessorModel for HostDecoders {
+    ///
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error> {
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        Ok(match idx {
+            FloatIndex::UartBaud => self.uart.baud.unwrap_or(0.0),
+            FloatIndex::UartThreshold => self.uart_threshold.threshold.level,
+            FloatIndex::UartHysteresis => self.uart_threshold.threshold.hysteresis,
+            FloatIndex::I2cThreshold => self.i2c_threshold.threshold.level,
+            FloatIndex::I2cHysteresis => self.i2c_threshold.threshold.hysteresis,
+            FloatIndex::SpiThreshold => self.spi_threshold.threshold.level,
+            FloatIndex::SpiHysteresis => self.spi_threshold.threshold.hysteresis,
+            FloatIndex::SpiWordBits => self.spi.word_bits as f64,
+        })
+    }
+
+    ///
+    ///
+    async fn set_float_at(&mut self, index: usize, value: f64) -> Result<(), Error> {
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        match idx {
+            FloatIndex::UartBaud => self.uart.baud = (value > 0.0).then_some(value),
+            FloatIndex::UartThreshold => self.uart_threshold.threshold.level = value,
+            FloatIndex::UartHysteresis => self.uart_threshold.threshold.hysteresis = value.abs(),
+            FloatIndex::I2cThreshold => self.i2c_threshold.threshold.level = value,
+            FloatIndex::I2cHysteresis => self.i2c_threshold.threshold.hysteresis = value.abs(),
+            FloatIndex::SpiThreshold => self.spi_threshold.threshold.level = value,
+            FloatIndex::SpiHysteresis => self.spi_threshold.threshold.hysteresis = value.abs(),
+            FloatIndex::SpiWordBits => {
+                if !(1.0..=32.0).contains(&value) {
+                    return Err(Error::InvalidArgument(format!(
+                        "Invalid word size {}, expected 1 to 32 bits",
+                        value
+                    )));
+                }
+                self.spi.word_bits = value.round() as u8
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Mount the decoders that run on the host over captured waveforms
+///
+/// `sources` names the waveforms of the interface that can feed a decoder.
+///
+pub async fn mount<C: Container + 'static, I: WaveformAccessorModel + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<I>>,
+    sources: Vec<(&'static str, usize)>,
+) -> Result<(), Error> {
+    let class_host_decode = parent.create_class("host_decode").finish().await;
+
+    let logger = class_host_decode.logger().clone();
+    log_debug_mount_start!(logger);
+
+    let decoders = Arc::new(Mutex::new(HostDecoders::new(sources)));
+
+    mount_uart(
+        class_host_decode.clone(),
+        interface.clone(),
+        decoders.clone(),
+    )
+    .await?;
+
+    mount_i2c(
+        class_host_decode.clone(),
+        interface.clone(),
+        decoders.clone(),
+    )
+    .await?;
+
+    mount_spi(
+        class_host_decode.clone(),
+        interface.clone(),
+        decoders.clone(),
+    )
+    .await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+/// Attributes of the logic conversion of an input
+///
+async fn mount_threshold<C: Container + 'static>(
+    parent: C,
+    decoders: Arc<Mutex<HostDecoders>>,
+    indexes: (BooleanIndex, FloatIndex, FloatIndex),
+) -> Result<(), Error> {
+    let (auto, threshold, hysteresis) = indexes;
+
+    std_att_boolean::mount(
+        parent.clone(),
+        decoders.clone(),
+        auto as usize,
+        "auto_threshold",
+        "Use the middle of the signal swing as threshold, with 10% of hysteresis",
+    )
+    .await?;
+
+    std_att_si::mount(
+        parent.clone(),
+        decoders.clone(),
+        threshold as usize,
+        "threshold",
+        "Logic threshold, when the automatic threshold is disabled",
+        SiFormat::new("V", -400.0, 400.0, 3),
+    )
+    .await?;
+
+    std_att_si::mount(
+        parent.clone(),
+        decoders.clone(),
+        hysteresis as usize,
+        "hysteresis",
+        "Width of the hysteresis around the threshold",
+        SiFormat::new("V", 0.0, 400.0, 3),
+    )
+    .await?;
+
+    Ok(())
+}
+
+///
+///
+async fn mount_uart<C: Container + 'static, I: WaveformAccessorModel + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<I>>,
+    decoders: Arc<Mutex<HostDecoders>>,
+) -> Result<(), Error> {
+    let class_uart = parent.create_class("uart").finish().await;
+    let sources = decoders.lock().await.source_names();
+
+    std_att_enum::mount(
+        class_uart.clone(),
+        decoders.clone(),
+        StringIndex::UartSource as usize,
+        "source",
+        "Channel of the UART line",
+        sources,
+    )
+    .await?;
+
+    mount_threshold(
+        class_uart.clone(),
+        decoders.clone(),
+        (
+            BooleanIndex::UartAutoThreshold,
+            FloatIndex::UartThreshold,
+            FloatIndex::UartHysteresis,
+        ),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_uart.clone(),
+        decoders.clone(),
+        FloatIndex::UartBaud as usize,
+        "baud",
+        "Baud rate, 0 to detect it from the shortest pulses",
+        SiFormat::new("bps", 0.0, 20_000_000.0, 0),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_uart.clone(),
+        decoders.clone(),
+        StringIndex::UartParity as usize,
+        "parity",
+        "",
+        UART_PARITY_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_uart.clone(),
+        decoders.clone(),
+        StringIndex::UartStopBits as usize,
+        "stop_bits",
+        "",
+        UART_STOP_BITS_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_uart.clone(),
+        decoders.clone(),
+        StringIndex::UartDataBits as usize,
+        "data_bits",
+        "",
+        UART_DATA_BITS_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_boolean::mount(
+        class_uart.clone(),
+        decoders.clone(),
+        BooleanIndex::UartInverted as usize,
+        "inverted",
+        "The line idles low, e.g. RS-232 levels probed before the transceiver",
+    )
+    .await?;
+
+    mount_decode(
+        class_uart,
+        interface,
+        decoders,
+        HostProtocol::Uart,
+        "frames",
+        "Write any value to capture the source and decode it
+
+    { \"source\": \"CHANnel1\", \"baud\": 115200, \"frames\": [
+      { \"time\": -0.00097, \"data\": 72, \"parity_error\": false, \"framing_error\": false } ] }
+",
+    )
+    .await
+}
+
+///
+///
+async fn mount_i2c<C: Container + 'static, I: WaveformAccessorModel + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<I>>,
+    decoders: Arc<Mutex<HostDecoders>>,
+) -> Result<(), Error> {
+    let class_i2c = parent.create_class("i2c").finish().await;
+    let sources = decoders.lock().await.source_names();
+
+    std_att_enum::mount(
+        class_i2c.clone(),
+        decoders.clone(),
+        StringIndex::I2cScl as usize,
+        "scl",
+        "Channel of the clock line",
+        sources.clone(),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_i2c.clone(),
+        decoders.clone(),
+        StringIndex::I2cSda as usize,
+        "sda",
+        "Channel of the data line",
+        sources,
+    )
+    .await?;
+
+    mount_threshold(
+        class_i2c.clone(),
+        decoders.clone(),
+        (
+            BooleanIndex::I2cAutoThreshold,
+            FloatIndex::I2cThreshold,
+            FloatIndex::I2cHysteresis,
+        ),
+    )
+    .await?;
+
+    mount_decode(
+        class_i2c,
+        interface,
+        decoders,
+        HostProtocol::I2c,
+        "transactions",
+        "Write any value to capture SCL and SDA together and decode them
+
+    { \"scl\": \"CHANnel1\", \"sda\": \"CHANnel2\", \"transactions\": [
+      { \"time\": -0.0004, \"restart\": false, \"address\": 80, \"read\": false,
+        \"address_ack\": true, \"data\": [ { \"time\": -0.00031, \"value\": 18, \"ack\": true } ],
+        \"stop\": -0.0002 } ] }
+",
+    )
+    .await
+}
+
+///
+///
+async fn mount_spi<C: Container + 'static, I: WaveformAccessorModel + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<I>>,
+    decoders: Arc<Mutex<HostDecoders>>,
+) -> Result<(), Error> {
+    let class_spi = parent.create_class("spi").finish().await;
+    let sources = decoders.lock().await.source_names();
+
+    std_att_enum::mount(
+        class_spi.clone(),
+        decoders.clone(),
+        StringIndex::SpiSck as usize,
+        "sck",
+        "Channel of the clock line",
+        sources.clone(),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_spi.clone(),
+        decoders.clone(),
+        StringIndex::SpiData as usize,
+        "data",
+        "Channel of the decoded data line, MOSI or MISO",
+        sources.clone(),
+    )
+    .await?;
+
+    let mut cs_choices = vec![SPI_NO_CS];
+    cs_choices.extend(sources);
+    std_att_enum::mount(
+        class_spi.clone(),
+        decoders.clone(),
+        StringIndex::SpiCs as usize,
+        "cs",
+        "Channel of the chip select, without it the windows are split on idle clock",
+        cs_choices,
+    )
+    .await?;
+
+    std_att_boolean::mount(
+        class_spi.clone(),
+        decoders.clone(),
+        BooleanIndex::SpiCsActiveHigh as usize,
+        "cs_active_high",
+        "",
+    )
+    .await?;
+
+    mount_threshold(
+        class_spi.clone(),
+        decoders.clone(),
+        (
+            BooleanIndex::SpiAutoThreshold,
+            FloatIndex::SpiThreshold,
+            FloatIndex::SpiHysteresis,
+        ),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_spi.clone(),
+        decoders.clone(),
+        StringIndex::SpiMode as usize,
+        "mode",
+        "
+    * 0: clock idles low, sampled on rising edges
+    * 1: clock idles low, sampled on falling edges
+    * 2: clock idles high, sampled on falling edges
+    * 3: clock idles high, sampled on rising edges
+        ",
+        SPI_MODE_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_spi.clone(),
+        decoders.clone(),
+        StringIndex::SpiBitOrder as usize,
+        "bit_order",
+        "",
+        SPI_BIT_ORDER_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_spi.clone(),
+        decoders.clone(),
+        FloatIndex::SpiWordBits as usize,
+        "word_bits",
+        "Number of bits per word",
+        SiFormat::new("", 1.0, 32.0, 0),
+    )
+    .await?;
+
+    mount_decode(
+        class_spi,
+        interface,
+        decoders,
+        HostProtocol::Spi,
+        "windows",
+        "Write any value to capture the lines together and decode them
+
+    { \"sck\": \"CHANnel1\", \"data\": \"CHANnel2\", \"cs\": null, \"windows\": [
+      { \"time\": -0.0001, \"end\": -0.00005, \"partial_bits\": 0,
+        \"words\": [ { \"time\": -0.0001, \"value\": 159 } ] } ] }
+",
+    )
+    .await
+}
+
+/// Attribute that captures the lines of a protocol and publishes their decoding
+///
+async fn mount_decode<C: Container + 'static, I: WaveformAccessorModel + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<I>>,
+    decoders: Arc<Mutex<HostDecoders>>,
+    protocol: HostProtocol,
+    name: &str,
+    info: &str,
+) -> Result<(), Error> {
+    let att = parent
+        .create_attribute(name)
+        .with_rw()
+        .with_info(info)
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => host decode",
+        parent,
+        att,
+        on_decode_command(att.clone(), interface.clone(), decoders.clone(), protocol)
+    );
+
+    Ok(())
+}
+
+/// Capture the lines of a protocol together and decode them
+///
+async fn capture_and_decode<I: WaveformAccessorModel + 'static>(
+    interface: &Arc<Mutex<I>>,
+    decoders: &Arc<Mutex<HostDecoders>>,
+    protocol: HostProtocol,
+) -> Result<serde_json::Value, Error> {
+    let indexes: Vec<usize> = {
+        let d = decoders.lock().await;
+        d.lines(protocol)
+            .into_iter()
+            .map(|l| d.waveform_index(l))
+            .collect()
+    };
+    let waveforms = interface.lock().await.get_waveforms_at(&indexes).await?;
+
+    decoders
+        .lock()
+        .await
+        .decode(protocol, &waveforms)
+        .map_err(Error::InvalidArgument)
+}
+
+///
+///
+async fn on_decode_command<I: WaveformAccessorModel + 'static>(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<I>>,
+    decoders: Arc<Mutex<HostDecoders>>,
+    protocol: HostProtocol,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Log
+        log_debug!(
+            att.logger(),
+            "host {:?} decode requested '{:?}'",
+            protocol,
+            command
+        );
+
+        match capture_and_decode(&interface, &decoders, protocol).await {
+            Ok(v) => att.set(v).await?,
+            Err(e) => log_warn!(att.logger(), "host {:?} decode failed '{:?}'", protocol, e),
+        }
+    }
+    Ok(())
+}
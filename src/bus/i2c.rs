@@ -0,0 +1,344 @@
+use serde_json::json;
+
+use super::LogicSignal;
+
+/// Byte transferred after the address, with the acknowledge of the receiver
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct I2cByte {
+    /// Time of the SCL rising edge of the first bit, in seconds
+    ///
+    pub time: f64,
+
+    ///
+    ///
+    pub value: u8,
+
+    /// True if the receiver pulled SDA low on the 9th clock
+    ///
+    pub ack: bool,
+}
+
+/// Transfer between a START (or repeated START) and the next START or STOP
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct I2cTransaction {
+    /// Time of the START condition, in seconds
+    ///
+    pub time: f64,
+
+    /// True if the transaction began with a repeated START
+    ///
+    pub restart: bool,
+
+    /// 7 bits address
+    ///
+    pub address: u8,
+
+    /// True for a read from the target
+    ///
+    pub read: bool,
+
+    /// True if the target acknowledged its address
+    ///
+    pub address_ack: bool,
+
+    ///
+    ///
+    pub data: Vec<I2cByte>,
+
+    /// Time of the STOP condition, None if the transaction ended with a
+    /// repeated START or with the capture
+    ///
+    pub stop: Option<f64>,
+}
+
+impl I2cTransaction {
+    /// JSON representation published on the attributes
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "time": self.time,
+            "restart": self.restart,
+            "address": self.address,
+            "read": self.read,
+            "address_ack": self.address_ack,
+            "data": self.data.iter().map(|b| json!({
+                "time": b.time,
+                "value": b.value,
+                "ack": b.ack,
+            })).collect::<Vec<_>>(),
+            "stop": self.stop,
+        })
+    }
+}
+
+/// Transaction being received
+///
+struct Pending {
+    ///
+    ///
+    transaction: I2cTransaction,
+
+    /// True once the address byte is received
+    ///
+    has_address: bool,
+
+    /// Bits of the current byte, then its acknowledge
+    ///
+    bits: Vec<bool>,
+
+    /// Time of the first bit of the current byte
+    ///
+    byte_time: f64,
+}
+
+impl Pending {
+    ///
+    ///
+    fn new(time: f64, restart: bool) -> Self {
+        Self {
+            transaction: I2cTransaction {
+                time,
+                restart,
+                address: 0,
+                read: false,
+                address_ack: false,
+                data: Vec::new(),
+                stop: None,
+            },
+            has_address: false,
+            bits: Vec::with_capacity(9),
+            byte_time: time,
+        }
+    }
+
+    /// Shift the bit sampled on a SCL rising edge
+    ///
+    fn push_bit(&mut self, time: f64, bit: bool) {
+        if self.bits.is_empty() {
+            self.byte_time = time;
+        }
+        self.bits.push(bit);
+        if self.bits.len() < 9 {
+            return;
+        }
+
+        let value = self.bits[..8]
+            .iter()
+            .fold(0u8, |acc, b| (acc << 1) | (*b as u8));
+        let ack = !self.bits[8];
+        self.bits.clear();
+
+        match self.has_address {
+            false => {
+                self.transaction.address = value >> 1;
+                self.transaction.read = value & 1 == 1;
+                self.transaction.address_ack = ack;
+                self.has_address = true;
+            }
+            true => self.transaction.data.push(I2cByte {
+                time: self.byte_time,
+                value,
+                ack,
+            }),
+        }
+    }
+}
+
+/// Decode the I2C transactions of a capture
+///
+/// SCL and SDA must be sampled together. Transactions without a complete
+/// address are dropped, an incomplete last byte is ignored.
+///
+pub fn decode_i2c(scl: &LogicSignal, sda: &LogicSignal) -> Result<Vec<I2cTransaction>, String> {
+    if scl.len() != sda.len() {
+        return Err(format!(
+            "SCL and SDA captures differ in length ({} and {} points)",
+            scl.len(),
+            sda.len()
+        ));
+    }
+
+    let mut transactions = Vec::new();
+    let mut pending: Option<Pending> = None;
+
+    for i in 1..scl.len() {
+        let (scl_prev, scl_now) = (scl.levels[i - 1], scl.levels[i]);
+        let (sda_prev, sda_now) = (sda.levels[i - 1], sda.levels[i]);
+        let time = scl.time_at(i);
+
+        //
+        // SDA moving while SCL stays high is a START or a STOP
+        if scl_prev && scl_now && sda_prev != sda_now {
+            let restart = pending.is_some();
+            if let Some(p) = pending.take() {
+                let mut transaction = p.transaction;
+                if sda_now {
+                    transaction.stop = Some(time);
+                }
+                if p.has_address {
+                    transactions.push(transaction);
+                }
+            }
+            if !sda_now {
+                pending = Some(Pending::new(time, restart));
+            }
+            continue;
+        }
+
+        //
+        // Bits are sampled on the SCL rising edges
+        if !scl_prev && scl_now {
+            if let Some(p) = pending.as_mut() {
+                p.push_bit(time, sda_now);
+            }
+        }
+    }
+
+    if let Some(p) = pending {
+        if p.has_address {
+            transactions.push(p.transaction);
+        }
+    }
+
+    Ok(transactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::Threshold;
+    use crate::waveform::Waveform;
+
+    /// Samples per half period of SCL
+    const HALF: usize = 5;
+
+    /// Synthetic bus driven bit by bit, 3.3V logic with a bit of noise
+    struct Bus {
+        scl: Vec<f64>,
+        sda: Vec<f64>,
+    }
+
+    impl Bus {
+        fn new() -> Self {
+            let mut bus = Self {
+                scl: Vec::new(),
+                sda: Vec::new(),
+            };
+            bus.hold(true, true, 2 * HALF);
+            bus
+        }
+
+        fn hold(&mut self, scl: bool, sda: bool, n: usize) {
+            for i in 0..n {
+                let noise = if i % 2 == 0 { 0.05 } else { -0.05 };
+                self.scl.push(if scl { 3.3 } else { 0.0 } + noise);
+                self.sda.push(if sda { 3.3 } else { 0.0 } - noise);
+            }
+        }
+
+        fn start(&mut self) {
+            self.hold(true, true, HALF);
+            self.hold(true, false, HALF);
+            self.hold(false, false, HALF);
+        }
+
+        fn stop(&mut self) {
+            self.hold(false, false, HALF);
+            self.hold(true, false, HALF);
+            self.hold(true, true, 2 * HALF);
+        }
+
+        fn bit(&mut self, bit: bool) {
+            self.hold(false, bit, HALF);
+            self.hold(true, bit, HALF);
+            self.hold(false, bit, 1);
+        }
+
+        fn byte(&mut self, value: u8, ack: bool) {
+            for i in (0..8).rev() {
+                self.bit(value >> i & 1 == 1);
+            }
+            self.bit(!ack);
+        }
+
+        fn signals(&self) -> (LogicSignal, LogicSignal) {
+            let convert = |samples: &Vec<f64>| {
+                let waveform = Waveform::new(samples.clone(), 0.0, 1e-6, "V");
+                LogicSignal::from_waveform(&waveform, Threshold::auto(samples).unwrap())
+            };
+            (convert(&self.scl), convert(&self.sda))
+        }
+    }
+
+    #[test]
+    fn decode_write() {
+        let mut bus = Bus::new();
+        bus.start();
+        bus.byte(0x50 << 1, true);
+        bus.byte(0x12, true);
+        bus.byte(0xA5, false);
+        bus.stop();
+
+        let (scl, sda) = bus.signals();
+        let transactions = decode_i2c(&scl, &sda).unwrap();
+        assert_eq!(transactions.len(), 1);
+
+        let t = &transactions[0];
+        assert!(!t.restart);
+        assert_eq!(t.address, 0x50);
+        assert!(!t.read);
+        assert!(t.address_ack);
+        assert_eq!(
+            t.data.iter().map(|b| (b.value, b.ack)).collect::<Vec<_>>(),
+            vec![(0x12, true), (0xA5, false)]
+        );
+        assert!(t.stop.is_some());
+        assert!(t.time < t.data[0].time && t.data[1].time < t.stop.unwrap());
+    }
+
+    #[test]
+    fn decode_repeated_start() {
+        let mut bus = Bus::new();
+        bus.start();
+        bus.byte(0x68 << 1, true);
+        bus.byte(0x75, true);
+        bus.hold(false, true, HALF);
+        bus.start();
+        bus.byte(0x68 << 1 | 1, true);
+        bus.byte(0x71, false);
+        bus.stop();
+
+        let (scl, sda) = bus.signals();
+        let transactions = decode_i2c(&scl, &sda).unwrap();
+        assert_eq!(transactions.len(), 2);
+
+        assert_eq!(transactions[0].address, 0x68);
+        assert!(!transactions[0].read);
+        assert_eq!(transactions[0].data[0].value, 0x75);
+        assert_eq!(transactions[0].stop, None);
+
+        assert!(transactions[1].restart);
+        assert_eq!(transactions[1].address, 0x68);
+        assert!(transactions[1].read);
+        assert_eq!(transactions[1].data[0].value, 0x71);
+        assert!(!transactions[1].data[0].ack);
+        assert!(transactions[1].stop.is_some());
+    }
+
+    #[test]
+    fn decode_address_nack() {
+        let mut bus = Bus::new();
+        bus.start();
+        bus.byte(0x3C << 1, false);
+        bus.stop();
+
+        let (scl, sda) = bus.signals();
+        let transactions = decode_i2c(&scl, &sda).unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].address, 0x3C);
+        assert!(!transactions[0].address_ack);
+        assert!(transactions[0].data.is_empty());
+    }
+}
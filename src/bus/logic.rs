@@ -0,0 +1,137 @@
+use crate::waveform::Waveform;
+
+/// Part of the signal swing used as hysteresis by the automatic threshold
+///
+const AUTO_HYSTERESIS_RATIO: f64 = 0.1;
+
+/// Threshold that converts an analog waveform into logic levels
+///
+/// The level switches high above `level + hysteresis / 2` and low below
+/// `level - hysteresis / 2`, noise within the hysteresis does not create edges.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Threshold {
+    /// Middle of the threshold, in the unit of the waveform
+    ///
+    pub level: f64,
+
+    /// Width of the hysteresis, in the unit of the waveform
+    ///
+    pub hysteresis: f64,
+}
+
+impl Threshold {
+    ///
+    ///
+    pub fn new(level: f64, hysteresis: f64) -> Self {
+        Self {
+            level,
+            hysteresis: hysteresis.abs(),
+        }
+    }
+
+    /// Threshold in the middle of the signal swing
+    ///
+    /// None if the signal is flat.
+    ///
+    pub fn auto(samples: &[f64]) -> Option<Self> {
+        let min = samples.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = samples.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let swing = max - min;
+        if !swing.is_finite() || swing <= 0.0 {
+            return None;
+        }
+        Some(Self::new((min + max) / 2.0, swing * AUTO_HYSTERESIS_RATIO))
+    }
+}
+
+/// Logic levels of a thresholded waveform, sampled like the waveform
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicSignal {
+    /// Level of each sample
+    ///
+    pub levels: Vec<bool>,
+
+    /// Time of the first sample, in seconds
+    ///
+    pub x_origin: f64,
+
+    /// Time between two samples, in seconds
+    ///
+    pub x_increment: f64,
+}
+
+impl LogicSignal {
+    /// Threshold a waveform
+    ///
+    pub fn from_waveform(waveform: &Waveform, threshold: Threshold) -> Self {
+        let high = threshold.level + threshold.hysteresis / 2.0;
+        let low = threshold.level - threshold.hysteresis / 2.0;
+        let mut state = waveform
+            .samples
+            .first()
+            .map(|s| *s >= threshold.level)
+            .unwrap_or(false);
+        let levels = waveform
+            .samples
+            .iter()
+            .map(|s| {
+                if *s > high {
+                    state = true;
+                } else if *s < low {
+                    state = false;
+                }
+                state
+            })
+            .collect();
+        Self {
+            levels,
+            x_origin: waveform.x_origin,
+            x_increment: waveform.x_increment,
+        }
+    }
+
+    /// Number of samples
+    ///
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// True if the signal has no sample
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// Time of the sample `i`, in seconds
+    ///
+    pub fn time_at(&self, i: usize) -> f64 {
+        self.x_origin + i as f64 * self.x_increment
+    }
+
+    /// Level at the sample `i`, the last level after the end of the signal
+    ///
+    pub fn level_at(&self, i: usize) -> bool {
+        self.levels
+            .get(i)
+            .or(self.levels.last())
+            .cloned()
+            .unwrap_or(false)
+    }
+
+    /// Index of the first sample of each new level
+    ///
+    pub fn edges(&self) -> Vec<usize> {
+        (1..self.levels.len())
+            .filter(|i| self.levels[*i] != self.levels[i - 1])
+            .collect()
+    }
+
+    /// Index of the first edge to `level` at or after the sample `from`
+    ///
+    pub fn next_edge(&self, from: usize, level: bool) -> Option<usize> {
+        (from.max(1)..self.levels.len())
+            .find(|i| self.levels[*i] == level && self.levels[i - 1] != level)
+    }
+}
@@ -0,0 +1,419 @@
+use serde_json::json;
+
+use super::LogicSignal;
+
+/// Names of the bit orders, as published on the attributes
+///
+pub const SPI_BIT_ORDER_CHOICES: [&str; 2] = ["MSB", "LSB"];
+
+/// Names of the SPI modes, as published on the attributes
+///
+pub const SPI_MODE_CHOICES: [&str; 4] = ["0", "1", "2", "3"];
+
+/// Idle clock longer than this many bit periods closes a window when there is
+/// no chip select
+///
+const WINDOW_GAP_BITS: f64 = 4.0;
+
+/// Format of the SPI bus
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpiConfig {
+    /// SPI mode 0 to 3, CPOL is the bit 1 and CPHA the bit 0
+    ///
+    pub mode: u8,
+
+    /// True if the words are sent MSB first
+    ///
+    pub msb_first: bool,
+
+    /// Number of bits per word, 1 to 32
+    ///
+    pub word_bits: u8,
+
+    /// True if the chip select is active high
+    ///
+    pub cs_active_high: bool,
+}
+
+impl Default for SpiConfig {
+    fn default() -> Self {
+        Self {
+            mode: 0,
+            msb_first: true,
+            word_bits: 8,
+            cs_active_high: false,
+        }
+    }
+}
+
+impl SpiConfig {
+    /// True if the data is sampled on the rising edges of the clock
+    ///
+    /// Mode 0 and 3 sample on rising edges, mode 1 and 2 on falling edges.
+    ///
+    pub fn samples_on_rising(&self) -> bool {
+        let cpol = self.mode & 0b10 != 0;
+        let cpha = self.mode & 0b01 != 0;
+        cpol == cpha
+    }
+}
+
+/// Word transferred in a window
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpiWord {
+    /// Time of the sampling edge of the first bit, in seconds
+    ///
+    pub time: f64,
+
+    ///
+    ///
+    pub value: u32,
+}
+
+/// Words transferred while the chip select is active
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpiWindow {
+    /// Time of the chip select activation (or of the first clock edge)
+    ///
+    pub time: f64,
+
+    /// Time of the chip select release (or of the last clock edge)
+    ///
+    pub end: f64,
+
+    ///
+    ///
+    pub words: Vec<SpiWord>,
+
+    /// Number of bits left after the last complete word
+    ///
+    pub partial_bits: usize,
+}
+
+impl SpiWindow {
+    ///
+    ///
+    fn new(time: f64) -> Self {
+        Self {
+            time,
+            end: time,
+            words: Vec::new(),
+            partial_bits: 0,
+        }
+    }
+
+    /// JSON representation published on the attributes
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "time": self.time,
+            "end": self.end,
+            "words": self.words.iter().map(|w| json!({
+                "time": w.time,
+                "value": w.value,
+            })).collect::<Vec<_>>(),
+            "partial_bits": self.partial_bits,
+        })
+    }
+}
+
+/// Decode the SPI windows of a capture
+///
+/// The windows follow the chip select when it is captured. Without chip select
+/// a window ends when the clock stays idle longer than a few bit periods, the
+/// first word is then aligned on the first clock edge of the capture.
+///
+pub fn decode_spi(
+    sck: &LogicSignal,
+    data: &LogicSignal,
+    cs: Option<&LogicSignal>,
+    config: &SpiConfig,
+) -> Result<Vec<SpiWindow>, String> {
+    if !(1..=32).contains(&config.word_bits) {
+        return Err(format!("Invalid word size {} bits", config.word_bits));
+    }
+    if config.mode > 3 {
+        return Err(format!("Invalid SPI mode {}", config.mode));
+    }
+    let len = sck.len();
+    if data.len() != len || cs.map(|c| c.len() != len).unwrap_or(false) {
+        return Err("SPI captures differ in length".to_string());
+    }
+
+    //
+    // Sampling edges, with the chip select they are kept inside active windows only
+    let sampling_level = config.samples_on_rising();
+    let active = |i: usize| {
+        cs.map(|c| c.levels[i] == config.cs_active_high)
+            .unwrap_or(true)
+    };
+    let edges: Vec<usize> = (1..len)
+        .filter(|i| sck.levels[*i] == sampling_level && sck.levels[i - 1] != sampling_level)
+        .collect();
+
+    //
+    // Without chip select, the bit period is the median spacing of the edges
+    let max_gap = match cs {
+        Some(_) => None,
+        None => {
+            let mut spacings: Vec<usize> = edges.windows(2).map(|w| w[1] - w[0]).collect();
+            spacings.sort_unstable();
+            spacings
+                .get(spacings.len() / 2)
+                .map(|s| *s as f64 * WINDOW_GAP_BITS)
+        }
+    };
+
+    let mut windows = Vec::new();
+    let mut window: Option<SpiWindow> = None;
+    let mut bits: Vec<bool> = Vec::with_capacity(config.word_bits as usize);
+    let mut word_time = 0.0;
+    let mut previous_edge: Option<usize> = None;
+
+    let close = |window: Option<SpiWindow>,
+                 bits: &mut Vec<bool>,
+                 windows: &mut Vec<SpiWindow>,
+                 end: f64| {
+        if let Some(mut w) = window {
+            w.end = end;
+            w.partial_bits = bits.len();
+            windows.push(w);
+        }
+        bits.clear();
+    };
+
+    //
+    // Chip select transitions and sampling edges, in time order
+    let mut next_edge = edges.iter().peekable();
+    for i in 0..len {
+        let is_active = active(i);
+        let was_active = i > 0 && active(i - 1);
+
+        if cs.is_some() && is_active && !was_active {
+            close(window.take(), &mut bits, &mut windows, sck.time_at(i));
+            window = Some(SpiWindow::new(sck.time_at(i)));
+        }
+        if cs.is_some() && !is_active && was_active {
+            close(window.take(), &mut bits, &mut windows, sck.time_at(i));
+        }
+
+        if next_edge.peek() != Some(&&i) {
+            continue;
+        }
+        next_edge.next();
+        if !is_active {
+            continue;
+        }
+
+        //
+        // Without chip select a long idle clock separates the windows
+        if let (Some(gap), Some(previous)) = (max_gap, previous_edge) {
+            if (i - previous) as f64 > gap {
+                close(
+                    window.take(),
+                    &mut bits,
+                    &mut windows,
+                    sck.time_at(previous),
+                );
+            }
+        }
+        previous_edge = Some(i);
+        let w = window.get_or_insert_with(|| SpiWindow::new(sck.time_at(i)));
+
+        if bits.is_empty() {
+            word_time = sck.time_at(i);
+        }
+        bits.push(data.levels[i]);
+        if bits.len() == config.word_bits as usize {
+            let value = match config.msb_first {
+                true => bits.iter().fold(0u32, |acc, b| (acc << 1) | (*b as u32)),
+                false => bits
+                    .iter()
+                    .rev()
+                    .fold(0u32, |acc, b| (acc << 1) | (*b as u32)),
+            };
+            w.words.push(SpiWord {
+                time: word_time,
+                value,
+            });
+            bits.clear();
+        }
+    }
+
+    let end = match (cs, previous_edge) {
+        (None, Some(previous)) => sck.time_at(previous),
+        _ => sck.time_at(len.saturating_sub(1)),
+    };
+    close(window.take(), &mut bits, &mut windows, end);
+
+    Ok(windows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::Threshold;
+    use crate::waveform::Waveform;
+
+    /// Samples per half period of SCK
+    const HALF: usize = 4;
+
+    /// Synthetic bus driven word by word, 3.3V logic
+    struct Bus {
+        config: SpiConfig,
+        sck: Vec<f64>,
+        data: Vec<f64>,
+        cs: Vec<f64>,
+    }
+
+    impl Bus {
+        fn new(config: SpiConfig) -> Self {
+            let mut bus = Self {
+                config,
+                sck: Vec::new(),
+                data: Vec::new(),
+                cs: Vec::new(),
+            };
+            bus.idle(3 * HALF);
+            bus
+        }
+
+        fn level(high: bool) -> f64 {
+            if high {
+                3.3
+            } else {
+                0.0
+            }
+        }
+
+        fn push(&mut self, sck: bool, data: bool, cs_active: bool, n: usize) {
+            for _ in 0..n {
+                self.sck.push(Self::level(sck));
+                self.data.push(Self::level(data));
+                self.cs
+                    .push(Self::level(cs_active == self.config.cs_active_high));
+            }
+        }
+
+        fn idle(&mut self, n: usize) {
+            let cpol = self.config.mode & 0b10 != 0;
+            self.push(cpol, false, false, n);
+        }
+
+        /// Chip select window with the given words
+        fn window(&mut self, words: &[u32]) {
+            let cpol = self.config.mode & 0b10 != 0;
+            let cpha = self.config.mode & 0b01 != 0;
+            self.push(cpol, false, true, HALF);
+            for word in words {
+                for i in 0..self.config.word_bits {
+                    let shift = match self.config.msb_first {
+                        true => self.config.word_bits - 1 - i,
+                        false => i,
+                    };
+                    let bit = word >> shift & 1 == 1;
+                    match cpha {
+                        // Data set before the leading edge, sampled on it
+                        false => {
+                            self.push(cpol, bit, true, HALF);
+                            self.push(!cpol, bit, true, HALF);
+                        }
+                        // Data set on the leading edge, sampled on the trailing one
+                        true => {
+                            self.push(!cpol, bit, true, HALF);
+                            self.push(cpol, bit, true, HALF);
+                        }
+                    }
+                }
+            }
+            self.push(cpol, false, true, HALF);
+            self.idle(3 * HALF);
+        }
+
+        fn signals(&self) -> (LogicSignal, LogicSignal, LogicSignal) {
+            let convert = |samples: &Vec<f64>| {
+                let waveform = Waveform::new(samples.clone(), 0.0, 1e-7, "V");
+                LogicSignal::from_waveform(&waveform, Threshold::new(1.65, 0.3))
+            };
+            (convert(&self.sck), convert(&self.data), convert(&self.cs))
+        }
+    }
+
+    #[test]
+    fn decode_modes_with_chip_select() {
+        for mode in 0..4 {
+            let config = SpiConfig {
+                mode,
+                ..SpiConfig::default()
+            };
+            let mut bus = Bus::new(config);
+            bus.window(&[0x9F, 0x00, 0xC2]);
+            bus.window(&[0x05]);
+
+            let (sck, data, cs) = bus.signals();
+            let windows = decode_spi(&sck, &data, Some(&cs), &config).unwrap();
+            assert_eq!(windows.len(), 2, "mode {}", mode);
+            assert_eq!(
+                windows[0].words.iter().map(|w| w.value).collect::<Vec<_>>(),
+                vec![0x9F, 0x00, 0xC2],
+                "mode {}",
+                mode
+            );
+            assert_eq!(windows[1].words[0].value, 0x05, "mode {}", mode);
+            assert_eq!(windows[0].partial_bits, 0);
+            assert!(windows[0].time < windows[0].words[0].time);
+            assert!(windows[0].end < windows[1].time);
+        }
+    }
+
+    #[test]
+    fn decode_lsb_first_wide_words() {
+        let config = SpiConfig {
+            mode: 3,
+            msb_first: false,
+            word_bits: 12,
+            cs_active_high: true,
+        };
+        let mut bus = Bus::new(config);
+        bus.window(&[0xABC, 0x123]);
+
+        let (sck, data, cs) = bus.signals();
+        let windows = decode_spi(&sck, &data, Some(&cs), &config).unwrap();
+        assert_eq!(windows.len(), 1);
+        assert_eq!(
+            windows[0].words.iter().map(|w| w.value).collect::<Vec<_>>(),
+            vec![0xABC, 0x123]
+        );
+    }
+
+    #[test]
+    fn decode_without_chip_select() {
+        let config = SpiConfig::default();
+        let mut bus = Bus::new(config);
+        bus.window(&[0x12, 0x34]);
+        bus.idle(20 * HALF);
+        bus.window(&[0x56]);
+
+        //
+        // A partial word at the end of the last window
+        let mut truncated = Bus::new(config);
+        truncated.window(&[0x78]);
+        let cut = truncated.sck.len() - 3 * HALF - HALF - 6 * HALF;
+        bus.sck.extend_from_slice(&truncated.sck[..cut]);
+        bus.data.extend_from_slice(&truncated.data[..cut]);
+
+        let (sck, data, _) = bus.signals();
+        let windows = decode_spi(&sck, &data, None, &config).unwrap();
+        assert_eq!(windows.len(), 3);
+        assert_eq!(
+            windows[0].words.iter().map(|w| w.value).collect::<Vec<_>>(),
+            vec![0x12, 0x34]
+        );
+        assert_eq!(windows[1].words[0].value, 0x56);
+        assert!(windows[2].words.is_empty());
+        assert_eq!(windows[2].partial_bits, 5);
+    }
+}
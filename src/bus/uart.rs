@@ -0,0 +1,465 @@
+use serde_json::json;
+
+use super::LogicSignal;
+
+/// Standard baud rates, an estimated rate close enough snaps to them
+///
+pub const STANDARD_BAUD_RATES: [f64; 16] = [
+    300.0, 600.0, 1200.0, 2400.0, 4800.0, 9600.0, 14400.0, 19200.0, 28800.0, 38400.0, 57600.0,
+    115200.0, 230400.0, 460800.0, 921600.0, 1000000.0,
+];
+
+/// Relative distance under which an estimated rate snaps to a standard one
+///
+const BAUD_SNAP_TOLERANCE: f64 = 0.04;
+
+/// Longest run between two edges considered by the baud detection, in bits
+///
+const BAUD_DETECTION_MAX_BITS: f64 = 12.0;
+
+/// Parity of the UART frames
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UartParity {
+    None,
+    Odd,
+    Even,
+}
+
+/// Names of the parities, as published on the attributes
+///
+pub const UART_PARITY_CHOICES: [&str; 3] = ["NONE", "ODD", "EVEN"];
+
+impl UartParity {
+    ///
+    ///
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_uppercase().as_str() {
+            "NONE" => Ok(Self::None),
+            "ODD" => Ok(Self::Odd),
+            "EVEN" => Ok(Self::Even),
+            _ => Err(format!(
+                "Invalid parity {:?}, expected one of {:?}",
+                name, UART_PARITY_CHOICES
+            )),
+        }
+    }
+
+    ///
+    ///
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => UART_PARITY_CHOICES[0],
+            Self::Odd => UART_PARITY_CHOICES[1],
+            Self::Even => UART_PARITY_CHOICES[2],
+        }
+    }
+}
+
+/// Format of the UART line
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UartConfig {
+    /// Baud rate, detected from the signal if None
+    ///
+    pub baud: Option<f64>,
+
+    /// Number of data bits, 5 to 9
+    ///
+    pub data_bits: u8,
+
+    ///
+    ///
+    pub parity: UartParity,
+
+    /// Number of stop bits, 1, 1.5 or 2
+    ///
+    pub stop_bits: f64,
+
+    /// True if the line idles low (e.g. RS-232 levels)
+    ///
+    pub inverted: bool,
+}
+
+impl Default for UartConfig {
+    fn default() -> Self {
+        Self {
+            baud: None,
+            data_bits: 8,
+            parity: UartParity::None,
+            stop_bits: 1.0,
+            inverted: false,
+        }
+    }
+}
+
+/// Character decoded on the line
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UartFrame {
+    /// Time of the start bit edge, in seconds
+    ///
+    pub time: f64,
+
+    /// Data bits, LSB first on the line
+    ///
+    pub data: u16,
+
+    /// True if the parity bit does not match the data
+    ///
+    pub parity_error: bool,
+
+    /// True if the stop bit is not at the idle level
+    ///
+    pub framing_error: bool,
+}
+
+/// Result of a decoding
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct UartDecoding {
+    /// Baud rate used, configured or detected
+    ///
+    pub baud: f64,
+
+    ///
+    ///
+    pub frames: Vec<UartFrame>,
+}
+
+impl UartDecoding {
+    /// JSON representation published on the attributes
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "baud": self.baud,
+            "frames": self.frames.iter().map(|f| json!({
+                "time": f.time,
+                "data": f.data,
+                "parity_error": f.parity_error,
+                "framing_error": f.framing_error,
+            })).collect::<Vec<serde_json::Value>>(),
+        })
+    }
+}
+
+/// Estimate the baud rate from the durations between the edges
+///
+/// The shortest run is taken as a first estimate of the bit time, each run is
+/// then counted as a whole number of bits and the bit time is the total
+/// duration over the total number of bits. The result snaps to a standard rate
+/// when it is close to one.
+///
+pub fn detect_baud(signal: &LogicSignal) -> Option<f64> {
+    let edges = signal.edges();
+    let runs: Vec<f64> = edges.windows(2).map(|w| (w[1] - w[0]) as f64).collect();
+    let shortest = runs.iter().cloned().fold(f64::INFINITY, f64::min);
+    if !shortest.is_finite() {
+        return None;
+    }
+
+    let (mut samples, mut bits) = (0.0, 0.0);
+    for run in runs.iter() {
+        let count = (run / shortest).round();
+        if count <= BAUD_DETECTION_MAX_BITS {
+            samples += run;
+            bits += count;
+        }
+    }
+    let baud = bits / (samples * signal.x_increment);
+
+    Some(
+        STANDARD_BAUD_RATES
+            .iter()
+            .cloned()
+            .find(|b| ((baud - b) / b).abs() <= BAUD_SNAP_TOLERANCE)
+            .unwrap_or(baud),
+    )
+}
+
+/// Decode the characters of a UART line
+///
+/// Each start bit edge is validated in the middle of the start bit, the other
+/// bits are sampled in their middle.
+///
+pub fn decode_uart(signal: &LogicSignal, config: &UartConfig) -> Result<UartDecoding, String> {
+    if !(5..=9).contains(&config.data_bits) {
+        return Err(format!(
+            "Invalid data bits {}, expected 5 to 9",
+            config.data_bits
+        ));
+    }
+    let baud = match config.baud {
+        Some(b) if b > 0.0 => b,
+        Some(b) => return Err(format!("Invalid baud rate {}", b)),
+        None => {
+            detect_baud(signal).ok_or("Not enough edges to detect the baud rate".to_string())?
+        }
+    };
+
+    let bit = 1.0 / (baud * signal.x_increment);
+    if bit < 2.0 {
+        return Err(format!(
+            "Sample rate too low: {:.2} samples per bit at {} baud",
+            bit, baud
+        ));
+    }
+    let idle = !config.inverted;
+    let sample = |edge: usize, position: f64| {
+        let index = (edge as f64 + position * bit).round() as usize;
+        (index < signal.len()).then(|| signal.level_at(index) == idle)
+    };
+
+    let parity_bits = if config.parity == UartParity::None {
+        0
+    } else {
+        1
+    };
+    let mut frames = Vec::new();
+    let mut from = 0;
+    while let Some(edge) = signal.next_edge(from, !idle) {
+        //
+        // Start bit, a glitch is ignored
+        match sample(edge, 0.5) {
+            Some(false) => {}
+            Some(true) => {
+                from = edge + 1;
+                continue;
+            }
+            None => break,
+        }
+
+        //
+        // Data, parity and stop bits
+        let mut data: u16 = 0;
+        let mut ones = 0;
+        let mut complete = true;
+        for i in 0..config.data_bits {
+            match sample(edge, 1.5 + i as f64) {
+                Some(true) => {
+                    data |= 1 << i;
+                    ones += 1;
+                }
+                Some(false) => {}
+                None => complete = false,
+            }
+        }
+        let stop_position = 1.5 + (config.data_bits + parity_bits) as f64;
+        let parity_error = match config.parity {
+            UartParity::None => false,
+            parity => match sample(edge, stop_position - 1.0) {
+                Some(p) => {
+                    let ones = ones + p as u32;
+                    (parity == UartParity::Even) != (ones % 2 == 0)
+                }
+                None => {
+                    complete = false;
+                    false
+                }
+            },
+        };
+        let framing_error = match sample(edge, stop_position) {
+            Some(level) => !level,
+            None => false,
+        };
+        if !complete || sample(edge, stop_position).is_none() {
+            break;
+        }
+
+        frames.push(UartFrame {
+            time: signal.time_at(edge),
+            data,
+            parity_error,
+            framing_error,
+        });
+
+        //
+        // The next start bit follows the middle of the stop bit
+        from = (edge as f64 + stop_position * bit).round() as usize;
+    }
+
+    Ok(UartDecoding { baud, frames })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::Threshold;
+    use crate::waveform::Waveform;
+
+    /// Sample rate of the synthetic lines
+    const SAMPLE_RATE: f64 = 10e6;
+
+    /// Synthetic line driven bit by bit, 3.3V logic with a bit of noise
+    struct Line {
+        baud: f64,
+        levels: Vec<bool>,
+        /// Time of the next bit, in samples, kept fractional for the odd bit times
+        time: f64,
+    }
+
+    impl Line {
+        fn new(baud: f64) -> Self {
+            let mut line = Self {
+                baud,
+                levels: Vec::new(),
+                time: 0.0,
+            };
+            line.hold(true, 3.0);
+            line
+        }
+
+        fn hold(&mut self, level: bool, bits: f64) {
+            self.time += bits * SAMPLE_RATE / self.baud;
+            while (self.levels.len() as f64) < self.time.round() {
+                self.levels.push(level);
+            }
+        }
+
+        /// Frame of 8 data bits, the parity bit and the stop level are given
+        fn frame(&mut self, data: u8, parity: Option<bool>, stop: bool) {
+            self.hold(false, 1.0);
+            for i in 0..8 {
+                self.hold(data >> i & 1 == 1, 1.0);
+            }
+            if let Some(p) = parity {
+                self.hold(p, 1.0);
+            }
+            self.hold(stop, 1.0);
+            self.hold(true, 1.5);
+        }
+
+        fn signal(&self, inverted: bool) -> LogicSignal {
+            let samples: Vec<f64> = self
+                .levels
+                .iter()
+                .enumerate()
+                .map(|(i, l)| {
+                    let noise = if i % 2 == 0 { 0.05 } else { -0.05 };
+                    if *l != inverted {
+                        3.3 + noise
+                    } else {
+                        noise
+                    }
+                })
+                .collect();
+            let waveform = Waveform::new(samples, 0.0, 1.0 / SAMPLE_RATE, "V");
+            LogicSignal::from_waveform(&waveform, Threshold::new(1.65, 0.5))
+        }
+    }
+
+    fn even(data: u8) -> bool {
+        data.count_ones() % 2 == 1
+    }
+
+    #[test]
+    fn decode_frames() {
+        let mut line = Line::new(115200.0);
+        for data in b"Hi!" {
+            line.frame(*data, None, true);
+        }
+        let config = UartConfig {
+            baud: Some(115200.0),
+            ..Default::default()
+        };
+        let decoding = decode_uart(&line.signal(false), &config).unwrap();
+        let data: Vec<u16> = decoding.frames.iter().map(|f| f.data).collect();
+        assert_eq!(data, vec![b'H' as u16, b'i' as u16, b'!' as u16]);
+        assert!(decoding
+            .frames
+            .iter()
+            .all(|f| !f.parity_error && !f.framing_error));
+        assert!((decoding.frames[0].time - 3.0 / 115200.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn check_parity() {
+        let mut line = Line::new(9600.0);
+        line.frame(0x31, Some(even(0x31)), true);
+        line.frame(0x32, Some(!even(0x32)), true);
+        let mut config = UartConfig {
+            baud: Some(9600.0),
+            parity: UartParity::Even,
+            ..Default::default()
+        };
+        let decoding = decode_uart(&line.signal(false), &config).unwrap();
+        let errors: Vec<bool> = decoding.frames.iter().map(|f| f.parity_error).collect();
+        assert_eq!(errors, vec![false, true]);
+
+        //
+        // Both frames read as odd parity
+        config.parity = UartParity::Odd;
+        let decoding = decode_uart(&line.signal(false), &config).unwrap();
+        let errors: Vec<bool> = decoding.frames.iter().map(|f| f.parity_error).collect();
+        assert_eq!(errors, vec![true, false]);
+    }
+
+    #[test]
+    fn report_framing_error() {
+        let mut line = Line::new(19200.0);
+        line.frame(0x55, None, false);
+        line.frame(0xAA, None, true);
+        let config = UartConfig {
+            baud: Some(19200.0),
+            ..Default::default()
+        };
+        let decoding = decode_uart(&line.signal(false), &config).unwrap();
+        assert_eq!(decoding.frames.len(), 2);
+        assert!(decoding.frames[0].framing_error);
+        assert_eq!(decoding.frames[1].data, 0xAA);
+        assert!(!decoding.frames[1].framing_error);
+    }
+
+    #[test]
+    fn decode_inverted_line() {
+        let mut line = Line::new(57600.0);
+        line.frame(0xC3, None, true);
+        let config = UartConfig {
+            baud: Some(57600.0),
+            inverted: true,
+            ..Default::default()
+        };
+        let decoding = decode_uart(&line.signal(true), &config).unwrap();
+        assert_eq!(decoding.frames.len(), 1);
+        assert_eq!(decoding.frames[0].data, 0xC3);
+    }
+
+    #[test]
+    fn detect_standard_rates() {
+        for baud in [9600.0, 115200.0, 460800.0] {
+            let mut line = Line::new(baud);
+            for data in [0x55, 0x0F, 0xA3] {
+                line.frame(data, None, true);
+            }
+            let signal = line.signal(false);
+            assert_eq!(detect_baud(&signal), Some(baud));
+            let decoding = decode_uart(&signal, &UartConfig::default()).unwrap();
+            assert_eq!(decoding.baud, baud);
+            assert_eq!(decoding.frames.len(), 3);
+        }
+    }
+
+    #[test]
+    fn detect_custom_rate() {
+        let mut line = Line::new(250_000.0);
+        line.frame(0x55, None, true);
+        let baud = detect_baud(&line.signal(false)).unwrap();
+        assert!((baud - 250_000.0).abs() < 2_500.0);
+        assert_eq!(detect_baud(&Line::new(9600.0).signal(false)), None);
+    }
+
+    #[test]
+    fn reject_invalid_settings() {
+        let signal = Line::new(9600.0).signal(false);
+        let config = UartConfig {
+            data_bits: 10,
+            ..Default::default()
+        };
+        assert!(decode_uart(&signal, &config).is_err());
+        let config = UartConfig {
+            baud: Some(6e6),
+            ..Default::default()
+        };
+        assert!(decode_uart(&signal, &config).is_err());
+    }
+}
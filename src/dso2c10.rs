@@ -1,17 +1,65 @@
 mod device;
 
-mod scpi_boolean;
-pub use scpi_boolean::ScpiBoolean;
+mod arbitrary_waveform;
+pub use arbitrary_waveform::ArbitraryWaveform;
+
+mod decode_table;
+pub use decode_table::DecodeTable;
+
+mod identity;
+pub use identity::Identity;
+
+mod memory_readout;
+pub use memory_readout::{MemoryReadout, ReadoutProgress, MEMORY_CHUNK_POINTS};
+
+mod profile;
+pub use profile::BandwidthLimit;
+pub use profile::Profile;
+
+mod roll_series;
+pub use roll_series::{RollChunk, RollSeries, MAX_SERIES_POINTS};
+
+mod screen;
+pub use screen::Screen;
+
+mod segment;
+pub use segment::{Segment, SegmentRing, MAX_SEGMENTS};
+
+mod setup_store;
+pub use setup_store::SetupStore;
+
+mod waveform_preamble;
+pub use waveform_preamble::WaveformPreamble;
 
 use device::Device;
 use panduza_platform_core::ProductionOrder;
 use panduza_platform_core::Scanner;
 use panduza_platform_core::{DriverOperations, Producer};
 
-#[derive(Default)]
-pub struct Package {}
+/// Producer of the Hantek SCPI oscilloscopes (DSO2000 and DSO4000C families)
+///
+/// The same driver serves every model, the model only changes the description
+/// and the fallback profile when the `*IDN?` reply cannot be parsed.
+///
+pub struct Package {
+    /// Model name (e.g. DSO2C10, DSO2D15)
+    ///
+    model: String,
+}
+
+impl Default for Package {
+    fn default() -> Self {
+        Self::new("DSO2C10")
+    }
+}
 
 impl Package {
+    pub fn new<A: Into<String>>(model: A) -> Self {
+        Self {
+            model: model.into(),
+        }
+    }
+
     pub fn boxed(self) -> Box<Self> {
         Box::new(self)
     }
@@ -23,11 +71,18 @@ impl Producer for Package {
     }
 
     fn model(&self) -> String {
-        "DSO2C10".to_string()
+        self.model.clone()
     }
 
     fn description(&self) -> String {
-        "Oscilloscope".to_string()
+        let profile = Profile::from_model(&self.model);
+        match profile.has_generator {
+            true => format!(
+                "{} channels oscilloscope with function generator",
+                profile.channel_count
+            ),
+            false => format!("{} channels oscilloscope", profile.channel_count),
+        }
     }
 
     fn props(&self) -> panduza_platform_core::Props {
@@ -37,7 +92,7 @@ impl Producer for Package {
     }
 
     fn produce(&self) -> Result<Box<dyn DriverOperations>, panduza_platform_core::Error> {
-        return Ok(Box::new(Device::default()));
+        return Ok(Box::new(Device::new(self.model.clone())));
     }
 }
 
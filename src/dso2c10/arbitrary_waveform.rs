@@ -0,0 +1,97 @@
+/// Number of points of an arbitrary waveform in the generator memory
+///
+pub const ARBITRARY_LENGTH: usize = 4096;
+
+/// Resolution of the generator DAC
+///
+pub const ARBITRARY_RESOLUTION_BITS: u32 = 12;
+
+/// Arbitrary waveform ready to be uploaded into the generator
+///
+/// Built from normalized samples in [-1.0, 1.0], resampled to the generator length
+/// and quantized to the DAC resolution.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbitraryWaveform {
+    /// DAC codes, ARBITRARY_LENGTH of them
+    ///
+    codes: Vec<u16>,
+}
+
+impl ArbitraryWaveform {
+    /// Build the waveform from normalized samples
+    ///
+    /// The samples describe one period, they are resampled with a linear
+    /// interpolation that wraps from the last sample to the first one.
+    ///
+    pub fn from_samples(samples: &[f64]) -> Result<Self, String> {
+        if samples.len() < 2 {
+            return Err(format!(
+                "At least 2 samples are required, {} given",
+                samples.len()
+            ));
+        }
+        if let Some((i, s)) = samples
+            .iter()
+            .enumerate()
+            .find(|(_, s)| !s.is_finite() || **s < -1.0 || **s > 1.0)
+        {
+            return Err(format!(
+                "Sample {} out of range: {} is not in [-1.0, 1.0]",
+                i, s
+            ));
+        }
+
+        let n = samples.len();
+        let full_scale = ((1u32 << ARBITRARY_RESOLUTION_BITS) - 1) as f64;
+        let codes = (0..ARBITRARY_LENGTH)
+            .map(|i| {
+                let position = i as f64 * n as f64 / ARBITRARY_LENGTH as f64;
+                let index = position.floor() as usize;
+                let fraction = position - index as f64;
+                let a = samples[index % n];
+                let b = samples[(index + 1) % n];
+                let v = a + (b - a) * fraction;
+                ((v + 1.0) / 2.0 * full_scale).round() as u16
+            })
+            .collect();
+
+        Ok(Self { codes })
+    }
+
+    /// Build the waveform from little endian f32 samples
+    ///
+    pub fn from_f32_le(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() % 4 != 0 {
+            return Err(format!(
+                "Invalid payload of {} bytes, expected little endian f32 samples",
+                bytes.len()
+            ));
+        }
+        let samples: Vec<f64> = bytes
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f64)
+            .collect();
+        Self::from_samples(&samples)
+    }
+
+    /// Build the waveform from a JSON array of numbers
+    ///
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, String> {
+        let array = value
+            .as_array()
+            .ok_or("Samples must be an array of numbers".to_string())?;
+        let samples = array
+            .iter()
+            .enumerate()
+            .map(|(i, v)| v.as_f64().ok_or(format!("Sample {} is not a number", i)))
+            .collect::<Result<Vec<f64>, String>>()?;
+        Self::from_samples(&samples)
+    }
+
+    /// DAC codes as 16 bits little endian
+    ///
+    pub fn to_dac16(&self) -> Vec<u8> {
+        self.codes.iter().flat_map(|c| c.to_le_bytes()).collect()
+    }
+}
@@ -0,0 +1,103 @@
+use serde_json::json;
+
+/// Columns of the event table published as numbers, the others are kept as text
+///
+/// Data cells such as "10" or "0010" are bus values, converting them would
+/// lose their radix and leading zeros.
+///
+const NUMERIC_COLUMNS: [&str; 2] = ["Index", "Time"];
+
+/// Event table of the bus decoder, reply of `DECode:LIST?`
+///
+/// The table is comma separated text, the first line names the columns:
+///
+/// `Index,Time,Data,Error`
+/// `1,-2.300000E-04,0x4A,`
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecodeTable {
+    /// Names of the columns
+    ///
+    pub columns: Vec<String>,
+
+    /// Cells of each decoded frame, one per column
+    ///
+    pub frames: Vec<Vec<String>>,
+}
+
+impl DecodeTable {
+    /// Parse the event table
+    ///
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text
+            .split(|c| c == '\n' || c == '\r')
+            .map(|l| l.trim_matches(|c: char| c.is_whitespace() || c == '\0'))
+            .filter(|l| !l.is_empty());
+
+        let columns: Vec<String> = match lines.next() {
+            Some(header) => header.split(',').map(|c| c.trim().to_string()).collect(),
+            None => return Ok(Self::default()),
+        };
+        let frames = lines
+            .enumerate()
+            .map(|(i, line)| {
+                let mut cells: Vec<String> =
+                    line.split(',').map(|c| c.trim().to_string()).collect();
+                if cells.len() > columns.len() {
+                    return Err(format!(
+                        "Frame {} has {} cells for {} columns",
+                        i + 1,
+                        cells.len(),
+                        columns.len()
+                    ));
+                }
+                cells.resize(columns.len(), String::new());
+                Ok(cells)
+            })
+            .collect::<Result<Vec<Vec<String>>, String>>()?;
+
+        Ok(Self { columns, frames })
+    }
+
+    /// JSON representation published on the attributes
+    ///
+    /// Each frame is an object keyed by the column names, the cells of the
+    /// Index and Time columns are numbers, empty cells are null and the others
+    /// (e.g. bus data) are kept as strings.
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        let frames: Vec<serde_json::Value> = self
+            .frames
+            .iter()
+            .map(|cells| {
+                let frame: serde_json::Map<String, serde_json::Value> = self
+                    .columns
+                    .iter()
+                    .zip(cells.iter())
+                    .map(|(column, cell)| {
+                        let numeric = NUMERIC_COLUMNS
+                            .iter()
+                            .any(|c| c.eq_ignore_ascii_case(column));
+                        let value = if cell.is_empty() {
+                            serde_json::Value::Null
+                        } else if !numeric {
+                            json!(cell)
+                        } else if let Ok(n) = cell.parse::<i64>() {
+                            json!(n)
+                        } else if let Ok(n) = cell.parse::<f64>() {
+                            json!(n)
+                        } else {
+                            json!(cell)
+                        };
+                        (column.clone(), value)
+                    })
+                    .collect();
+                serde_json::Value::Object(frame)
+            })
+            .collect();
+        json!({
+            "columns": self.columns,
+            "frames": frames,
+        })
+    }
+}
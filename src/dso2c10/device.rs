@@ -1,24 +1,52 @@
 mod channel;
+mod cursor;
+mod decode;
+mod generator;
 mod interface;
+mod math;
+mod memory;
+mod record;
+mod reference;
+mod roll;
+mod setup;
+mod timebase;
+mod trigger;
 
-use interface::DSO2C10Interface;
+use interface::{DSO2C10Interface, WaveformIndex};
 
+use crate::bus;
+use crate::dso2c10::{Identity, Profile, SetupStore};
+use crate::export;
+use crate::stream;
 use async_trait::async_trait;
 use panduza_platform_core::connector::usb::tmc::Driver as UsbTmcDriver;
 use panduza_platform_core::connector::usb::Settings as UsbSettings;
-use panduza_platform_core::{log_debug, Container, DriverOperations, Error, Instance};
+use panduza_platform_core::std::attribute::idn::IdnReader;
+use panduza_platform_core::{
+    log_debug, log_info, log_warn, Container, DriverOperations, Error, Instance,
+};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::time::sleep;
 
-#[derive(Default)]
 ///
-/// Device to control PicoHA SSB Board
+/// Device to control the Hantek DSO2000 oscilloscopes
 ///
-pub struct Device {}
+pub struct Device {
+    /// Model requested by the producer
+    ///
+    model: String,
+}
 
-impl Device {}
+impl Device {
+    ///
+    ///
+    pub fn new(model: String) -> Self {
+        Self { model }
+    }
+}
 
 #[async_trait]
 impl DriverOperations for Device {
@@ -54,11 +82,89 @@ impl DriverOperations for Device {
         panduza_platform_core::std::attribute::idn::mount(instance.clone(), interface.clone())
             .await?;
 
+        //
+        // Select the capabilities from the identity of the instrument
+        let idn = interface.lock().await.read_idn().await?;
+        let profile = match Identity::parse(&idn) {
+            Ok(identity) => {
+                log_info!(logger, "Identity {:?}", identity);
+                Profile::from_identity(&identity)
+            }
+            Err(e) => {
+                log_warn!(logger, "{}, fallback on {} profile", e, self.model);
+                Profile::from_model(&self.model)
+            }
+        };
+        log_info!(logger, "Profile {:?}", profile);
+
         let class_channels = instance.create_class("channel").finish().await;
-        for i in 1..=2 {
-            channel::mount(class_channels.clone(), i, interface.clone()).await?;
+        for i in 1..=profile.channel_count {
+            channel::mount(class_channels.clone(), i, interface.clone(), &profile).await?;
         }
 
+        timebase::mount(instance.clone(), interface.clone()).await?;
+
+        trigger::mount(instance.clone(), interface.clone(), &profile).await?;
+
+        math::mount(instance.clone(), interface.clone(), &profile).await?;
+
+        cursor::mount(instance.clone(), interface.clone(), &profile).await?;
+
+        reference::mount(instance.clone(), interface.clone(), &profile).await?;
+
+        memory::mount(instance.clone(), interface.clone(), &profile).await?;
+
+        record::mount(instance.clone(), interface.clone(), &profile).await?;
+
+        roll::mount(instance.clone(), interface.clone(), &profile).await?;
+
+        if !profile.decoders.is_empty() {
+            decode::mount(instance.clone(), interface.clone(), &profile).await?;
+        }
+
+        //
+        // Host decoders, exports and the stream read the analog channels
+        let sources: Vec<(&'static str, usize)> = profile
+            .channel_sources()
+            .into_iter()
+            .zip([
+                WaveformIndex::Channel1,
+                WaveformIndex::Channel2,
+                WaveformIndex::Channel3,
+                WaveformIndex::Channel4,
+            ])
+            .map(|(name, index)| (name, index as usize))
+            .collect();
+        bus::mount(instance.clone(), interface.clone(), sources.clone()).await?;
+
+        //
+        // Exports are written in 'export_directory' if given
+        let export_directory = match settings.get("export_directory").and_then(|d| d.as_str()) {
+            Some(directory) => PathBuf::from(directory),
+            None => export::default_directory(),
+        };
+        export::mount(
+            instance.clone(),
+            interface.clone(),
+            sources.clone(),
+            export_directory,
+        )
+        .await?;
+
+        stream::mount(instance.clone(), interface.clone(), sources).await?;
+
+        if profile.has_generator {
+            generator::mount(instance.clone(), interface.clone()).await?;
+        }
+
+        //
+        // Named setups are stored in 'setups_directory' if given
+        let store = match settings.get("setups_directory").and_then(|d| d.as_str()) {
+            Some(directory) => SetupStore::new(directory),
+            None => SetupStore::new(SetupStore::default_directory()),
+        };
+        setup::mount(instance.clone(), interface.clone(), &profile, store).await?;
+
         Ok(())
     }
     ///
@@ -9,8 +9,13 @@ use std::sync::Arc;
 
 use crate::dso2c10::device::interface::BooleanIndex;
 use crate::dso2c10::device::interface::StringIndex;
+use crate::dso2c10::device::interface::WaveformIndex;
+use crate::dso2c10::device::interface::CHANNEL_COUPLING_CHOICES;
 
 use super::interface::DSO2C10Interface;
+use crate::dso2c10::{BandwidthLimit, Profile};
+use crate::spectrum as std_att_spectrum;
+use crate::waveform as std_att_waveform;
 
 ///
 ///
@@ -18,6 +23,7 @@ pub async fn mount<C: Container + 'static>(
     mut parent: C,
     channel_id: usize,
     interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
 ) -> Result<(), Error> {
     let class_chan = parent
         .create_class(format!("{}", channel_id))
@@ -28,23 +34,51 @@ pub async fn mount<C: Container + 'static>(
     log_debug_mount_start!(logger);
 
     //
-    //
-    std_att_boolean::mount(
-        class_chan.clone(),
-        interface.clone(),
-        match channel_id {
-            1 => BooleanIndex::Channel1BwLimit,
-            2 => BooleanIndex::Channel2BwLimit,
-            _ => BooleanIndex::Channel1BwLimit,
-        } as usize,
-        "bandwidth_limit",
-        "* OFF: Turn off the 20MHz bandwidth limit, and the high-frequency components
+    // Depends on the limits provided by the model
+    match &profile.bandwidth_limit {
+        BandwidthLimit::None => {}
+        BandwidthLimit::Boolean => {
+            std_att_boolean::mount(
+                class_chan.clone(),
+                interface.clone(),
+                match channel_id {
+                    1 => BooleanIndex::Channel1BwLimit,
+                    2 => BooleanIndex::Channel2BwLimit,
+                    3 => BooleanIndex::Channel3BwLimit,
+                    4 => BooleanIndex::Channel4BwLimit,
+                    _ => BooleanIndex::Channel1BwLimit,
+                } as usize,
+                "bandwidth_limit",
+                "* OFF: Turn off the 20MHz bandwidth limit, and the high-frequency components
 contained in the measured signal can pass.
 * ON: Turn on the bandwidth limitation, and the high-frequency components contained in
 the signal under test are attenuated.
 ",
-    )
-    .await?;
+            )
+            .await?;
+        }
+        BandwidthLimit::Choices(choices) => {
+            std_att_enum::mount(
+                class_chan.clone(),
+                interface.clone(),
+                match channel_id {
+                    1 => StringIndex::Channel1BwLimit,
+                    2 => StringIndex::Channel2BwLimit,
+                    3 => StringIndex::Channel3BwLimit,
+                    4 => StringIndex::Channel4BwLimit,
+                    _ => StringIndex::Channel1BwLimit,
+                } as usize,
+                "bandwidth_limit",
+                "* OFF: No bandwidth limit, and the high-frequency components contained in the
+measured signal can pass.
+* 20M, 100M: Limit the bandwidth to the given frequency, the high-frequency components
+contained in the signal under test are attenuated.
+",
+                choices.clone(),
+            )
+            .await?;
+        }
+    }
 
     //
     //
@@ -54,6 +88,8 @@ the signal under test are attenuated.
         match channel_id {
             1 => BooleanIndex::Channel1Display,
             2 => BooleanIndex::Channel2Display,
+            3 => BooleanIndex::Channel3Display,
+            4 => BooleanIndex::Channel4Display,
             _ => BooleanIndex::Channel1Display,
         } as usize,
         "display",
@@ -69,6 +105,8 @@ the signal under test are attenuated.
         match channel_id {
             1 => BooleanIndex::Channel1Invert,
             2 => BooleanIndex::Channel2Invert,
+            3 => BooleanIndex::Channel3Invert,
+            4 => BooleanIndex::Channel4Invert,
             _ => BooleanIndex::Channel1Invert,
         } as usize,
         "invert",
@@ -84,6 +122,8 @@ the signal under test are attenuated.
         match channel_id {
             1 => BooleanIndex::Channel1Vernier,
             2 => BooleanIndex::Channel2Vernier,
+            3 => BooleanIndex::Channel3Vernier,
+            4 => BooleanIndex::Channel4Vernier,
             _ => BooleanIndex::Channel1Vernier,
         } as usize,
         "vertical_fine_tuning",
@@ -102,7 +142,9 @@ the signal under test are attenuated.
         interface.clone(),
         match channel_id {
             1 => StringIndex::Channel1Coupling,
-            2 => StringIndex::Channel1Coupling,
+            2 => StringIndex::Channel2Coupling,
+            3 => StringIndex::Channel3Coupling,
+            4 => StringIndex::Channel4Coupling,
             _ => StringIndex::Channel1Coupling,
         } as usize,
         "coupling",
@@ -112,7 +154,7 @@ Oscilloscope coupling is the process of connecting an oscilloscope to a signal s
     * DC: Both the DC and AC components of the signal under test can pass.
     * GND: Both the DC and AC components of the signal under test are blocked
         ",
-        vec!["DC", "AC", "GND"],
+        CHANNEL_COUPLING_CHOICES.to_vec(),
     )
     .await?;
 
@@ -121,7 +163,9 @@ Oscilloscope coupling is the process of connecting an oscilloscope to a signal s
         interface.clone(),
         match channel_id {
             1 => StringIndex::Channel1Scale,
-            2 => StringIndex::Channel1Scale,
+            2 => StringIndex::Channel2Scale,
+            3 => StringIndex::Channel3Scale,
+            4 => StringIndex::Channel4Scale,
             _ => StringIndex::Channel1Scale,
         } as usize,
         "scale",
@@ -138,7 +182,9 @@ Oscilloscope coupling is the process of connecting an oscilloscope to a signal s
         interface.clone(),
         match channel_id {
             1 => StringIndex::Channel1Probe,
-            2 => StringIndex::Channel1Probe,
+            2 => StringIndex::Channel2Probe,
+            3 => StringIndex::Channel3Probe,
+            4 => StringIndex::Channel4Probe,
             _ => StringIndex::Channel1Probe,
         } as usize,
         "probe",
@@ -147,6 +193,35 @@ Oscilloscope coupling is the process of connecting an oscilloscope to a signal s
     )
     .await?;
 
+    std_att_waveform::mount(
+        class_chan.clone(),
+        interface.clone(),
+        match channel_id {
+            1 => WaveformIndex::Channel1,
+            2 => WaveformIndex::Channel2,
+            3 => WaveformIndex::Channel3,
+            4 => WaveformIndex::Channel4,
+            _ => WaveformIndex::Channel1,
+        } as usize,
+        "waveform",
+        "Waveform displayed on the channel, write any value to capture it",
+    )
+    .await?;
+
+    std_att_spectrum::mount(
+        class_chan.clone(),
+        interface.clone(),
+        match channel_id {
+            1 => WaveformIndex::Channel1,
+            2 => WaveformIndex::Channel2,
+            3 => WaveformIndex::Channel3,
+            4 => WaveformIndex::Channel4,
+            _ => WaveformIndex::Channel1,
+        } as usize,
+        "spectrum",
+    )
+    .await?;
+
     //
     //
     log_debug_mount_end!(logger);
@@ -0,0 +1,243 @@
+use async_trait::async_trait;
+use strum_macros::FromRepr;
+use tokio::sync::Mutex;
+
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::{
+    log_debug_mount_end, log_debug_mount_start, log_warn, Container, Error, Logger,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::dso2c10::device::interface::FloatIndex;
+use crate::dso2c10::device::interface::StringIndex;
+use crate::dso2c10::device::interface::CURSOR_MODE_CHOICES;
+use crate::dso2c10::device::interface::CURSOR_TYPE_CHOICES;
+use crate::dso2c10::Profile;
+use crate::si as std_att_si;
+use crate::si::{FloatAccessorModel, SiFormat};
+
+use super::interface::DSO2C10Interface;
+
+/// Refresh period of the cursor deltas
+///
+const DELTA_PERIOD: Duration = Duration::from_secs(1);
+
+/// Last deltas read from the scope, shared by the delta attributes
+///
+#[derive(Default)]
+pub struct CursorDeltas {
+    /// Time between the cursors, B - A
+    ///
+    time: f64,
+
+    /// Voltage between the cursors, B - A
+    ///
+    voltage: f64,
+}
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum FloatDeltaIndex {
+    //
+    Time,
+    InverseTime,
+    Voltage,
+}
+
+#[async_trait]
+///
+///
+impl FloatAccessorModel for CursorDeltas {
+    ///
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error> {
+        let idx = FloatDeltaIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        Ok(match idx {
+            FloatDeltaIndex::Time => self.time,
+            FloatDeltaIndex::InverseTime => match self.time != 0.0 {
+                true => 1.0 / self.time.abs(),
+                false => 0.0,
+            },
+            FloatDeltaIndex::Voltage => self.voltage,
+        })
+    }
+
+    ///
+    ///
+    async fn set_float_at(&mut self, _index: usize, _value: f64) -> Result<(), Error> {
+        Err(Error::InvalidArgument("Read only value".to_string()))
+    }
+}
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+) -> Result<(), Error> {
+    let mut class_cursor = parent.create_class("cursor").finish().await;
+
+    let logger = class_cursor.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    //
+    std_att_enum::mount(
+        class_cursor.clone(),
+        interface.clone(),
+        StringIndex::CursorMode as usize,
+        "mode",
+        "
+    * OFF: cursors hidden
+    * MANual: cursors placed with the position attributes
+    * TRACk: cursors follow the waveform of the source
+        ",
+        CURSOR_MODE_CHOICES.to_vec(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_cursor.clone(),
+        interface.clone(),
+        StringIndex::CursorType as usize,
+        "type",
+        "
+    * X: vertical lines, measure time
+    * Y: horizontal lines, measure voltage
+        ",
+        CURSOR_TYPE_CHOICES.to_vec(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_cursor.clone(),
+        interface.clone(),
+        StringIndex::CursorSource as usize,
+        "source",
+        "Channel measured by the cursors, its scale and offset convert the voltages",
+        profile.channel_sources(),
+    )
+    .await?;
+
+    //
+    //
+    for (index, name, info) in [
+        (FloatIndex::CursorATime, "a_time", "Time of the cursor A"),
+        (FloatIndex::CursorBTime, "b_time", "Time of the cursor B"),
+    ] {
+        std_att_si::mount(
+            class_cursor.clone(),
+            interface.clone(),
+            index as usize,
+            name,
+            info,
+            SiFormat::new("s", -10000.0, 10000.0, 9),
+        )
+        .await?;
+    }
+
+    //
+    //
+    for (index, name, info) in [
+        (
+            FloatIndex::CursorAVoltage,
+            "a_voltage",
+            "Voltage of the cursor A",
+        ),
+        (
+            FloatIndex::CursorBVoltage,
+            "b_voltage",
+            "Voltage of the cursor B",
+        ),
+    ] {
+        std_att_si::mount(
+            class_cursor.clone(),
+            interface.clone(),
+            index as usize,
+            name,
+            info,
+            SiFormat::new("V", -10000.0, 10000.0, 6),
+        )
+        .await?;
+    }
+
+    //
+    // The deltas are read once per period for the 3 attributes
+    let deltas = Arc::new(Mutex::new(CursorDeltas::default()));
+    class_cursor
+        .spawn(
+            "refresh => cursor deltas",
+            refresh_deltas(interface.clone(), deltas.clone(), logger.clone()),
+        )
+        .await;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_cursor.clone(),
+        deltas.clone(),
+        FloatDeltaIndex::Time as usize,
+        "delta_time",
+        "Time between the cursors, B - A, 0 while the cursors are OFF",
+        SiFormat::new("s", -10000.0, 10000.0, 9),
+        DELTA_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_cursor.clone(),
+        deltas.clone(),
+        FloatDeltaIndex::InverseTime as usize,
+        "inverse_delta_time",
+        "Frequency of a period between the cursors, 1/|B - A|, 0 when they overlap",
+        SiFormat::new("Hz", 0.0, 10_000_000_000.0, 3),
+        DELTA_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_cursor.clone(),
+        deltas,
+        FloatDeltaIndex::Voltage as usize,
+        "delta_voltage",
+        "Voltage between the cursors, B - A, 0 while the cursors are OFF",
+        SiFormat::new("V", -100000.0, 100000.0, 6),
+        DELTA_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+/// Read the deltas periodically, only the mode is queried while the cursors are OFF
+///
+async fn refresh_deltas(
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    deltas: Arc<Mutex<CursorDeltas>>,
+    logger: Logger,
+) -> Result<(), Error> {
+    loop {
+        tokio::time::sleep(DELTA_PERIOD).await;
+        let read = interface.lock().await.get_cursor_deltas().await;
+        match read {
+            Ok(read) => {
+                let (time, voltage) = read.unwrap_or((0.0, 0.0));
+                *deltas.lock().await = CursorDeltas { time, voltage };
+            }
+            Err(e) => log_warn!(logger, "cursor deltas refresh failed '{:?}'", e),
+        }
+    }
+}
@@ -0,0 +1,439 @@
+use tokio::sync::Mutex;
+
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer,
+};
+use std::sync::Arc;
+
+use crate::dso2c10::device::interface::BooleanIndex;
+use crate::dso2c10::device::interface::FloatIndex;
+use crate::dso2c10::device::interface::StringIndex;
+use crate::dso2c10::device::interface::DECODE_CAN_SIGNAL_CHOICES;
+use crate::dso2c10::device::interface::DECODE_I2C_ADDRESS_WIDTH_CHOICES;
+use crate::dso2c10::device::interface::DECODE_SPI_BIT_ORDER_CHOICES;
+use crate::dso2c10::device::interface::DECODE_SPI_MODE_CHOICES;
+use crate::dso2c10::device::interface::DECODE_UART_DATA_BITS_CHOICES;
+use crate::dso2c10::device::interface::DECODE_UART_PARITY_CHOICES;
+use crate::dso2c10::device::interface::DECODE_UART_STOP_BITS_CHOICES;
+use crate::dso2c10::Profile;
+use crate::si as std_att_si;
+use crate::si::SiFormat;
+
+use super::interface::DSO2C10Interface;
+
+/// Format of the decoder thresholds
+///
+const THRESHOLD_FORMAT: SiFormat = SiFormat::new("V", -400.0, 400.0, 3);
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+) -> Result<(), Error> {
+    let mut class_decode = parent.create_class("decode").finish().await;
+
+    let logger = class_decode.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    //
+    std_att_enum::mount(
+        class_decode.clone(),
+        interface.clone(),
+        StringIndex::DecodeType as usize,
+        "type",
+        "Bus decoded by the scope, each bus is configured in its own class",
+        profile.decoders.clone(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_boolean::mount(
+        class_decode.clone(),
+        interface.clone(),
+        BooleanIndex::DecodeDisplay as usize,
+        "display",
+        "Display the decoded bus on the screen",
+    )
+    .await?;
+
+    //
+    //
+    let att_frames = class_decode
+        .create_attribute("frames")
+        .with_rw()
+        .with_info(
+            "Write any value to read the event table of the decoder
+
+    { \"columns\": [\"Index\", \"Time\", \"Data\", \"Error\"],
+      \"frames\": [ { \"Index\": 1, \"Time\": -0.00023, \"Data\": \"0x4A\", \"Error\": null } ] }
+",
+        )
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => decode frames",
+        class_decode,
+        att_frames,
+        on_frames_command(att_frames.clone(), interface.clone())
+    );
+
+    let sources = profile.channel_sources();
+    if profile.has_decoder("UART") {
+        mount_uart(class_decode.clone(), interface.clone(), &sources).await?;
+    }
+    if profile.has_decoder("IIC") {
+        mount_i2c(class_decode.clone(), interface.clone(), &sources).await?;
+    }
+    if profile.has_decoder("SPI") {
+        mount_spi(class_decode.clone(), interface.clone(), &sources).await?;
+    }
+    if profile.has_decoder("CAN") {
+        mount_can(class_decode.clone(), interface.clone(), &sources).await?;
+    }
+    if profile.has_decoder("LIN") {
+        mount_lin(class_decode.clone(), interface.clone(), &sources).await?;
+    }
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+///
+///
+async fn mount_uart<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    sources: &Vec<&'static str>,
+) -> Result<(), Error> {
+    let class_uart = parent.create_class("uart").finish().await;
+
+    std_att_enum::mount(
+        class_uart.clone(),
+        interface.clone(),
+        StringIndex::DecodeUartSource as usize,
+        "source",
+        "Channel of the UART line",
+        sources.clone(),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_uart.clone(),
+        interface.clone(),
+        FloatIndex::DecodeUartThreshold as usize,
+        "threshold",
+        "Logic threshold of the UART line",
+        THRESHOLD_FORMAT,
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_uart.clone(),
+        interface.clone(),
+        FloatIndex::DecodeUartBaud as usize,
+        "baud",
+        "Baud rate of the UART line",
+        SiFormat::new("bps", 50.0, 20_000_000.0, 0),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_uart.clone(),
+        interface.clone(),
+        StringIndex::DecodeUartParity as usize,
+        "parity",
+        "",
+        DECODE_UART_PARITY_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_uart.clone(),
+        interface.clone(),
+        StringIndex::DecodeUartStopBits as usize,
+        "stop_bits",
+        "",
+        DECODE_UART_STOP_BITS_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_uart.clone(),
+        interface.clone(),
+        StringIndex::DecodeUartDataBits as usize,
+        "data_bits",
+        "",
+        DECODE_UART_DATA_BITS_CHOICES.to_vec(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+///
+///
+async fn mount_i2c<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    sources: &Vec<&'static str>,
+) -> Result<(), Error> {
+    let class_i2c = parent.create_class("i2c").finish().await;
+
+    for (source, threshold, line) in [
+        (
+            StringIndex::DecodeI2cSclSource,
+            FloatIndex::DecodeI2cSclThreshold,
+            "scl",
+        ),
+        (
+            StringIndex::DecodeI2cSdaSource,
+            FloatIndex::DecodeI2cSdaThreshold,
+            "sda",
+        ),
+    ] {
+        std_att_enum::mount(
+            class_i2c.clone(),
+            interface.clone(),
+            source as usize,
+            &format!("{}_source", line),
+            &format!("Channel of the {} line", line.to_uppercase()),
+            sources.clone(),
+        )
+        .await?;
+
+        std_att_si::mount(
+            class_i2c.clone(),
+            interface.clone(),
+            threshold as usize,
+            &format!("{}_threshold", line),
+            &format!("Logic threshold of the {} line", line.to_uppercase()),
+            THRESHOLD_FORMAT,
+        )
+        .await?;
+    }
+
+    std_att_enum::mount(
+        class_i2c.clone(),
+        interface.clone(),
+        StringIndex::DecodeI2cAddressWidth as usize,
+        "address_width",
+        "Width of the addresses, in bits",
+        DECODE_I2C_ADDRESS_WIDTH_CHOICES.to_vec(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+///
+///
+async fn mount_spi<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    sources: &Vec<&'static str>,
+) -> Result<(), Error> {
+    let class_spi = parent.create_class("spi").finish().await;
+
+    for (source, threshold, line) in [
+        (
+            StringIndex::DecodeSpiClockSource,
+            FloatIndex::DecodeSpiClockThreshold,
+            "clk",
+        ),
+        (
+            StringIndex::DecodeSpiMosiSource,
+            FloatIndex::DecodeSpiMosiThreshold,
+            "mosi",
+        ),
+        (
+            StringIndex::DecodeSpiMisoSource,
+            FloatIndex::DecodeSpiMisoThreshold,
+            "miso",
+        ),
+        (
+            StringIndex::DecodeSpiCsSource,
+            FloatIndex::DecodeSpiCsThreshold,
+            "cs",
+        ),
+    ] {
+        std_att_enum::mount(
+            class_spi.clone(),
+            interface.clone(),
+            source as usize,
+            &format!("{}_source", line),
+            &format!("Channel of the {} line", line.to_uppercase()),
+            sources.clone(),
+        )
+        .await?;
+
+        std_att_si::mount(
+            class_spi.clone(),
+            interface.clone(),
+            threshold as usize,
+            &format!("{}_threshold", line),
+            &format!("Logic threshold of the {} line", line.to_uppercase()),
+            THRESHOLD_FORMAT,
+        )
+        .await?;
+    }
+
+    std_att_enum::mount(
+        class_spi.clone(),
+        interface.clone(),
+        StringIndex::DecodeSpiCpol as usize,
+        "cpol",
+        "Clock polarity, level of the clock when idle",
+        DECODE_SPI_MODE_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_spi.clone(),
+        interface.clone(),
+        StringIndex::DecodeSpiCpha as usize,
+        "cpha",
+        "Clock phase, 0 samples on the first clock edge and 1 on the second",
+        DECODE_SPI_MODE_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_spi.clone(),
+        interface.clone(),
+        StringIndex::DecodeSpiBitOrder as usize,
+        "bit_order",
+        "",
+        DECODE_SPI_BIT_ORDER_CHOICES.to_vec(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+///
+///
+async fn mount_can<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    sources: &Vec<&'static str>,
+) -> Result<(), Error> {
+    let class_can = parent.create_class("can").finish().await;
+
+    std_att_enum::mount(
+        class_can.clone(),
+        interface.clone(),
+        StringIndex::DecodeCanSource as usize,
+        "source",
+        "Channel of the CAN line",
+        sources.clone(),
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_can.clone(),
+        interface.clone(),
+        StringIndex::DecodeCanSignal as usize,
+        "signal",
+        "
+    * CANH, CANL: one wire of the differential bus
+    * TX, RX: logic side of the transceiver
+        ",
+        DECODE_CAN_SIGNAL_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_can.clone(),
+        interface.clone(),
+        FloatIndex::DecodeCanThreshold as usize,
+        "threshold",
+        "Logic threshold of the CAN line",
+        THRESHOLD_FORMAT,
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_can.clone(),
+        interface.clone(),
+        FloatIndex::DecodeCanBaud as usize,
+        "bit_rate",
+        "Nominal bit rate of the CAN bus",
+        SiFormat::new("bps", 10_000.0, 1_000_000.0, 0),
+    )
+    .await?;
+
+    Ok(())
+}
+
+///
+///
+async fn mount_lin<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    sources: &Vec<&'static str>,
+) -> Result<(), Error> {
+    let class_lin = parent.create_class("lin").finish().await;
+
+    std_att_enum::mount(
+        class_lin.clone(),
+        interface.clone(),
+        StringIndex::DecodeLinSource as usize,
+        "source",
+        "Channel of the LIN line",
+        sources.clone(),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_lin.clone(),
+        interface.clone(),
+        FloatIndex::DecodeLinThreshold as usize,
+        "threshold",
+        "Logic threshold of the LIN line",
+        THRESHOLD_FORMAT,
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_lin.clone(),
+        interface.clone(),
+        FloatIndex::DecodeLinBaud as usize,
+        "baud",
+        "Baud rate of the LIN bus",
+        SiFormat::new("bps", 1_000.0, 20_000.0, 0),
+    )
+    .await?;
+
+    Ok(())
+}
+
+///
+///
+async fn on_frames_command(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Log
+        log_debug!(att.logger(), "decode frames requested '{:?}'", command);
+
+        let table = interface.lock().await.get_decode_frames().await;
+        match table {
+            Ok(t) => att.set(t.to_json()).await?,
+            Err(e) => log_warn!(att.logger(), "decode frames read failed '{:?}'", e),
+        }
+    }
+    Ok(())
+}
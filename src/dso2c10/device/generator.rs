@@ -0,0 +1,108 @@
+mod arbitrary;
+use tokio::sync::Mutex;
+
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::{log_debug_mount_end, log_debug_mount_start, Container, Error};
+use std::sync::Arc;
+
+use crate::dso2c10::device::interface::BooleanIndex;
+use crate::dso2c10::device::interface::FloatIndex;
+use crate::dso2c10::device::interface::StringIndex;
+use crate::dso2c10::device::interface::GENERATOR_WAVEFORM_CHOICES;
+use crate::si as std_att_si;
+use crate::si::SiFormat;
+
+use super::interface::DSO2C10Interface;
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    let class_gen = parent.create_class("generator").finish().await;
+
+    let logger = class_gen.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    //
+    std_att_boolean::mount(
+        class_gen.clone(),
+        interface.clone(),
+        BooleanIndex::GeneratorOutput as usize,
+        "output_enable",
+        "Enable or disable the output of the function generator",
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_gen.clone(),
+        interface.clone(),
+        StringIndex::GeneratorWaveform as usize,
+        "waveform",
+        "Waveform generated by the DDS
+    * ARB1 to ARB4: user arbitrary waveforms, see 'arbitrary'
+        ",
+        GENERATOR_WAVEFORM_CHOICES.to_vec(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_gen.clone(),
+        interface.clone(),
+        FloatIndex::GeneratorFrequency as usize,
+        "frequency",
+        "Frequency of the generated waveform",
+        SiFormat::new("Hz", 0.1, 25_000_000.0, 1),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_gen.clone(),
+        interface.clone(),
+        FloatIndex::GeneratorAmplitude as usize,
+        "amplitude",
+        "Peak to peak amplitude of the generated waveform",
+        SiFormat::new("V", 0.0, 7.0, 3),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_gen.clone(),
+        interface.clone(),
+        FloatIndex::GeneratorOffset as usize,
+        "offset",
+        "DC offset of the generated waveform",
+        SiFormat::new("V", -3.5, 3.5, 3),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_gen.clone(),
+        interface.clone(),
+        FloatIndex::GeneratorDuty as usize,
+        "duty",
+        "Duty cycle of the square and pulse waveforms",
+        SiFormat::new("%", 0.0, 100.0, 1),
+    )
+    .await?;
+
+    arbitrary::mount(class_gen.clone(), interface.clone()).await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
@@ -0,0 +1,264 @@
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command,
+    BytesAttServer, Container, Error, JsonAttServer,
+};
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use std::sync::Arc;
+
+use crate::dso2c10::device::interface::DSO2C10Interface;
+use crate::dso2c10::device::interface::GENERATOR_ARBITRARY_SLOTS;
+use crate::dso2c10::ArbitraryWaveform;
+
+/// Slot used when the command does not specify one
+///
+const DEFAULT_SLOT: usize = 1;
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    //
+    //
+    let att = parent
+        .create_attribute("arbitrary")
+        .with_wo()
+        .with_info(
+            "Upload an arbitrary waveform into the ARB1 slot
+
+The payload is the raw list of 16 bits DAC codes, little endian.
+",
+        )
+        .finish_as_bytes()
+        .await?;
+    let logger = att.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    spawn_on_command!(
+        "on_command => arbitrary",
+        parent,
+        att,
+        on_command(att.clone(), interface.clone())
+    );
+
+    //
+    //
+    let att_samples = parent
+        .create_attribute("samples")
+        .with_rw()
+        .with_info(
+            "Upload normalized samples into an arbitrary slot and select it
+
+The payload is either an array of samples or an object:
+    { \"slot\": 1, \"samples\": [0.0, 0.5, 1.0, 0.5, 0.0, -0.5, -1.0, -0.5] }
+Samples describe one period and must be in [-1.0, 1.0], they are resampled to the
+generator memory length and quantized to the DAC resolution. The slot defaults
+to 1, it must be an integer from 1 to 4.
+
+The result of each upload is published:
+    { \"slot\": 1, \"points\": 8 }
+    { \"error\": \"Sample 2 out of range: 1.5 is not in [-1.0, 1.0]\" }
+",
+        )
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => samples",
+        parent,
+        att_samples,
+        on_samples_command(att_samples.clone(), interface.clone())
+    );
+
+    //
+    //
+    let att_samples_binary = parent
+        .create_attribute("samples_binary")
+        .with_wo()
+        .with_info(
+            "Same as 'samples' with a binary payload
+
+The first byte is the slot (1 to 4), followed by the samples as little endian f32.
+The result of each upload is published on 'samples'.
+",
+        )
+        .finish_as_bytes()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => samples_binary",
+        parent,
+        att_samples_binary,
+        on_samples_binary_command(
+            att_samples_binary.clone(),
+            att_samples.clone(),
+            interface.clone()
+        )
+    );
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+///
+///
+async fn on_command(
+    mut att: BytesAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Log
+        log_debug!(att.logger(), "arbitrary upload of {} bytes", command.len());
+
+        //
+        // A bad payload must not stop the attribute
+        if let Err(e) = interface
+            .lock()
+            .await
+            .upload_generator_arbitrary(DEFAULT_SLOT, &command)
+            .await
+        {
+            log_warn!(att.logger(), "arbitrary upload failed '{:?}'", e);
+        }
+    }
+    Ok(())
+}
+
+///
+///
+async fn on_samples_command(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Decode the payload
+        let (slot, waveform, points) = match samples_command(&command) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                log_warn!(att.logger(), "invalid samples '{}'", e);
+                att.set(json!({ "error": e })).await?;
+                continue;
+            }
+        };
+
+        //
+        // Log
+        log_debug!(att.logger(), "samples upload into ARB{}", slot);
+
+        let result = interface
+            .lock()
+            .await
+            .load_generator_arbitrary(slot, &waveform)
+            .await;
+        match result {
+            Ok(_) => att.set(json!({ "slot": slot, "points": points })).await?,
+            Err(e) => {
+                log_warn!(att.logger(), "samples upload failed '{:?}'", e);
+                att.set(json!({ "error": format!("{:?}", e) })).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Slot, waveform and number of samples of a samples command
+///
+fn samples_command(
+    command: &serde_json::Value,
+) -> Result<(usize, ArbitraryWaveform, usize), String> {
+    let (slot, samples) = match command {
+        serde_json::Value::Object(o) => {
+            let slot = match o.get("slot") {
+                None => DEFAULT_SLOT,
+                Some(s) => s
+                    .as_u64()
+                    .map(|s| s as usize)
+                    .filter(|s| (1..=GENERATOR_ARBITRARY_SLOTS).contains(s))
+                    .ok_or(format!(
+                        "Invalid slot {}, expected an integer from 1 to {}",
+                        s, GENERATOR_ARBITRARY_SLOTS
+                    ))?,
+            };
+            let samples = o
+                .get("samples")
+                .ok_or("Expected a 'samples' array".to_string())?;
+            (slot, samples)
+        }
+        _ => (DEFAULT_SLOT, command),
+    };
+    let points = samples.as_array().map(|a| a.len()).unwrap_or(0);
+    let waveform = ArbitraryWaveform::from_json(samples)?;
+    Ok((slot, waveform, points))
+}
+
+///
+///
+async fn on_samples_binary_command(
+    mut att: BytesAttServer,
+    mut att_result: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Decode the payload
+        let (slot, waveform, points) = match samples_binary_command(&command) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                log_warn!(att.logger(), "invalid samples '{}'", e);
+                att_result.set(json!({ "error": e })).await?;
+                continue;
+            }
+        };
+
+        //
+        // Log
+        log_debug!(att.logger(), "samples upload into ARB{}", slot);
+
+        let result = interface
+            .lock()
+            .await
+            .load_generator_arbitrary(slot, &waveform)
+            .await;
+        match result {
+            Ok(_) => {
+                att_result
+                    .set(json!({ "slot": slot, "points": points }))
+                    .await?
+            }
+            Err(e) => {
+                log_warn!(att.logger(), "samples upload failed '{:?}'", e);
+                att_result
+                    .set(json!({ "error": format!("{:?}", e) }))
+                    .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Slot, waveform and number of samples of a binary samples command
+///
+fn samples_binary_command(command: &[u8]) -> Result<(usize, ArbitraryWaveform, usize), String> {
+    let (slot, samples) = command
+        .split_first()
+        .ok_or("Empty payload, expected the slot byte".to_string())?;
+    let slot = *slot as usize;
+    if !(1..=GENERATOR_ARBITRARY_SLOTS).contains(&slot) {
+        return Err(format!(
+            "Invalid slot {}, expected an integer from 1 to {}",
+            slot, GENERATOR_ARBITRARY_SLOTS
+        ));
+    }
+    let waveform = ArbitraryWaveform::from_f32_le(samples)?;
+    Ok((slot, waveform, samples.len() / 4))
+}
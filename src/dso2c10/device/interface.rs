@@ -5,26 +5,171 @@ use panduza_platform_core::std::attribute::boolean::BooleanAccessorModel;
 use panduza_platform_core::std::attribute::idn::IdnReader;
 use panduza_platform_core::std::attribute::r#enum::StringAccessorModel;
 use panduza_platform_core::std::class::repl::ReplProtocol;
-use panduza_platform_core::{log_info, log_trace, Error, Logger};
+use panduza_platform_core::{log_info, Error, Logger};
 use strum_macros::FromRepr;
 use tokio::sync::Mutex;
 
 use std::sync::Arc;
-use std::time::Instant;
 
-use crate::dso2c10::ScpiBoolean;
+use crate::dso2c10::ArbitraryWaveform;
+use crate::dso2c10::DecodeTable;
+use crate::dso2c10::MemoryReadout;
+use crate::dso2c10::ReadoutProgress;
+use crate::dso2c10::Screen;
+use crate::dso2c10::Segment;
+use crate::dso2c10::WaveformPreamble;
+use crate::dso2c10::MEMORY_CHUNK_POINTS;
+use crate::scpi::ScpiBlock;
+use crate::scpi::ScpiNumeric;
+use crate::scpi::ScpiString;
+use crate::scpi::ScpiTmc;
+use crate::si::FloatAccessorModel;
+use crate::waveform::Waveform;
+use crate::waveform::WaveformAccessorModel;
+
+/// Choices of the channel coupling
+///
+pub const CHANNEL_COUPLING_CHOICES: [&str; 3] = ["DC", "AC", "GND"];
+
+/// Choices of the channel bandwidth limit, on models that provide several limits
+///
+pub const CHANNEL_BANDWIDTH_LIMIT_CHOICES: [&str; 3] = ["OFF", "20M", "100M"];
+
+/// Choices of the timebase mode, ROLL scrolls the waveform for slow timebases
+///
+pub const TIMEBASE_MODE_CHOICES: [&str; 2] = ["MAIN", "ROLL"];
+
+/// Choices of the trigger source, restricted by the profile of the model
+///
+pub const TRIGGER_SOURCE_CHOICES: [&str; 5] =
+    ["CHANnel1", "CHANnel2", "CHANnel3", "CHANnel4", "EXT"];
+
+/// Choices of the trigger sweep
+///
+pub const TRIGGER_SWEEP_CHOICES: [&str; 3] = ["AUTO", "NORMal", "SINGle"];
+
+/// Choices of the trigger mode, the edge trigger or a serial bus restricted by the
+/// decoders of the model
+///
+pub const TRIGGER_MODE_CHOICES: [&str; 6] = ["EDGE", "UART", "IIC", "SPI", "CAN", "LIN"];
+
+/// Choices of the UART trigger condition
+///
+pub const TRIGGER_UART_CONDITION_CHOICES: [&str; 4] = ["STARt", "STOP", "DATA", "ERRor"];
+
+/// Choices of the I2C trigger condition
+///
+pub const TRIGGER_I2C_CONDITION_CHOICES: [&str; 7] = [
+    "STARt", "STOP", "RESTart", "NACK", "ADDRess", "DATA", "ADATa",
+];
+
+/// Choices of the SPI trigger condition
+///
+pub const TRIGGER_SPI_CONDITION_CHOICES: [&str; 2] = ["STARt", "DATA"];
+
+/// Choices of the CAN trigger condition
+///
+pub const TRIGGER_CAN_CONDITION_CHOICES: [&str; 5] = ["STARt", "ID", "DATA", "IDData", "ERRor"];
+
+/// Choices of the LIN trigger condition
+///
+pub const TRIGGER_LIN_CONDITION_CHOICES: [&str; 5] = ["SYNC", "ID", "DATA", "IDData", "ERRor"];
+
+/// Largest CAN frame identifier (29 bits extended format)
+///
+const TRIGGER_CAN_ID_MAX: u64 = 0x1FFF_FFFF;
+
+/// Largest LIN frame identifier (6 bits)
+///
+const TRIGGER_LIN_ID_MAX: u64 = 0x3F;
+
+/// Choices of the math operator
+///
+pub const MATH_OPERATOR_CHOICES: [&str; 5] = ["ADD", "SUBTract", "MULTiply", "DIVision", "FFT"];
+
+/// Choices of the analog sources (math, cursor, decode), restricted by the profile
+/// of the model
+///
+pub const CHANNEL_SOURCE_CHOICES: [&str; 4] = ["CHANnel1", "CHANnel2", "CHANnel3", "CHANnel4"];
+
+/// Choices of the FFT window function
+///
+pub const MATH_FFT_WINDOW_CHOICES: [&str; 5] =
+    ["RECTangle", "HANNing", "HAMMing", "BLACkman", "FLATtop"];
+
+/// Choices of the FFT vertical unit
+///
+pub const MATH_FFT_UNIT_CHOICES: [&str; 2] = ["VRMS", "DB"];
+
+/// Choices of the cursor mode
+///
+pub const CURSOR_MODE_CHOICES: [&str; 3] = ["OFF", "MANual", "TRACk"];
+
+/// Choices of the cursor type, X measures time and Y measures voltage
+///
+pub const CURSOR_TYPE_CHOICES: [&str; 2] = ["X", "Y"];
+
+/// Choices of the decoded bus, restricted by the profile of the model
+///
+pub const DECODE_TYPE_CHOICES: [&str; 5] = ["UART", "IIC", "SPI", "CAN", "LIN"];
+
+/// Choices of the UART parity
+///
+pub const DECODE_UART_PARITY_CHOICES: [&str; 3] = ["NONE", "ODD", "EVEN"];
+
+/// Choices of the UART stop bits
+///
+pub const DECODE_UART_STOP_BITS_CHOICES: [&str; 3] = ["1", "1.5", "2"];
+
+/// Choices of the UART data bits
+///
+pub const DECODE_UART_DATA_BITS_CHOICES: [&str; 4] = ["5", "6", "7", "8"];
+
+/// Choices of the I2C address width, in bits
+///
+pub const DECODE_I2C_ADDRESS_WIDTH_CHOICES: [&str; 2] = ["7", "10"];
+
+/// Choices of the SPI clock polarity and phase
+///
+pub const DECODE_SPI_MODE_CHOICES: [&str; 2] = ["0", "1"];
+
+/// Choices of the SPI bit order
+///
+pub const DECODE_SPI_BIT_ORDER_CHOICES: [&str; 2] = ["MSB", "LSB"];
+
+/// Choices of the CAN signal probed
+///
+pub const DECODE_CAN_SIGNAL_CHOICES: [&str; 4] = ["CANH", "CANL", "TX", "RX"];
+
+/// Number of reference waveform slots
+///
+pub const REFERENCE_SLOTS: usize = 4;
+
+/// Number of internal setup slots, used by `*SAV` and `*RCL`
+///
+pub const SETUP_SLOTS: usize = 10;
+
+/// Choices of the generator waveform
+///
+pub const GENERATOR_WAVEFORM_CHOICES: [&str; 10] = [
+    "SINE", "SQUare", "RAMP", "EXP", "NOISe", "DC", "ARB1", "ARB2", "ARB3", "ARB4",
+];
+
+/// Number of user arbitrary waveform slots of the generator
+///
+pub const GENERATOR_ARBITRARY_SLOTS: usize = 4;
+
+/// Largest number of frames of a record
+///
+pub const RECORD_MAX_FRAMES: usize = 1000;
 
 #[derive(Clone)]
 /// Interface to communicate with the DSO2C10 device
 ///
 pub struct DSO2C10Interface {
-    /// Lower level interface (USB TMC)
+    /// SCPI commands and queries over the USB TMC connection
     ///
-    sub_interface: Arc<Mutex<UsbTmcInterface>>,
-
-    /// Logger for the driver
-    ///
-    logger: Logger,
+    scpi: ScpiTmc,
 }
 
 ///
@@ -40,187 +185,646 @@ impl DSO2C10Interface {
         //
         // Build the object
         DSO2C10Interface {
-            sub_interface,
-            logger,
+            scpi: ScpiTmc::new(sub_interface, logger),
         }
     }
 
-    /// Generic way to get boolean parameter from the device
     ///
-    pub async fn get_boolean_parameter(&self, cmd: &[u8]) -> Result<bool, Error> {
-        //
-        // Measure perfs
-        let start = Instant::now();
-
-        //
-        // Perform request
-        let mut response: Vec<u8> = Vec::new();
-        self.sub_interface
-            .lock()
+    ///
+    pub async fn get_channel_coupling(&self, channel_id: usize) -> Result<String, Error> {
+        let cmd_string = format!("CHANnel{}:COUPling?", channel_id);
+        self.scpi
+            .get_enum_parameter(cmd_string.as_bytes(), &CHANNEL_COUPLING_CHOICES)
             .await
-            .execute_command(cmd, &mut response)
+    }
+
+    ///
+    ///
+    pub async fn get_channel_offset(&self, channel_id: usize) -> Result<f64, Error> {
+        let cmd_string = format!("CHANnel{}:OFFSet?", channel_id);
+        self.scpi.get_float_parameter(cmd_string.as_bytes()).await
+    }
+
+    ///
+    ///
+    pub async fn set_channel_offset(&self, channel_id: usize, value: f64) -> Result<(), Error> {
+        let cmd_string = format!("CHANnel{}:OFFSet", channel_id);
+        self.scpi.set_float_parameter(&cmd_string, value).await
+    }
+
+    ///
+    ///
+    pub async fn get_channel_scale(&self, channel_id: usize) -> Result<f64, Error> {
+        let cmd_string = format!("CHANnel{}:SCALe?", channel_id);
+        self.scpi.get_float_parameter(cmd_string.as_bytes()).await
+    }
+
+    ///
+    ///
+    pub async fn set_channel_scale(&self, channel_id: usize, value: f64) -> Result<(), Error> {
+        let cmd_string = format!("CHANnel{}:SCALe", channel_id);
+        self.scpi.set_float_parameter(&cmd_string, value).await
+    }
+
+    /// Read the waveform of a channel
+    ///
+    pub async fn get_channel_waveform(&self, channel_id: usize) -> Result<Waveform, Error> {
+        self.get_waveform(&format!("CHANnel{}", channel_id)).await
+    }
+
+    /// Read the preamble of the selected waveform source
+    ///
+    async fn get_preamble(&self) -> Result<WaveformPreamble, Error> {
+        let preamble = self
+            .scpi
+            .get_string_parameter(b"WAVeform:PREamble?")
             .await?;
+        WaveformPreamble::parse(&preamble).map_err(|e| Error::DeserializeError(e))
+    }
 
-        //
-        // Log
-        log_trace!(
-            self.logger,
-            "ASK <=> {:?} - {:?} - {:.2?}",
-            cmd,
-            response,
-            start.elapsed()
-        );
+    /// Read the waveform of a source (e.g. CHANnel1, MATH)
+    ///
+    /// Points are read as BYTE and converted into volts with the preamble.
+    ///
+    pub async fn get_waveform(&self, source: &str) -> Result<Waveform, Error> {
+        self.scpi
+            .set_string_parameter("WAVeform:SOURce", &source.to_string())
+            .await?;
+        self.scpi
+            .set_string_parameter("WAVeform:MODE", &"NORMal".to_string())
+            .await?;
+        self.scpi
+            .set_string_parameter("WAVeform:FORMat", &"BYTE".to_string())
+            .await?;
 
-        //
-        // End
-        let v = ScpiBoolean::from_vec_ascii(&response).map_err(|e| Error::DeserializeError(e))?;
-        Ok(v.into())
+        let preamble = self.get_preamble().await?;
+
+        let response = self.scpi.ask(b"WAVeform:DATA?").await?;
+        let block = ScpiBlock::from_vec(&response).map_err(|e| Error::DeserializeError(e))?;
+
+        Ok(preamble.decode(block.data(), 0))
     }
 
+    /// Arm a single acquisition, the scope stops after the next trigger
     ///
+    pub async fn arm_single(&self) -> Result<(), Error> {
+        self.scpi.send("SINGle").await
+    }
+
+    /// True if the acquisition is stopped
     ///
-    pub async fn set_boolean_parameter(&self, cmd: &str, value: bool) -> Result<(), Error> {
-        let cmd_string = format!("{} {}", cmd, ScpiBoolean::new(value).to_digital_str());
-        self.sub_interface
-            .lock()
-            .await
-            .send_command(cmd_string.as_bytes())
-            .await
+    pub async fn is_acquisition_stopped(&self) -> Result<bool, Error> {
+        let status = self.scpi.get_string_parameter(b"TRIGger:STATus?").await?;
+        Ok(status.eq_ignore_ascii_case("STOP"))
     }
 
-    /// Generic way to get string parameter from the device
+    /// Read several channels from the same acquisition
+    ///
+    /// A running acquisition is stopped during the readout, otherwise each
+    /// channel could come from a different trigger.
     ///
-    pub async fn get_string_parameter(&self, cmd: &[u8]) -> Result<String, Error> {
+    pub async fn get_channel_waveforms(
+        &self,
+        channel_ids: &[usize],
+    ) -> Result<Vec<Waveform>, Error> {
+        let running = !self.is_acquisition_stopped().await?;
+        if running {
+            self.scpi.send("STOP").await?;
+        }
+
+        let mut waveforms = Vec::with_capacity(channel_ids.len());
+        let mut result = Ok(());
+        for channel_id in channel_ids {
+            match self.get_channel_waveform(*channel_id).await {
+                Ok(w) => waveforms.push(w),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+
         //
-        // Measure perfs
-        let start = Instant::now();
+        // Restart even if a readout failed
+        if running {
+            self.scpi.send("RUN").await?;
+        }
+        result.map(|_| waveforms)
+    }
+
+    /// Read the whole acquisition memory of a source, in chunks
+    ///
+    /// The acquisition is stopped during the readout. `progress` is updated after
+    /// each chunk, a cancellation is checked before each one. The waveform mode
+    /// and range are restored afterwards, the screen readouts would otherwise be
+    /// limited to the last chunk.
+    ///
+    pub async fn get_memory_waveform(
+        &self,
+        source: &str,
+        progress: &Arc<Mutex<ReadoutProgress>>,
+    ) -> Result<Waveform, Error> {
+        let start = self.scpi.get_float_parameter(b"WAVeform:STARt?").await?;
+        let stop = self.scpi.get_float_parameter(b"WAVeform:STOP?").await?;
+
+        let running = !self.is_acquisition_stopped().await?;
+        if running {
+            self.scpi.send("STOP").await?;
+        }
+
+        let result = self.read_memory(source, progress).await;
 
         //
-        // Perform request
-        let mut response: Vec<u8> = Vec::new();
-        self.sub_interface
-            .lock()
+        // Restore and restart even if the readout failed or was cancelled, the first
+        // error is returned
+        let restored = self.set_waveform_range(start, stop).await;
+        let restarted = if running {
+            self.scpi.send("RUN").await
+        } else {
+            Ok(())
+        };
+        let waveform = result?;
+        restored?;
+        restarted?;
+        Ok(waveform)
+    }
+
+    /// Select the NORMal waveform mode and the range of points it reads
+    ///
+    /// STARt is set first, it stays below the STOP left by a memory readout.
+    ///
+    async fn set_waveform_range(&self, start: f64, stop: f64) -> Result<(), Error> {
+        self.scpi
+            .set_string_parameter("WAVeform:MODE", &"NORMal".to_string())
+            .await?;
+        self.scpi
+            .set_string_parameter("WAVeform:STARt", &(start as usize).to_string())
+            .await?;
+        self.scpi
+            .set_string_parameter("WAVeform:STOP", &(stop as usize).to_string())
             .await
-            .execute_command(cmd, &mut response)
+    }
+
+    ///
+    ///
+    async fn read_memory(
+        &self,
+        source: &str,
+        progress: &Arc<Mutex<ReadoutProgress>>,
+    ) -> Result<Waveform, Error> {
+        self.scpi
+            .set_string_parameter("WAVeform:SOURce", &source.to_string())
+            .await?;
+        self.scpi
+            .set_string_parameter("WAVeform:MODE", &"RAW".to_string())
+            .await?;
+        self.scpi
+            .set_string_parameter("WAVeform:FORMat", &"BYTE".to_string())
             .await?;
 
-        //
-        // Log
-        log_trace!(
-            self.logger,
-            "ASK <=> {:?} - {:?} - {:.2?}",
-            cmd,
-            response,
-            start.elapsed()
-        );
+        let preamble = self
+            .scpi
+            .get_string_parameter(b"WAVeform:PREamble?")
+            .await?;
+        let preamble =
+            WaveformPreamble::parse(&preamble).map_err(|e| Error::DeserializeError(e))?;
 
-        //
-        // End
-        match String::from_utf8(response) {
-            Ok(s) => Ok(s),
-            Err(e) => Err(Error::DeserializeError(
-                "Cannot convert response into string".to_string(),
-            )),
+        let mut readout = MemoryReadout::new(preamble.points, MEMORY_CHUNK_POINTS)
+            .map_err(|e| Error::InvalidArgument(e))?;
+        progress.lock().await.start(readout.total());
+
+        while let Some((start, stop)) = readout.next_chunk() {
+            if progress.lock().await.cancel_requested {
+                return Err(Error::Generic(format!(
+                    "Readout of {} cancelled after {} points",
+                    source,
+                    readout.points_read()
+                )));
+            }
+
+            self.scpi
+                .set_string_parameter("WAVeform:STARt", &start.to_string())
+                .await?;
+            self.scpi
+                .set_string_parameter("WAVeform:STOP", &stop.to_string())
+                .await?;
+            let response = self.scpi.ask(b"WAVeform:DATA?").await?;
+            let block = ScpiBlock::from_vec(&response).map_err(|e| Error::DeserializeError(e))?;
+            readout
+                .push(block.data())
+                .map_err(|e| Error::DeserializeError(e))?;
+
+            progress.lock().await.points_read = readout.points_read();
         }
+
+        let codes = readout.finish().map_err(|e| Error::DeserializeError(e))?;
+        Ok(preamble.decode(&codes, 0))
     }
 
+    /// Select the trigger mode, EDGE or one of the serial buses
     ///
+    /// A bus trigger uses the lines and the format configured on the decoder, so
+    /// the decoder must be set to the same bus.
     ///
-    pub async fn set_string_parameter(&self, cmd: &str, value: &String) -> Result<(), Error> {
-        let cmd_string = format!("{} {}", cmd, value);
-        self.sub_interface
-            .lock()
-            .await
-            .send_command(cmd_string.as_bytes())
+    pub async fn set_trigger_mode(&self, value: &String) -> Result<(), Error> {
+        let mode = ScpiString::new(value)
+            .resolve(&TRIGGER_MODE_CHOICES)
+            .ok_or(Error::InvalidArgument(format!(
+                "Invalid trigger mode {:?}, expected one of {:?}",
+                value, TRIGGER_MODE_CHOICES
+            )))?;
+        if mode != "EDGE" {
+            let decoded = self
+                .scpi
+                .get_enum_parameter(b"DECode:TYPE?", &DECODE_TYPE_CHOICES)
+                .await?;
+            if decoded != mode {
+                return Err(Error::InvalidArgument(format!(
+                    "The {} trigger uses the decoder settings, the decoder is set to {}",
+                    mode, decoded
+                )));
+            }
+        }
+        self.scpi
+            .set_string_parameter("TRIGger:MODE", &mode.to_string())
             .await
     }
 
-    /// Generic way to get string parameter from the device
+    /// Largest value of a bus trigger field allowed by the decoder settings
     ///
-    pub async fn get_float_parameter(&self, cmd: &[u8]) -> Result<f64, Error> {
-        //
-        // Measure perfs
-        let start = Instant::now();
+    async fn get_trigger_bus_value_max(&self, index: FloatIndex) -> Result<u64, Error> {
+        match index {
+            FloatIndex::TriggerUartData | FloatIndex::TriggerUartDataMask => {
+                let bits = self
+                    .scpi
+                    .get_numeric_enum_parameter(
+                        b"DECode:UART:WIDTh?",
+                        &DECODE_UART_DATA_BITS_CHOICES,
+                    )
+                    .await?;
+                Ok((1 << bits.parse::<u32>().unwrap_or(8)) - 1)
+            }
+            FloatIndex::TriggerI2cAddress | FloatIndex::TriggerI2cAddressMask => {
+                let bits = self
+                    .scpi
+                    .get_numeric_enum_parameter(
+                        b"DECode:IIC:AWIDth?",
+                        &DECODE_I2C_ADDRESS_WIDTH_CHOICES,
+                    )
+                    .await?;
+                Ok((1 << bits.parse::<u32>().unwrap_or(7)) - 1)
+            }
+            FloatIndex::TriggerSpiData | FloatIndex::TriggerSpiDataMask => Ok(u32::MAX as u64),
+            FloatIndex::TriggerI2cData
+            | FloatIndex::TriggerI2cDataMask
+            | FloatIndex::TriggerCanData
+            | FloatIndex::TriggerCanDataMask
+            | FloatIndex::TriggerLinData
+            | FloatIndex::TriggerLinDataMask => Ok(u8::MAX as u64),
+            FloatIndex::TriggerCanId => Ok(TRIGGER_CAN_ID_MAX),
+            FloatIndex::TriggerLinId => Ok(TRIGGER_LIN_ID_MAX),
+            _ => Err(Error::InvalidArgument(format!(
+                "{:?} is not a bus trigger value",
+                index
+            ))),
+        }
+    }
 
-        //
-        // Perform request
-        let mut response: Vec<u8> = Vec::new();
-        self.sub_interface
-            .lock()
+    /// Set a bus trigger value after checking it against the decoder settings
+    ///
+    async fn set_trigger_bus_value(
+        &self,
+        cmd: &str,
+        index: FloatIndex,
+        value: f64,
+    ) -> Result<(), Error> {
+        let max = self.get_trigger_bus_value_max(index).await?;
+        if value < 0.0 || value.fract() != 0.0 || value > max as f64 {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid {:?} {}, expected an integer from 0 to {:#X}",
+                index, value, max
+            )));
+        }
+        self.scpi
+            .set_string_parameter(cmd, &(value as u64).to_string())
             .await
-            .execute_command(cmd, &mut response)
-            .await?;
+    }
 
-        //
-        // Log
-        log_trace!(
-            self.logger,
-            "ASK <=> {:?} - {:?} - {:.2?}",
-            cmd,
-            response,
-            start.elapsed()
-        );
+    /// Set a frame number or count of the record, an integer from 1 to the
+    /// largest record
+    ///
+    async fn set_record_frame_parameter(&self, cmd: &str, value: f64) -> Result<(), Error> {
+        if value < 1.0 || value.fract() != 0.0 || value > RECORD_MAX_FRAMES as f64 {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid frame {}, expected an integer from 1 to {}",
+                value, RECORD_MAX_FRAMES
+            )));
+        }
+        self.scpi
+            .set_string_parameter(cmd, &(value as usize).to_string())
+            .await
+    }
 
-        //
-        // End
-        match String::from_utf8(response) {
-            Ok(s) => Ok(s
-                .parse::<f64>()
-                .map_err(|_| Error::Generic("Cannot convert response into float".to_string()))?),
-            Err(e) => Err(Error::Generic(
-                "Cannot convert response into string".to_string(),
-            )),
+    /// Number of frames recorded by the last record
+    ///
+    pub async fn get_record_count(&self) -> Result<usize, Error> {
+        Ok(self.scpi.get_float_parameter(b"RECord:PLAY:COUNt?").await? as usize)
+    }
+
+    /// Read a recorded frame of a source, with its timestamp
+    ///
+    /// The frame becomes the current frame of the playback.
+    ///
+    pub async fn get_record_frame(
+        &self,
+        frame: usize,
+        source: &'static str,
+    ) -> Result<Segment, Error> {
+        let count = self.get_record_count().await?;
+        if !(1..=count).contains(&frame) {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid frame {}, {} frames recorded",
+                frame, count
+            )));
         }
+        self.scpi
+            .set_string_parameter("RECord:PLAY:FRAMe", &frame.to_string())
+            .await?;
+        let timestamp = self
+            .scpi
+            .get_float_parameter(b"RECord:PLAY:TIMestamp?")
+            .await?;
+        let waveform = self.get_waveform(source).await?;
+        Ok(Segment {
+            frame,
+            timestamp,
+            source,
+            waveform,
+        })
     }
 
+    /// Read the waveform of the math channel
     ///
+    /// With the FFT operator the points are spectrum bins: the x axis is in Hz and
+    /// the samples are in the FFT unit.
     ///
-    pub async fn get_channel_coupling(&self, channel_id: usize) -> Result<String, Error> {
-        let cmd_string = format!("CHANnel{}:COUPling?", channel_id);
-        self.get_string_parameter(cmd_string.as_bytes()).await
+    pub async fn get_math_waveform(&self) -> Result<Waveform, Error> {
+        let mut waveform = self.get_waveform("MATH").await?;
+        let operator = self
+            .scpi
+            .get_enum_parameter(b"MATH:OPERator?", &MATH_OPERATOR_CHOICES)
+            .await?;
+        if operator == "FFT" {
+            let unit = self
+                .scpi
+                .get_enum_parameter(b"MATH:FFT:UNIT?", &MATH_FFT_UNIT_CHOICES)
+                .await?;
+            waveform.unit = match unit.as_str() {
+                "DB" => "dBV".to_string(),
+                _ => "Vrms".to_string(),
+            };
+        }
+        Ok(waveform)
+    }
+
+    /// Conversion between screen and physical units for the source of the cursor
+    /// node (MANual or TRACk)
+    ///
+    pub async fn get_cursor_screen(&self, node: &str) -> Result<Screen, Error> {
+        let cmd_string = format!("{}:SOURce?", node);
+        let source = self
+            .scpi
+            .get_enum_parameter(cmd_string.as_bytes(), &CHANNEL_SOURCE_CHOICES)
+            .await?;
+        let channel_id = CHANNEL_SOURCE_CHOICES
+            .iter()
+            .position(|c| *c == source)
+            .unwrap_or(0)
+            + 1;
+        Ok(Screen {
+            time_scale: self.scpi.get_float_parameter(b"TIMebase:SCALe?").await?,
+            time_position: self.scpi.get_float_parameter(b"TIMebase:POSition?").await?,
+            voltage_scale: self.get_channel_scale(channel_id).await?,
+            voltage_offset: self.get_channel_offset(channel_id).await?,
+        })
     }
 
+    /// SCPI node of the cursor positions, they depend on the cursor mode
     ///
+    async fn get_cursor_node(&self) -> Result<&'static str, Error> {
+        let mode = self
+            .scpi
+            .get_enum_parameter(b"CURSor:MODE?", &CURSOR_MODE_CHOICES)
+            .await?;
+        Ok(match mode.as_str() {
+            "TRACk" => "CURSor:TRACk",
+            _ => "CURSor:MANual",
+        })
+    }
+
+    /// Time of the cursor line (A or B), in seconds
     ///
-    pub async fn get_channel_offset(&self, channel_id: usize) -> Result<f64, Error> {
-        let cmd_string = format!("CHANnel{}:OFFSet?", channel_id);
-        self.get_float_parameter(cmd_string.as_bytes()).await
+    pub async fn get_cursor_time(&self, cursor: char) -> Result<f64, Error> {
+        let node = self.get_cursor_node().await?;
+        let cmd_string = format!("{}:{}X?", node, cursor);
+        let x = self.scpi.get_float_parameter(cmd_string.as_bytes()).await?;
+        Ok(self.get_cursor_screen(node).await?.time_at(x))
     }
 
     ///
     ///
-    pub async fn set_channel_offset(&self, channel_id: usize, value: f64) -> Result<(), Error> {
-        // let mut v = "0";
-        // if value {
-        //     v = "1";
-        // }
-        let cmd_string = format!("CHANnel{}:OFFSet {}", channel_id, value);
-        let cmd = cmd_string.as_bytes();
-        self.sub_interface.lock().await.send_command(cmd).await?;
+    pub async fn set_cursor_time(&self, cursor: char, value: f64) -> Result<(), Error> {
+        let node = self.get_cursor_node().await?;
+        let x = self.get_cursor_screen(node).await?.x_of(value);
+        let cmd_string = format!("{}:{}X", node, cursor);
+        self.scpi.set_float_parameter(&cmd_string, x).await
+    }
+
+    /// Voltage of the cursor line (A or B), in volts
+    ///
+    pub async fn get_cursor_voltage(&self, cursor: char) -> Result<f64, Error> {
+        let node = self.get_cursor_node().await?;
+        let cmd_string = format!("{}:{}Y?", node, cursor);
+        let y = self.scpi.get_float_parameter(cmd_string.as_bytes()).await?;
+        Ok(self.get_cursor_screen(node).await?.voltage_at(y))
+    }
+
+    ///
+    ///
+    pub async fn set_cursor_voltage(&self, cursor: char, value: f64) -> Result<(), Error> {
+        let node = self.get_cursor_node().await?;
+        let y = self.get_cursor_screen(node).await?.y_of(value);
+        let cmd_string = format!("{}:{}Y", node, cursor);
+        self.scpi.set_float_parameter(&cmd_string, y).await
+    }
+
+    /// Time and voltage between the cursors B and A, in seconds and volts
+    ///
+    /// None while the cursors are OFF. The node and the screen are read once for
+    /// both cursors.
+    ///
+    pub async fn get_cursor_deltas(&self) -> Result<Option<(f64, f64)>, Error> {
+        let mode = self
+            .scpi
+            .get_enum_parameter(b"CURSor:MODE?", &CURSOR_MODE_CHOICES)
+            .await?;
+        let node = match mode.as_str() {
+            "OFF" => return Ok(None),
+            "TRACk" => "CURSor:TRACk",
+            _ => "CURSor:MANual",
+        };
+        let screen = self.get_cursor_screen(node).await?;
+
+        let mut positions = [(0.0, 0.0); 2];
+        for (position, cursor) in positions.iter_mut().zip(['A', 'B']) {
+            let x = format!("{}:{}X?", node, cursor);
+            let y = format!("{}:{}Y?", node, cursor);
+            *position = (
+                self.scpi.get_float_parameter(x.as_bytes()).await?,
+                self.scpi.get_float_parameter(y.as_bytes()).await?,
+            );
+        }
+        let [(ax, ay), (bx, by)] = positions;
+        Ok(Some((
+            screen.time_at(bx) - screen.time_at(ax),
+            screen.voltage_at(by) - screen.voltage_at(ay),
+        )))
+    }
+
+    ///
+    ///
+    fn check_reference_slot(slot: usize) -> Result<(), Error> {
+        if slot < 1 || slot > REFERENCE_SLOTS {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid reference slot {}, expected 1 to {}",
+                slot, REFERENCE_SLOTS
+            )));
+        }
         Ok(())
     }
 
+    /// Save the waveform of a source into a reference slot
     ///
+    /// The saved waveform is read and returned, the scope does not give the
+    /// content of its reference slots back.
     ///
-    pub async fn get_channel_scale(&self, channel_id: usize) -> Result<f64, Error> {
-        let cmd_string = format!("CHANnel{}:SCALe?", channel_id);
-        self.get_float_parameter(cmd_string.as_bytes()).await
+    pub async fn save_reference(&self, slot: usize, source: &str) -> Result<Waveform, Error> {
+        Self::check_reference_slot(slot)?;
+        let waveform = self.get_waveform(source).await?;
+        self.scpi
+            .set_string_parameter(&format!("REFerence{}:SAVE", slot), &source.to_string())
+            .await?;
+        Ok(waveform)
     }
 
+    /// Upload a host waveform into a reference slot
     ///
+    /// Samples are converted into BYTE codes with the vertical scale of
+    /// `scale_source`, the reference is drawn on the grid of that channel and
+    /// samples outside of its screen are clipped.
     ///
-    pub async fn set_channel_scale(&self, channel_id: usize, value: f64) -> Result<(), Error> {
-        // let mut v = "0";
-        // if value {
-        //     v = "1";
-        // }
-        let cmd_string = format!("CHANnel{}:SCALe {}", channel_id, value);
-        let cmd = cmd_string.as_bytes();
-        self.sub_interface.lock().await.send_command(cmd).await?;
+    pub async fn load_reference(
+        &self,
+        slot: usize,
+        scale_source: &str,
+        waveform: &Waveform,
+    ) -> Result<(), Error> {
+        Self::check_reference_slot(slot)?;
+        if waveform.unit != "V" || waveform.samples.is_empty() {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid reference of {} samples in {:?}, expected samples in V",
+                waveform.samples.len(),
+                waveform.unit
+            )));
+        }
+
+        self.scpi
+            .set_string_parameter("WAVeform:SOURce", &scale_source.to_string())
+            .await?;
+        let preamble = self.get_preamble().await?;
+
+        let mut cmd = format!("REFerence{}:DATA ", slot).into_bytes();
+        cmd.extend(ScpiBlock::new(preamble.encode(waveform)).to_vec());
+        self.scpi.send_bytes(&cmd).await
+    }
+
+    /// Read the event table of the decoder
+    ///
+    pub async fn get_decode_frames(&self) -> Result<DecodeTable, Error> {
+        let response = self.scpi.ask(b"DECode:LIST?").await?;
+        let text = match response.first() {
+            Some(b'#') => ScpiBlock::from_vec(&response)
+                .map_err(|e| Error::DeserializeError(e))?
+                .into_data(),
+            _ => response,
+        };
+        DecodeTable::parse(&String::from_utf8_lossy(&text)).map_err(|e| Error::DeserializeError(e))
+    }
+
+    ///
+    ///
+    fn check_setup_slot(slot: usize) -> Result<(), Error> {
+        if slot < 1 || slot > SETUP_SLOTS {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid setup slot {}, expected 1 to {}",
+                slot, SETUP_SLOTS
+            )));
+        }
         Ok(())
     }
 
-    // CHANnel<n>:PROBe
+    /// Save the current setup into an internal slot
+    ///
+    pub async fn save_setup(&self, slot: usize) -> Result<(), Error> {
+        Self::check_setup_slot(slot)?;
+        self.scpi
+            .set_string_parameter("*SAV", &slot.to_string())
+            .await
+    }
+
+    /// Recall the setup of an internal slot
+    ///
+    pub async fn recall_setup(&self, slot: usize) -> Result<(), Error> {
+        Self::check_setup_slot(slot)?;
+        self.scpi
+            .set_string_parameter("*RCL", &slot.to_string())
+            .await
+    }
+
+    /// Upload raw 16 bits DAC codes (little endian) into an arbitrary slot
+    ///
+    pub async fn upload_generator_arbitrary(&self, slot: usize, codes: &[u8]) -> Result<(), Error> {
+        if slot < 1 || slot > GENERATOR_ARBITRARY_SLOTS {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid arbitrary slot {}, expected 1 to {}",
+                slot, GENERATOR_ARBITRARY_SLOTS
+            )));
+        }
+        if codes.is_empty() || codes.len() % 2 != 0 {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid arbitrary payload of {} bytes, expected 16 bits codes",
+                codes.len()
+            )));
+        }
+
+        let mut cmd = format!("DDS:ARB:DAC16:BIN ARB{},", slot).into_bytes();
+        cmd.extend(ScpiBlock::new(codes.to_vec()).to_vec());
+        self.scpi.send_bytes(&cmd).await
+    }
+
+    /// Upload an arbitrary waveform into a slot and select it as generator waveform
+    ///
+    pub async fn load_generator_arbitrary(
+        &self,
+        slot: usize,
+        waveform: &ArbitraryWaveform,
+    ) -> Result<(), Error> {
+        self.upload_generator_arbitrary(slot, &waveform.to_dac16())
+            .await?;
+        self.scpi
+            .set_string_parameter("DDS:TYPE", &format!("ARB{}", slot))
+            .await
+    }
 }
 
 #[async_trait]
@@ -228,7 +832,7 @@ impl ReplProtocol for DSO2C10Interface {
     ///
     ///
     async fn eval(&mut self, command: String) -> Result<String, Error> {
-        self.sub_interface.lock().await.eval(command).await
+        self.scpi.eval(command).await
     }
 }
 
@@ -237,34 +841,9 @@ impl ReplProtocol for DSO2C10Interface {
 ///
 impl IdnReader for DSO2C10Interface {
     async fn read_idn(&mut self) -> Result<String, Error> {
-        //
-        // Measure perfs
-        let start = Instant::now();
-
-        //
-        // Perform request
-        let mut response: Vec<u8> = Vec::new();
-        let cmd = "*IDN?".as_bytes();
-        self.sub_interface
-            .lock()
-            .await
-            .execute_command(cmd, &mut response)
-            .await?;
-
-        //
-        // Log
-        log_trace!(
-            self.logger,
-            "ASK <=> {:?} - {:?} - {:.2?}",
-            cmd,
-            response,
-            start.elapsed()
-        );
-
-        //
-        // End
-        match String::from_utf8(response) {
-            Ok(s) => Ok(s),
+        let response = self.scpi.ask("*IDN?".as_bytes()).await?;
+        match ScpiString::from_vec_ascii(&response) {
+            Ok(s) => Ok(s.into()),
             Err(_) => Ok("Cannot convert the payload into string".to_string()),
         }
     }
@@ -274,7 +853,7 @@ impl IdnReader for DSO2C10Interface {
 // ----------------------------------------------------------------------------
 // ----------------------------------------------------------------------------
 
-#[derive(FromRepr, Debug, PartialEq)]
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
 pub enum BooleanIndex {
     //
     Channel1BwLimit,
@@ -287,11 +866,35 @@ pub enum BooleanIndex {
     Channel2Invert,
     Channel2Vernier,
     //
+    Channel3BwLimit,
+    Channel3Display,
+    Channel3Invert,
+    Channel3Vernier,
+    //
+    Channel4BwLimit,
+    Channel4Display,
+    Channel4Invert,
+    Channel4Vernier,
+    //
     TimebaseWindowEnable,
     //
     MeasureEnable,
     MeasureADisplay,
     MeasureGateEnable,
+    //
+    MathDisplay,
+    //
+    Reference1Display,
+    Reference2Display,
+    Reference3Display,
+    Reference4Display,
+    //
+    DecodeDisplay,
+    //
+    RecordEnable,
+    RecordPlay,
+    //
+    GeneratorOutput,
 }
 
 #[async_trait]
@@ -310,25 +913,107 @@ impl BooleanAccessorModel for DSO2C10Interface {
         // Perform the request
         match idx {
             //
-            BooleanIndex::Channel1BwLimit => self.get_boolean_parameter(b"CHANnel1:BWLimit?").await,
-            BooleanIndex::Channel1Display => self.get_boolean_parameter(b"CHANnel1:DISPlay?").await,
-            BooleanIndex::Channel1Invert => self.get_boolean_parameter(b"CHANnel1:INVert?").await,
-            BooleanIndex::Channel1Vernier => self.get_boolean_parameter(b"CHANnel1:VERNier?").await,
+            BooleanIndex::Channel1BwLimit => {
+                self.scpi.get_boolean_parameter(b"CHANnel1:BWLimit?").await
+            }
+            BooleanIndex::Channel1Display => {
+                self.scpi.get_boolean_parameter(b"CHANnel1:DISPlay?").await
+            }
+            BooleanIndex::Channel1Invert => {
+                self.scpi.get_boolean_parameter(b"CHANnel1:INVert?").await
+            }
+            BooleanIndex::Channel1Vernier => {
+                self.scpi.get_boolean_parameter(b"CHANnel1:VERNier?").await
+            }
+            //
+            BooleanIndex::Channel2BwLimit => {
+                self.scpi.get_boolean_parameter(b"CHANnel2:BWLimit?").await
+            }
+            BooleanIndex::Channel2Display => {
+                self.scpi.get_boolean_parameter(b"CHANnel2:DISPlay?").await
+            }
+            BooleanIndex::Channel2Invert => {
+                self.scpi.get_boolean_parameter(b"CHANnel2:INVert?").await
+            }
+            BooleanIndex::Channel2Vernier => {
+                self.scpi.get_boolean_parameter(b"CHANnel2:VERNier?").await
+            }
             //
-            BooleanIndex::Channel2BwLimit => self.get_boolean_parameter(b"CHANnel2:BWLimit?").await,
-            BooleanIndex::Channel2Display => self.get_boolean_parameter(b"CHANnel2:DISPlay?").await,
-            BooleanIndex::Channel2Invert => self.get_boolean_parameter(b"CHANnel2:INVert?").await,
-            BooleanIndex::Channel2Vernier => self.get_boolean_parameter(b"CHANnel2:VERNier?").await,
+            BooleanIndex::Channel3BwLimit => {
+                self.scpi.get_boolean_parameter(b"CHANnel3:BWLimit?").await
+            }
+            BooleanIndex::Channel3Display => {
+                self.scpi.get_boolean_parameter(b"CHANnel3:DISPlay?").await
+            }
+            BooleanIndex::Channel3Invert => {
+                self.scpi.get_boolean_parameter(b"CHANnel3:INVert?").await
+            }
+            BooleanIndex::Channel3Vernier => {
+                self.scpi.get_boolean_parameter(b"CHANnel3:VERNier?").await
+            }
+            //
+            BooleanIndex::Channel4BwLimit => {
+                self.scpi.get_boolean_parameter(b"CHANnel4:BWLimit?").await
+            }
+            BooleanIndex::Channel4Display => {
+                self.scpi.get_boolean_parameter(b"CHANnel4:DISPlay?").await
+            }
+            BooleanIndex::Channel4Invert => {
+                self.scpi.get_boolean_parameter(b"CHANnel4:INVert?").await
+            }
+            BooleanIndex::Channel4Vernier => {
+                self.scpi.get_boolean_parameter(b"CHANnel4:VERNier?").await
+            }
             //
             BooleanIndex::TimebaseWindowEnable => {
-                self.get_boolean_parameter(b"TIMebase:WINDow:ENABle?").await
+                self.scpi
+                    .get_boolean_parameter(b"TIMebase:WINDow:ENABle?")
+                    .await
             }
             //
-            BooleanIndex::MeasureEnable => self.get_boolean_parameter(b"MEASure:ENABle?").await,
-            BooleanIndex::MeasureADisplay => self.get_boolean_parameter(b"MEASure:ADISplay?").await,
+            BooleanIndex::MeasureEnable => {
+                self.scpi.get_boolean_parameter(b"MEASure:ENABle?").await
+            }
+            BooleanIndex::MeasureADisplay => {
+                self.scpi.get_boolean_parameter(b"MEASure:ADISplay?").await
+            }
             BooleanIndex::MeasureGateEnable => {
-                self.get_boolean_parameter(b"MEASure:GATE:ENABle?").await
+                self.scpi
+                    .get_boolean_parameter(b"MEASure:GATE:ENABle?")
+                    .await
+            }
+            //
+            BooleanIndex::MathDisplay => self.scpi.get_boolean_parameter(b"MATH:DISPlay?").await,
+            //
+            BooleanIndex::Reference1Display => {
+                self.scpi
+                    .get_boolean_parameter(b"REFerence1:DISPlay?")
+                    .await
+            }
+            BooleanIndex::Reference2Display => {
+                self.scpi
+                    .get_boolean_parameter(b"REFerence2:DISPlay?")
+                    .await
+            }
+            BooleanIndex::Reference3Display => {
+                self.scpi
+                    .get_boolean_parameter(b"REFerence3:DISPlay?")
+                    .await
+            }
+            BooleanIndex::Reference4Display => {
+                self.scpi
+                    .get_boolean_parameter(b"REFerence4:DISPlay?")
+                    .await
+            }
+            //
+            BooleanIndex::DecodeDisplay => {
+                self.scpi.get_boolean_parameter(b"DECode:DISPlay?").await
             }
+            //
+            BooleanIndex::RecordEnable => self.scpi.get_boolean_parameter(b"RECord:ENABle?").await,
+            BooleanIndex::RecordPlay => self.scpi.get_boolean_parameter(b"RECord:PLAY?").await,
+            //
+            BooleanIndex::GeneratorOutput => self.scpi.get_boolean_parameter(b"DDS:SWITch?").await,
         }
     }
 
@@ -345,46 +1030,152 @@ impl BooleanAccessorModel for DSO2C10Interface {
         match idx {
             //
             BooleanIndex::Channel1BwLimit => {
-                self.set_boolean_parameter("CHANnel1:BWLimit", value).await
+                self.scpi
+                    .set_boolean_parameter("CHANnel1:BWLimit", value)
+                    .await
             }
             BooleanIndex::Channel1Display => {
-                self.set_boolean_parameter("CHANnel1:DISPlay", value).await
+                self.scpi
+                    .set_boolean_parameter("CHANnel1:DISPlay", value)
+                    .await
             }
             BooleanIndex::Channel1Invert => {
-                self.set_boolean_parameter("CHANnel1:INVert", value).await
+                self.scpi
+                    .set_boolean_parameter("CHANnel1:INVert", value)
+                    .await
             }
             BooleanIndex::Channel1Vernier => {
-                self.set_boolean_parameter("CHANnel1:VERNier", value).await
+                self.scpi
+                    .set_boolean_parameter("CHANnel1:VERNier", value)
+                    .await
             }
             //
             BooleanIndex::Channel2BwLimit => {
-                self.set_boolean_parameter("CHANnel2:BWLimit", value).await
+                self.scpi
+                    .set_boolean_parameter("CHANnel2:BWLimit", value)
+                    .await
             }
             BooleanIndex::Channel2Display => {
-                self.set_boolean_parameter("CHANnel2:DISPlay", value).await
+                self.scpi
+                    .set_boolean_parameter("CHANnel2:DISPlay", value)
+                    .await
             }
             BooleanIndex::Channel2Invert => {
-                self.set_boolean_parameter("CHANnel2:INVert", value).await
+                self.scpi
+                    .set_boolean_parameter("CHANnel2:INVert", value)
+                    .await
             }
             BooleanIndex::Channel2Vernier => {
-                self.set_boolean_parameter("CHANnel2:VERNier", value).await
+                self.scpi
+                    .set_boolean_parameter("CHANnel2:VERNier", value)
+                    .await
+            }
+            //
+            BooleanIndex::Channel3BwLimit => {
+                self.scpi
+                    .set_boolean_parameter("CHANnel3:BWLimit", value)
+                    .await
+            }
+            BooleanIndex::Channel3Display => {
+                self.scpi
+                    .set_boolean_parameter("CHANnel3:DISPlay", value)
+                    .await
+            }
+            BooleanIndex::Channel3Invert => {
+                self.scpi
+                    .set_boolean_parameter("CHANnel3:INVert", value)
+                    .await
+            }
+            BooleanIndex::Channel3Vernier => {
+                self.scpi
+                    .set_boolean_parameter("CHANnel3:VERNier", value)
+                    .await
+            }
+            //
+            BooleanIndex::Channel4BwLimit => {
+                self.scpi
+                    .set_boolean_parameter("CHANnel4:BWLimit", value)
+                    .await
+            }
+            BooleanIndex::Channel4Display => {
+                self.scpi
+                    .set_boolean_parameter("CHANnel4:DISPlay", value)
+                    .await
+            }
+            BooleanIndex::Channel4Invert => {
+                self.scpi
+                    .set_boolean_parameter("CHANnel4:INVert", value)
+                    .await
+            }
+            BooleanIndex::Channel4Vernier => {
+                self.scpi
+                    .set_boolean_parameter("CHANnel4:VERNier", value)
+                    .await
             }
             //
             BooleanIndex::TimebaseWindowEnable => {
-                self.set_boolean_parameter("TIMebase:WINDow:ENABle", value)
+                self.scpi
+                    .set_boolean_parameter("TIMebase:WINDow:ENABle", value)
                     .await
             }
             //
             BooleanIndex::MeasureEnable => {
-                self.set_boolean_parameter("MEASure:ENABle", value).await
+                self.scpi
+                    .set_boolean_parameter("MEASure:ENABle", value)
+                    .await
             }
             BooleanIndex::MeasureADisplay => {
-                self.set_boolean_parameter("MEASure:ADISplay", value).await
+                self.scpi
+                    .set_boolean_parameter("MEASure:ADISplay", value)
+                    .await
             }
             BooleanIndex::MeasureGateEnable => {
-                self.set_boolean_parameter("MEASure:GATE:ENABle", value)
+                self.scpi
+                    .set_boolean_parameter("MEASure:GATE:ENABle", value)
+                    .await
+            }
+            //
+            BooleanIndex::MathDisplay => {
+                self.scpi.set_boolean_parameter("MATH:DISPlay", value).await
+            }
+            //
+            BooleanIndex::Reference1Display => {
+                self.scpi
+                    .set_boolean_parameter("REFerence1:DISPlay", value)
+                    .await
+            }
+            BooleanIndex::Reference2Display => {
+                self.scpi
+                    .set_boolean_parameter("REFerence2:DISPlay", value)
+                    .await
+            }
+            BooleanIndex::Reference3Display => {
+                self.scpi
+                    .set_boolean_parameter("REFerence3:DISPlay", value)
+                    .await
+            }
+            BooleanIndex::Reference4Display => {
+                self.scpi
+                    .set_boolean_parameter("REFerence4:DISPlay", value)
+                    .await
+            }
+            //
+            BooleanIndex::DecodeDisplay => {
+                self.scpi
+                    .set_boolean_parameter("DECode:DISPlay", value)
+                    .await
+            }
+            //
+            BooleanIndex::RecordEnable => {
+                self.scpi
+                    .set_boolean_parameter("RECord:ENABle", value)
                     .await
             }
+            BooleanIndex::RecordPlay => self.scpi.set_boolean_parameter("RECord:PLAY", value).await,
+            //
+            BooleanIndex::GeneratorOutput => {
+                self.scpi.set_boolean_parameter("DDS:SWITch", value).await
+            }
         }
     }
 }
@@ -393,12 +1184,116 @@ impl BooleanAccessorModel for DSO2C10Interface {
 // ----------------------------------------------------------------------------
 // ----------------------------------------------------------------------------
 
-#[derive(FromRepr, Debug, PartialEq)]
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
 pub enum StringIndex {
     //
     Channel1Coupling,
     Channel1Scale,
     Channel1Probe,
+    //
+    Channel2Coupling,
+    Channel2Scale,
+    Channel2Probe,
+    //
+    Channel3Coupling,
+    Channel3Scale,
+    Channel3Probe,
+    //
+    Channel4Coupling,
+    Channel4Scale,
+    Channel4Probe,
+    //
+    Channel1BwLimit,
+    Channel2BwLimit,
+    Channel3BwLimit,
+    Channel4BwLimit,
+    //
+    TimebaseMode,
+    //
+    TriggerSource,
+    TriggerSweep,
+    TriggerMode,
+    //
+    TriggerUartCondition,
+    TriggerI2cCondition,
+    TriggerSpiCondition,
+    TriggerCanCondition,
+    TriggerLinCondition,
+    //
+    MathOperator,
+    MathSourceA,
+    MathSourceB,
+    MathFftWindow,
+    MathFftUnit,
+    //
+    CursorMode,
+    CursorType,
+    CursorSource,
+    //
+    DecodeType,
+    //
+    DecodeUartSource,
+    DecodeUartParity,
+    DecodeUartStopBits,
+    DecodeUartDataBits,
+    //
+    DecodeI2cSclSource,
+    DecodeI2cSdaSource,
+    DecodeI2cAddressWidth,
+    //
+    DecodeSpiClockSource,
+    DecodeSpiMosiSource,
+    DecodeSpiMisoSource,
+    DecodeSpiCsSource,
+    DecodeSpiCpol,
+    DecodeSpiCpha,
+    DecodeSpiBitOrder,
+    //
+    DecodeCanSource,
+    DecodeCanSignal,
+    //
+    DecodeLinSource,
+    //
+    GeneratorWaveform,
+}
+
+impl DSO2C10Interface {
+    ///
+    ///
+    async fn get_channel_scale_label(&self, channel_id: usize) -> Result<String, Error> {
+        let f = self.get_channel_scale(channel_id).await?;
+        Ok(scale_label(f))
+    }
+
+    ///
+    ///
+    async fn get_channel_bandwidth_limit(&self, channel_id: usize) -> Result<String, Error> {
+        let cmd_string = format!("CHANnel{}:BWLimit?", channel_id);
+        self.scpi
+            .get_enum_parameter(cmd_string.as_bytes(), &CHANNEL_BANDWIDTH_LIMIT_CHOICES)
+            .await
+    }
+
+    ///
+    ///
+    async fn get_channel_probe_label(&self, channel_id: usize) -> Result<String, Error> {
+        let cmd_string = format!("CHANnel{}:PROBe?", channel_id);
+        let f = self.scpi.get_float_parameter(cmd_string.as_bytes()).await?;
+        Ok(ScpiNumeric::new(f).to_scpi_string())
+    }
+}
+
+/// Label of a vertical scale as listed in the scale enum (e.g. 100mV, 2V)
+///
+fn scale_label(f: f64) -> String {
+    if f < 1.0 {
+        format!(
+            "{}mV",
+            ScpiNumeric::new((f * 1000.0).round()).to_scpi_string()
+        )
+    } else {
+        format!("{}V", ScpiNumeric::new(f).to_scpi_string())
+    }
 }
 
 #[async_trait]
@@ -416,30 +1311,227 @@ impl StringAccessorModel for DSO2C10Interface {
         //
         // Perform the request
         match idx {
-            StringIndex::Channel1Coupling => self.get_string_parameter(b"CHANnel1:COUPling?").await,
-            StringIndex::Channel1Scale => {
-                let f = self.get_float_parameter(b"CHANnel1:SCALe?").await?;
-                // println!("f: {}", f);
-                match f {
-                    0.1 => Ok("100mV".to_string()),
-                    0.2 => Ok("200mV".to_string()),
-                    0.5 => Ok("500mV".to_string()),
-                    1.0 => Ok("1V".to_string()),
-                    2.0 => Ok("2V".to_string()),
-                    5.0 => Ok("5V".to_string()),
-                    10.0 => Ok("10V".to_string()),
-                    _ => Ok(f.to_string()),
-                }
+            //
+            StringIndex::Channel1Coupling => self.get_channel_coupling(1).await,
+            StringIndex::Channel1Scale => self.get_channel_scale_label(1).await,
+            StringIndex::Channel1Probe => self.get_channel_probe_label(1).await,
+            //
+            StringIndex::Channel2Coupling => self.get_channel_coupling(2).await,
+            StringIndex::Channel2Scale => self.get_channel_scale_label(2).await,
+            StringIndex::Channel2Probe => self.get_channel_probe_label(2).await,
+            //
+            StringIndex::Channel3Coupling => self.get_channel_coupling(3).await,
+            StringIndex::Channel3Scale => self.get_channel_scale_label(3).await,
+            StringIndex::Channel3Probe => self.get_channel_probe_label(3).await,
+            //
+            StringIndex::Channel4Coupling => self.get_channel_coupling(4).await,
+            StringIndex::Channel4Scale => self.get_channel_scale_label(4).await,
+            StringIndex::Channel4Probe => self.get_channel_probe_label(4).await,
+            //
+            StringIndex::Channel1BwLimit => self.get_channel_bandwidth_limit(1).await,
+            StringIndex::Channel2BwLimit => self.get_channel_bandwidth_limit(2).await,
+            StringIndex::Channel3BwLimit => self.get_channel_bandwidth_limit(3).await,
+            StringIndex::Channel4BwLimit => self.get_channel_bandwidth_limit(4).await,
+            //
+            StringIndex::TimebaseMode => {
+                self.scpi
+                    .get_enum_parameter(b"TIMebase:MODE?", &TIMEBASE_MODE_CHOICES)
+                    .await
             }
-            StringIndex::Channel1Probe => {
-                let f = self.get_float_parameter(b"CHANnel1:PROBe?").await?;
-                match f {
-                    1.0 => Ok("1".to_string()),
-                    10.0 => Ok("10".to_string()),
-                    100.0 => Ok("100".to_string()),
-                    1000.0 => Ok("1000".to_string()),
-                    _ => Ok(f.to_string()),
-                }
+            //
+            StringIndex::TriggerSource => {
+                self.scpi
+                    .get_enum_parameter(b"TRIGger:EDGE:SOURce?", &TRIGGER_SOURCE_CHOICES)
+                    .await
+            }
+            StringIndex::TriggerSweep => {
+                self.scpi
+                    .get_enum_parameter(b"TRIGger:SWEep?", &TRIGGER_SWEEP_CHOICES)
+                    .await
+            }
+            StringIndex::TriggerMode => {
+                self.scpi
+                    .get_enum_parameter(b"TRIGger:MODE?", &TRIGGER_MODE_CHOICES)
+                    .await
+            }
+            //
+            StringIndex::TriggerUartCondition => {
+                self.scpi
+                    .get_enum_parameter(b"TRIGger:UART:CONDition?", &TRIGGER_UART_CONDITION_CHOICES)
+                    .await
+            }
+            StringIndex::TriggerI2cCondition => {
+                self.scpi
+                    .get_enum_parameter(b"TRIGger:IIC:CONDition?", &TRIGGER_I2C_CONDITION_CHOICES)
+                    .await
+            }
+            StringIndex::TriggerSpiCondition => {
+                self.scpi
+                    .get_enum_parameter(b"TRIGger:SPI:CONDition?", &TRIGGER_SPI_CONDITION_CHOICES)
+                    .await
+            }
+            StringIndex::TriggerCanCondition => {
+                self.scpi
+                    .get_enum_parameter(b"TRIGger:CAN:CONDition?", &TRIGGER_CAN_CONDITION_CHOICES)
+                    .await
+            }
+            StringIndex::TriggerLinCondition => {
+                self.scpi
+                    .get_enum_parameter(b"TRIGger:LIN:CONDition?", &TRIGGER_LIN_CONDITION_CHOICES)
+                    .await
+            }
+            //
+            StringIndex::MathOperator => {
+                self.scpi
+                    .get_enum_parameter(b"MATH:OPERator?", &MATH_OPERATOR_CHOICES)
+                    .await
+            }
+            StringIndex::MathSourceA => {
+                self.scpi
+                    .get_enum_parameter(b"MATH:SOURce1?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            StringIndex::MathSourceB => {
+                self.scpi
+                    .get_enum_parameter(b"MATH:SOURce2?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            StringIndex::MathFftWindow => {
+                self.scpi
+                    .get_enum_parameter(b"MATH:FFT:WINDow?", &MATH_FFT_WINDOW_CHOICES)
+                    .await
+            }
+            StringIndex::MathFftUnit => {
+                self.scpi
+                    .get_enum_parameter(b"MATH:FFT:UNIT?", &MATH_FFT_UNIT_CHOICES)
+                    .await
+            }
+            //
+            StringIndex::CursorMode => {
+                self.scpi
+                    .get_enum_parameter(b"CURSor:MODE?", &CURSOR_MODE_CHOICES)
+                    .await
+            }
+            StringIndex::CursorType => {
+                self.scpi
+                    .get_enum_parameter(b"CURSor:MANual:TYPE?", &CURSOR_TYPE_CHOICES)
+                    .await
+            }
+            StringIndex::CursorSource => {
+                self.scpi
+                    .get_enum_parameter(b"CURSor:MANual:SOURce?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            //
+            StringIndex::DecodeType => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:TYPE?", &DECODE_TYPE_CHOICES)
+                    .await
+            }
+            //
+            StringIndex::DecodeUartSource => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:UART:SOURce?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            StringIndex::DecodeUartParity => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:UART:PARity?", &DECODE_UART_PARITY_CHOICES)
+                    .await
+            }
+            StringIndex::DecodeUartStopBits => {
+                self.scpi
+                    .get_numeric_enum_parameter(
+                        b"DECode:UART:STOP?",
+                        &DECODE_UART_STOP_BITS_CHOICES,
+                    )
+                    .await
+            }
+            StringIndex::DecodeUartDataBits => {
+                self.scpi
+                    .get_numeric_enum_parameter(
+                        b"DECode:UART:WIDTh?",
+                        &DECODE_UART_DATA_BITS_CHOICES,
+                    )
+                    .await
+            }
+            //
+            StringIndex::DecodeI2cSclSource => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:IIC:SCL?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            StringIndex::DecodeI2cSdaSource => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:IIC:SDA?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            StringIndex::DecodeI2cAddressWidth => {
+                self.scpi
+                    .get_numeric_enum_parameter(
+                        b"DECode:IIC:AWIDth?",
+                        &DECODE_I2C_ADDRESS_WIDTH_CHOICES,
+                    )
+                    .await
+            }
+            //
+            StringIndex::DecodeSpiClockSource => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:SPI:CLK?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            StringIndex::DecodeSpiMosiSource => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:SPI:MOSI?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            StringIndex::DecodeSpiMisoSource => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:SPI:MISO?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            StringIndex::DecodeSpiCsSource => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:SPI:CS?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            StringIndex::DecodeSpiCpol => {
+                self.scpi
+                    .get_numeric_enum_parameter(b"DECode:SPI:CPOL?", &DECODE_SPI_MODE_CHOICES)
+                    .await
+            }
+            StringIndex::DecodeSpiCpha => {
+                self.scpi
+                    .get_numeric_enum_parameter(b"DECode:SPI:CPHA?", &DECODE_SPI_MODE_CHOICES)
+                    .await
+            }
+            StringIndex::DecodeSpiBitOrder => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:SPI:ORDer?", &DECODE_SPI_BIT_ORDER_CHOICES)
+                    .await
+            }
+            //
+            StringIndex::DecodeCanSource => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:CAN:SOURce?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            StringIndex::DecodeCanSignal => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:CAN:SIGNal?", &DECODE_CAN_SIGNAL_CHOICES)
+                    .await
+            }
+            //
+            StringIndex::DecodeLinSource => {
+                self.scpi
+                    .get_enum_parameter(b"DECode:LIN:SOURce?", &CHANNEL_SOURCE_CHOICES)
+                    .await
+            }
+            //
+            StringIndex::GeneratorWaveform => {
+                self.scpi
+                    .get_enum_parameter(b"DDS:TYPE?", &GENERATOR_WAVEFORM_CHOICES)
+                    .await
             }
         }
     }
@@ -455,11 +1547,719 @@ impl StringAccessorModel for DSO2C10Interface {
         //
         // Perform the request
         match idx {
+            //
             StringIndex::Channel1Coupling => {
-                self.set_string_parameter("CHANnel1:COUPling", value).await
+                self.scpi
+                    .set_string_parameter("CHANnel1:COUPling", value)
+                    .await
+            }
+            StringIndex::Channel1Scale => {
+                self.scpi
+                    .set_string_parameter("CHANnel1:SCALe", value)
+                    .await
+            }
+            StringIndex::Channel1Probe => {
+                self.scpi
+                    .set_string_parameter("CHANnel1:PROBe", value)
+                    .await
+            }
+            //
+            StringIndex::Channel2Coupling => {
+                self.scpi
+                    .set_string_parameter("CHANnel2:COUPling", value)
+                    .await
+            }
+            StringIndex::Channel2Scale => {
+                self.scpi
+                    .set_string_parameter("CHANnel2:SCALe", value)
+                    .await
+            }
+            StringIndex::Channel2Probe => {
+                self.scpi
+                    .set_string_parameter("CHANnel2:PROBe", value)
+                    .await
+            }
+            //
+            StringIndex::Channel3Coupling => {
+                self.scpi
+                    .set_string_parameter("CHANnel3:COUPling", value)
+                    .await
+            }
+            StringIndex::Channel3Scale => {
+                self.scpi
+                    .set_string_parameter("CHANnel3:SCALe", value)
+                    .await
+            }
+            StringIndex::Channel3Probe => {
+                self.scpi
+                    .set_string_parameter("CHANnel3:PROBe", value)
+                    .await
+            }
+            //
+            StringIndex::Channel4Coupling => {
+                self.scpi
+                    .set_string_parameter("CHANnel4:COUPling", value)
+                    .await
+            }
+            StringIndex::Channel4Scale => {
+                self.scpi
+                    .set_string_parameter("CHANnel4:SCALe", value)
+                    .await
+            }
+            StringIndex::Channel4Probe => {
+                self.scpi
+                    .set_string_parameter("CHANnel4:PROBe", value)
+                    .await
+            }
+            //
+            StringIndex::Channel1BwLimit => {
+                self.scpi
+                    .set_string_parameter("CHANnel1:BWLimit", value)
+                    .await
+            }
+            StringIndex::Channel2BwLimit => {
+                self.scpi
+                    .set_string_parameter("CHANnel2:BWLimit", value)
+                    .await
+            }
+            StringIndex::Channel3BwLimit => {
+                self.scpi
+                    .set_string_parameter("CHANnel3:BWLimit", value)
+                    .await
+            }
+            StringIndex::Channel4BwLimit => {
+                self.scpi
+                    .set_string_parameter("CHANnel4:BWLimit", value)
+                    .await
+            }
+            //
+            StringIndex::TimebaseMode => {
+                self.scpi.set_string_parameter("TIMebase:MODE", value).await
+            }
+            //
+            StringIndex::TriggerSource => {
+                self.scpi
+                    .set_string_parameter("TRIGger:EDGE:SOURce", value)
+                    .await
+            }
+            StringIndex::TriggerSweep => {
+                self.scpi.set_string_parameter("TRIGger:SWEep", value).await
+            }
+            StringIndex::TriggerMode => self.set_trigger_mode(value).await,
+            //
+            StringIndex::TriggerUartCondition => {
+                self.scpi
+                    .set_string_parameter("TRIGger:UART:CONDition", value)
+                    .await
+            }
+            StringIndex::TriggerI2cCondition => {
+                self.scpi
+                    .set_string_parameter("TRIGger:IIC:CONDition", value)
+                    .await
+            }
+            StringIndex::TriggerSpiCondition => {
+                self.scpi
+                    .set_string_parameter("TRIGger:SPI:CONDition", value)
+                    .await
+            }
+            StringIndex::TriggerCanCondition => {
+                self.scpi
+                    .set_string_parameter("TRIGger:CAN:CONDition", value)
+                    .await
+            }
+            StringIndex::TriggerLinCondition => {
+                self.scpi
+                    .set_string_parameter("TRIGger:LIN:CONDition", value)
+                    .await
+            }
+            //
+            StringIndex::MathOperator => {
+                self.scpi.set_string_parameter("MATH:OPERator", value).await
+            }
+            StringIndex::MathSourceA => self.scpi.set_string_parameter("MATH:SOURce1", value).await,
+            StringIndex::MathSourceB => self.scpi.set_string_parameter("MATH:SOURce2", value).await,
+            StringIndex::MathFftWindow => {
+                self.scpi
+                    .set_string_parameter("MATH:FFT:WINDow", value)
+                    .await
+            }
+            StringIndex::MathFftUnit => {
+                self.scpi.set_string_parameter("MATH:FFT:UNIT", value).await
+            }
+            //
+            StringIndex::CursorMode => self.scpi.set_string_parameter("CURSor:MODE", value).await,
+            StringIndex::CursorType => {
+                self.scpi
+                    .set_string_parameter("CURSor:MANual:TYPE", value)
+                    .await
+            }
+            StringIndex::CursorSource => {
+                self.scpi
+                    .set_string_parameter("CURSor:MANual:SOURce", value)
+                    .await
+            }
+            //
+            StringIndex::DecodeType => self.scpi.set_string_parameter("DECode:TYPE", value).await,
+            //
+            StringIndex::DecodeUartSource => {
+                self.scpi
+                    .set_string_parameter("DECode:UART:SOURce", value)
+                    .await
+            }
+            StringIndex::DecodeUartParity => {
+                self.scpi
+                    .set_string_parameter("DECode:UART:PARity", value)
+                    .await
+            }
+            StringIndex::DecodeUartStopBits => {
+                self.scpi
+                    .set_string_parameter("DECode:UART:STOP", value)
+                    .await
+            }
+            StringIndex::DecodeUartDataBits => {
+                self.scpi
+                    .set_string_parameter("DECode:UART:WIDTh", value)
+                    .await
+            }
+            //
+            StringIndex::DecodeI2cSclSource => {
+                self.scpi
+                    .set_string_parameter("DECode:IIC:SCL", value)
+                    .await
+            }
+            StringIndex::DecodeI2cSdaSource => {
+                self.scpi
+                    .set_string_parameter("DECode:IIC:SDA", value)
+                    .await
+            }
+            StringIndex::DecodeI2cAddressWidth => {
+                self.scpi
+                    .set_string_parameter("DECode:IIC:AWIDth", value)
+                    .await
+            }
+            //
+            StringIndex::DecodeSpiClockSource => {
+                self.scpi
+                    .set_string_parameter("DECode:SPI:CLK", value)
+                    .await
+            }
+            StringIndex::DecodeSpiMosiSource => {
+                self.scpi
+                    .set_string_parameter("DECode:SPI:MOSI", value)
+                    .await
+            }
+            StringIndex::DecodeSpiMisoSource => {
+                self.scpi
+                    .set_string_parameter("DECode:SPI:MISO", value)
+                    .await
+            }
+            StringIndex::DecodeSpiCsSource => {
+                self.scpi.set_string_parameter("DECode:SPI:CS", value).await
+            }
+            StringIndex::DecodeSpiCpol => {
+                self.scpi
+                    .set_string_parameter("DECode:SPI:CPOL", value)
+                    .await
+            }
+            StringIndex::DecodeSpiCpha => {
+                self.scpi
+                    .set_string_parameter("DECode:SPI:CPHA", value)
+                    .await
+            }
+            StringIndex::DecodeSpiBitOrder => {
+                self.scpi
+                    .set_string_parameter("DECode:SPI:ORDer", value)
+                    .await
+            }
+            //
+            StringIndex::DecodeCanSource => {
+                self.scpi
+                    .set_string_parameter("DECode:CAN:SOURce", value)
+                    .await
+            }
+            StringIndex::DecodeCanSignal => {
+                self.scpi
+                    .set_string_parameter("DECode:CAN:SIGNal", value)
+                    .await
+            }
+            //
+            StringIndex::DecodeLinSource => {
+                self.scpi
+                    .set_string_parameter("DECode:LIN:SOURce", value)
+                    .await
+            }
+            //
+            StringIndex::GeneratorWaveform => {
+                self.scpi.set_string_parameter("DDS:TYPE", value).await
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum FloatIndex {
+    //
+    Channel1Offset,
+    Channel2Offset,
+    Channel3Offset,
+    Channel4Offset,
+    //
+    TimebaseScale,
+    TimebasePosition,
+    //
+    TriggerLevel,
+    //
+    TriggerUartData,
+    TriggerUartDataMask,
+    //
+    TriggerI2cAddress,
+    TriggerI2cAddressMask,
+    TriggerI2cData,
+    TriggerI2cDataMask,
+    //
+    TriggerSpiData,
+    TriggerSpiDataMask,
+    //
+    TriggerCanId,
+    TriggerCanData,
+    TriggerCanDataMask,
+    //
+    TriggerLinId,
+    TriggerLinData,
+    TriggerLinDataMask,
+    //
+    MathScale,
+    MathOffset,
+    MathFftCenter,
+    MathFftSpan,
+    //
+    CursorATime,
+    CursorBTime,
+    CursorAVoltage,
+    CursorBVoltage,
+    //
+    DecodeUartThreshold,
+    DecodeUartBaud,
+    //
+    DecodeI2cSclThreshold,
+    DecodeI2cSdaThreshold,
+    //
+    DecodeSpiClockThreshold,
+    DecodeSpiMosiThreshold,
+    DecodeSpiMisoThreshold,
+    DecodeSpiCsThreshold,
+    //
+    DecodeCanThreshold,
+    DecodeCanBaud,
+    //
+    DecodeLinThreshold,
+    DecodeLinBaud,
+    //
+    RecordFrames,
+    RecordInterval,
+    RecordPlayFrame,
+    //
+    GeneratorFrequency,
+    GeneratorAmplitude,
+    GeneratorOffset,
+    GeneratorDuty,
+}
+
+#[async_trait]
+///
+///
+impl FloatAccessorModel for DSO2C10Interface {
+    ///
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error> {
+        //
+        // Get the index
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            //
+            FloatIndex::Channel1Offset => self.get_channel_offset(1).await,
+            FloatIndex::Channel2Offset => self.get_channel_offset(2).await,
+            FloatIndex::Channel3Offset => self.get_channel_offset(3).await,
+            FloatIndex::Channel4Offset => self.get_channel_offset(4).await,
+            //
+            FloatIndex::TimebaseScale => self.scpi.get_float_parameter(b"TIMebase:SCALe?").await,
+            FloatIndex::TimebasePosition => {
+                self.scpi.get_float_parameter(b"TIMebase:POSition?").await
+            }
+            //
+            FloatIndex::TriggerLevel => self.scpi.get_float_parameter(b"TRIGger:EDGE:LEVel?").await,
+            //
+            FloatIndex::TriggerUartData => {
+                self.scpi.get_float_parameter(b"TRIGger:UART:DATA?").await
+            }
+            FloatIndex::TriggerUartDataMask => {
+                self.scpi.get_float_parameter(b"TRIGger:UART:DMASk?").await
+            }
+            FloatIndex::TriggerI2cAddress => {
+                self.scpi.get_float_parameter(b"TRIGger:IIC:ADDRess?").await
+            }
+            FloatIndex::TriggerI2cAddressMask => {
+                self.scpi.get_float_parameter(b"TRIGger:IIC:AMASk?").await
+            }
+            FloatIndex::TriggerI2cData => self.scpi.get_float_parameter(b"TRIGger:IIC:DATA?").await,
+            FloatIndex::TriggerI2cDataMask => {
+                self.scpi.get_float_parameter(b"TRIGger:IIC:DMASk?").await
+            }
+            FloatIndex::TriggerSpiData => self.scpi.get_float_parameter(b"TRIGger:SPI:DATA?").await,
+            FloatIndex::TriggerSpiDataMask => {
+                self.scpi.get_float_parameter(b"TRIGger:SPI:DMASk?").await
+            }
+            FloatIndex::TriggerCanId => self.scpi.get_float_parameter(b"TRIGger:CAN:ID?").await,
+            FloatIndex::TriggerCanData => self.scpi.get_float_parameter(b"TRIGger:CAN:DATA?").await,
+            FloatIndex::TriggerCanDataMask => {
+                self.scpi.get_float_parameter(b"TRIGger:CAN:DMASk?").await
+            }
+            FloatIndex::TriggerLinId => self.scpi.get_float_parameter(b"TRIGger:LIN:ID?").await,
+            FloatIndex::TriggerLinData => self.scpi.get_float_parameter(b"TRIGger:LIN:DATA?").await,
+            FloatIndex::TriggerLinDataMask => {
+                self.scpi.get_float_parameter(b"TRIGger:LIN:DMASk?").await
+            }
+            //
+            FloatIndex::MathScale => self.scpi.get_float_parameter(b"MATH:SCALe?").await,
+            FloatIndex::MathOffset => self.scpi.get_float_parameter(b"MATH:OFFSet?").await,
+            FloatIndex::MathFftCenter => self.scpi.get_float_parameter(b"MATH:FFT:HCENter?").await,
+            FloatIndex::MathFftSpan => self.scpi.get_float_parameter(b"MATH:FFT:HSPan?").await,
+            //
+            FloatIndex::CursorATime => self.get_cursor_time('A').await,
+            FloatIndex::CursorBTime => self.get_cursor_time('B').await,
+            FloatIndex::CursorAVoltage => self.get_cursor_voltage('A').await,
+            FloatIndex::CursorBVoltage => self.get_cursor_voltage('B').await,
+            //
+            FloatIndex::DecodeUartThreshold => {
+                self.scpi
+                    .get_float_parameter(b"DECode:UART:THReshold?")
+                    .await
+            }
+            FloatIndex::DecodeUartBaud => self.scpi.get_float_parameter(b"DECode:UART:BAUD?").await,
+            //
+            FloatIndex::DecodeI2cSclThreshold => {
+                self.scpi
+                    .get_float_parameter(b"DECode:IIC:SCLThreshold?")
+                    .await
+            }
+            FloatIndex::DecodeI2cSdaThreshold => {
+                self.scpi
+                    .get_float_parameter(b"DECode:IIC:SDAThreshold?")
+                    .await
+            }
+            //
+            FloatIndex::DecodeSpiClockThreshold => {
+                self.scpi
+                    .get_float_parameter(b"DECode:SPI:CLKThreshold?")
+                    .await
+            }
+            FloatIndex::DecodeSpiMosiThreshold => {
+                self.scpi
+                    .get_float_parameter(b"DECode:SPI:MOSIThreshold?")
+                    .await
+            }
+            FloatIndex::DecodeSpiMisoThreshold => {
+                self.scpi
+                    .get_float_parameter(b"DECode:SPI:MISOThreshold?")
+                    .await
+            }
+            FloatIndex::DecodeSpiCsThreshold => {
+                self.scpi
+                    .get_float_parameter(b"DECode:SPI:CSThreshold?")
+                    .await
+            }
+            //
+            FloatIndex::DecodeCanThreshold => {
+                self.scpi
+                    .get_float_parameter(b"DECode:CAN:THReshold?")
+                    .await
+            }
+            FloatIndex::DecodeCanBaud => self.scpi.get_float_parameter(b"DECode:CAN:BAUD?").await,
+            //
+            FloatIndex::DecodeLinThreshold => {
+                self.scpi
+                    .get_float_parameter(b"DECode:LIN:THReshold?")
+                    .await
+            }
+            FloatIndex::DecodeLinBaud => self.scpi.get_float_parameter(b"DECode:LIN:BAUD?").await,
+            //
+            FloatIndex::RecordFrames => self.scpi.get_float_parameter(b"RECord:FRAMes?").await,
+            FloatIndex::RecordInterval => self.scpi.get_float_parameter(b"RECord:INTerval?").await,
+            FloatIndex::RecordPlayFrame => {
+                self.scpi.get_float_parameter(b"RECord:PLAY:FRAMe?").await
+            }
+            //
+            FloatIndex::GeneratorFrequency => self.scpi.get_float_parameter(b"DDS:FREQ?").await,
+            FloatIndex::GeneratorAmplitude => self.scpi.get_float_parameter(b"DDS:AMP?").await,
+            FloatIndex::GeneratorOffset => self.scpi.get_float_parameter(b"DDS:OFFSet?").await,
+            FloatIndex::GeneratorDuty => self.scpi.get_float_parameter(b"DDS:DUTY?").await,
+        }
+    }
+
+    ///
+    ///
+    async fn set_float_at(&mut self, index: usize, value: f64) -> Result<(), Error> {
+        //
+        // Get the index
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            //
+            FloatIndex::Channel1Offset => self.set_channel_offset(1, value).await,
+            FloatIndex::Channel2Offset => self.set_channel_offset(2, value).await,
+            FloatIndex::Channel3Offset => self.set_channel_offset(3, value).await,
+            FloatIndex::Channel4Offset => self.set_channel_offset(4, value).await,
+            //
+            FloatIndex::TimebaseScale => {
+                self.scpi.set_float_parameter("TIMebase:SCALe", value).await
+            }
+            FloatIndex::TimebasePosition => {
+                self.scpi
+                    .set_float_parameter("TIMebase:POSition", value)
+                    .await
+            }
+            //
+            FloatIndex::TriggerLevel => {
+                self.scpi
+                    .set_float_parameter("TRIGger:EDGE:LEVel", value)
+                    .await
+            }
+            //
+            FloatIndex::TriggerUartData => {
+                self.set_trigger_bus_value("TRIGger:UART:DATA", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerUartDataMask => {
+                self.set_trigger_bus_value("TRIGger:UART:DMASk", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerI2cAddress => {
+                self.set_trigger_bus_value("TRIGger:IIC:ADDRess", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerI2cAddressMask => {
+                self.set_trigger_bus_value("TRIGger:IIC:AMASk", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerI2cData => {
+                self.set_trigger_bus_value("TRIGger:IIC:DATA", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerI2cDataMask => {
+                self.set_trigger_bus_value("TRIGger:IIC:DMASk", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerSpiData => {
+                self.set_trigger_bus_value("TRIGger:SPI:DATA", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerSpiDataMask => {
+                self.set_trigger_bus_value("TRIGger:SPI:DMASk", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerCanId => {
+                self.set_trigger_bus_value("TRIGger:CAN:ID", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerCanData => {
+                self.set_trigger_bus_value("TRIGger:CAN:DATA", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerCanDataMask => {
+                self.set_trigger_bus_value("TRIGger:CAN:DMASk", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerLinId => {
+                self.set_trigger_bus_value("TRIGger:LIN:ID", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerLinData => {
+                self.set_trigger_bus_value("TRIGger:LIN:DATA", idx, value)
+                    .await
+            }
+            FloatIndex::TriggerLinDataMask => {
+                self.set_trigger_bus_value("TRIGger:LIN:DMASk", idx, value)
+                    .await
+            }
+            //
+            FloatIndex::MathScale => self.scpi.set_float_parameter("MATH:SCALe", value).await,
+            FloatIndex::MathOffset => self.scpi.set_float_parameter("MATH:OFFSet", value).await,
+            FloatIndex::MathFftCenter => {
+                self.scpi
+                    .set_float_parameter("MATH:FFT:HCENter", value)
+                    .await
+            }
+            FloatIndex::MathFftSpan => self.scpi.set_float_parameter("MATH:FFT:HSPan", value).await,
+            //
+            FloatIndex::CursorATime => self.set_cursor_time('A', value).await,
+            FloatIndex::CursorBTime => self.set_cursor_time('B', value).await,
+            FloatIndex::CursorAVoltage => self.set_cursor_voltage('A', value).await,
+            FloatIndex::CursorBVoltage => self.set_cursor_voltage('B', value).await,
+            //
+            FloatIndex::DecodeUartThreshold => {
+                self.scpi
+                    .set_float_parameter("DECode:UART:THReshold", value)
+                    .await
+            }
+            FloatIndex::DecodeUartBaud => {
+                self.scpi
+                    .set_float_parameter("DECode:UART:BAUD", value)
+                    .await
+            }
+            //
+            FloatIndex::DecodeI2cSclThreshold => {
+                self.scpi
+                    .set_float_parameter("DECode:IIC:SCLThreshold", value)
+                    .await
+            }
+            FloatIndex::DecodeI2cSdaThreshold => {
+                self.scpi
+                    .set_float_parameter("DECode:IIC:SDAThreshold", value)
+                    .await
+            }
+            //
+            FloatIndex::DecodeSpiClockThreshold => {
+                self.scpi
+                    .set_float_parameter("DECode:SPI:CLKThreshold", value)
+                    .await
+            }
+            FloatIndex::DecodeSpiMosiThreshold => {
+                self.scpi
+                    .set_float_parameter("DECode:SPI:MOSIThreshold", value)
+                    .await
+            }
+            FloatIndex::DecodeSpiMisoThreshold => {
+                self.scpi
+                    .set_float_parameter("DECode:SPI:MISOThreshold", value)
+                    .await
+            }
+            FloatIndex::DecodeSpiCsThreshold => {
+                self.scpi
+                    .set_float_parameter("DECode:SPI:CSThreshold", value)
+                    .await
+            }
+            //
+            FloatIndex::DecodeCanThreshold => {
+                self.scpi
+                    .set_float_parameter("DECode:CAN:THReshold", value)
+                    .await
+            }
+            FloatIndex::DecodeCanBaud => {
+                self.scpi
+                    .set_float_parameter("DECode:CAN:BAUD", value)
+                    .await
+            }
+            //
+            FloatIndex::DecodeLinThreshold => {
+                self.scpi
+                    .set_float_parameter("DECode:LIN:THReshold", value)
+                    .await
+            }
+            FloatIndex::DecodeLinBaud => {
+                self.scpi
+                    .set_float_parameter("DECode:LIN:BAUD", value)
+                    .await
+            }
+            //
+            FloatIndex::RecordFrames => {
+                self.set_record_frame_parameter("RECord:FRAMes", value)
+                    .await
+            }
+            FloatIndex::RecordInterval => {
+                self.scpi
+                    .set_float_parameter("RECord:INTerval", value)
+                    .await
+            }
+            FloatIndex::RecordPlayFrame => {
+                self.set_record_frame_parameter("RECord:PLAY:FRAMe", value)
+                    .await
+            }
+            //
+            FloatIndex::GeneratorFrequency => {
+                self.scpi.set_float_parameter("DDS:FREQ", value).await
+            }
+            FloatIndex::GeneratorAmplitude => self.scpi.set_float_parameter("DDS:AMP", value).await,
+            FloatIndex::GeneratorOffset => self.scpi.set_float_parameter("DDS:OFFSet", value).await,
+            FloatIndex::GeneratorDuty => self.scpi.set_float_parameter("DDS:DUTY", value).await,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum WaveformIndex {
+    //
+    Channel1,
+    Channel2,
+    Channel3,
+    Channel4,
+    //
+    Math,
+}
+
+#[async_trait]
+///
+///
+impl WaveformAccessorModel for DSO2C10Interface {
+    ///
+    ///
+    async fn get_waveform_at(&mut self, index: usize) -> Result<Waveform, Error> {
+        //
+        // Get the index
+        let idx = WaveformIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            WaveformIndex::Channel1 => self.get_channel_waveform(1).await,
+            WaveformIndex::Channel2 => self.get_channel_waveform(2).await,
+            WaveformIndex::Channel3 => self.get_channel_waveform(3).await,
+            WaveformIndex::Channel4 => self.get_channel_waveform(4).await,
+            //
+            WaveformIndex::Math => self.get_math_waveform().await,
+        }
+    }
+
+    /// Channels are read from the same acquisition, math is read afterwards
+    ///
+    async fn get_waveforms_at(&mut self, indexes: &[usize]) -> Result<Vec<Waveform>, Error> {
+        let mut channel_ids = Vec::with_capacity(indexes.len());
+        for index in indexes {
+            let idx = WaveformIndex::from_repr(*index)
+                .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+            match idx {
+                WaveformIndex::Channel1 => channel_ids.push(Some(1)),
+                WaveformIndex::Channel2 => channel_ids.push(Some(2)),
+                WaveformIndex::Channel3 => channel_ids.push(Some(3)),
+                WaveformIndex::Channel4 => channel_ids.push(Some(4)),
+                WaveformIndex::Math => channel_ids.push(None),
+            }
+        }
+
+        let channels: Vec<usize> = channel_ids.iter().flatten().cloned().collect();
+        let mut waveforms = self.get_channel_waveforms(&channels).await?.into_iter();
+        let mut result = Vec::with_capacity(channel_ids.len());
+        for channel_id in channel_ids {
+            match channel_id {
+                Some(_) => result.extend(waveforms.next()),
+                None => result.push(self.get_math_waveform().await?),
             }
-            StringIndex::Channel1Scale => self.set_string_parameter("CHANnel1:SCALe", value).await,
-            StringIndex::Channel1Probe => self.set_string_parameter("CHANnel1:PROBe", value).await,
         }
+        Ok(result)
     }
 }
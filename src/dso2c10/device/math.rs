@@ -0,0 +1,194 @@
+use tokio::sync::Mutex;
+
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::{log_debug_mount_end, log_debug_mount_start, Container, Error};
+use std::sync::Arc;
+
+use crate::dso2c10::device::interface::BooleanIndex;
+use crate::dso2c10::device::interface::FloatIndex;
+use crate::dso2c10::device::interface::StringIndex;
+use crate::dso2c10::device::interface::WaveformIndex;
+use crate::dso2c10::device::interface::MATH_FFT_UNIT_CHOICES;
+use crate::dso2c10::device::interface::MATH_FFT_WINDOW_CHOICES;
+use crate::dso2c10::device::interface::MATH_OPERATOR_CHOICES;
+use crate::dso2c10::Profile;
+use crate::si as std_att_si;
+use crate::si::SiFormat;
+use crate::waveform as std_att_waveform;
+
+use super::interface::DSO2C10Interface;
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+) -> Result<(), Error> {
+    let class_math = parent.create_class("math").finish().await;
+
+    let logger = class_math.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    //
+    std_att_boolean::mount(
+        class_math.clone(),
+        interface.clone(),
+        BooleanIndex::MathDisplay as usize,
+        "display",
+        "Display the math channel",
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_math.clone(),
+        interface.clone(),
+        StringIndex::MathOperator as usize,
+        "operator",
+        "
+    * ADD, SUBTract, MULTiply, DIVision: source_a <op> source_b
+    * FFT: spectrum of source_a, see 'fft'
+        ",
+        MATH_OPERATOR_CHOICES.to_vec(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_math.clone(),
+        interface.clone(),
+        StringIndex::MathSourceA as usize,
+        "source_a",
+        "First operand of the operator, source of the FFT",
+        profile.channel_sources(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_math.clone(),
+        interface.clone(),
+        StringIndex::MathSourceB as usize,
+        "source_b",
+        "Second operand of the operator, unused by the FFT",
+        profile.channel_sources(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_math.clone(),
+        interface.clone(),
+        FloatIndex::MathScale as usize,
+        "scale",
+        "Vertical scale of the math channel, units per division",
+        SiFormat::new("V", 0.000001, 10000.0, 6),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_math.clone(),
+        interface.clone(),
+        FloatIndex::MathOffset as usize,
+        "offset",
+        "Vertical offset of the math channel",
+        SiFormat::new("V", -100000.0, 100000.0, 6),
+    )
+    .await?;
+
+    mount_fft(class_math.clone(), interface.clone()).await?;
+
+    //
+    //
+    std_att_waveform::mount(
+        class_math.clone(),
+        interface.clone(),
+        WaveformIndex::Math as usize,
+        "waveform",
+        "Write any value to read the math waveform
+    With the FFT operator, x is in Hz and the samples are in the FFT unit
+        ",
+    )
+    .await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+/// Settings only used by the FFT operator
+///
+async fn mount_fft<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    let class_fft = parent.create_class("fft").finish().await;
+
+    //
+    //
+    std_att_enum::mount(
+        class_fft.clone(),
+        interface.clone(),
+        StringIndex::MathFftWindow as usize,
+        "window",
+        "
+    * RECTangle: best frequency resolution, for transients
+    * HANNing, HAMMing: good compromise for periodic signals
+    * BLACkman: best amplitude resolution
+    * FLATtop: accurate amplitude of the harmonics
+        ",
+        MATH_FFT_WINDOW_CHOICES.to_vec(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_fft.clone(),
+        interface.clone(),
+        StringIndex::MathFftUnit as usize,
+        "unit",
+        "
+    * VRMS: linear amplitude
+    * DB: logarithmic amplitude, dBV
+        ",
+        MATH_FFT_UNIT_CHOICES.to_vec(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_fft.clone(),
+        interface.clone(),
+        FloatIndex::MathFftCenter as usize,
+        "center",
+        "Frequency at the center of the screen",
+        SiFormat::new("Hz", 0.0, 1_000_000_000.0, 1),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_fft.clone(),
+        interface.clone(),
+        FloatIndex::MathFftSpan as usize,
+        "span",
+        "Frequency range displayed on the screen",
+        SiFormat::new("Hz", 0.0, 1_000_000_000.0, 1),
+    )
+    .await?;
+
+    Ok(())
+}
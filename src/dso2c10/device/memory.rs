@@ -0,0 +1,247 @@
+use async_trait::async_trait;
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::std::attribute::boolean::BooleanAccessorModel;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer,
+};
+use strum_macros::FromRepr;
+use tokio::sync::Mutex;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::dso2c10::device::interface::DSO2C10Interface;
+use crate::dso2c10::Profile;
+use crate::dso2c10::ReadoutProgress;
+use crate::scpi::ScpiString;
+use crate::si as std_att_si;
+use crate::si::{FloatAccessorModel, SiFormat};
+
+/// Refresh period of the progress attributes
+///
+const PROGRESS_PERIOD: Duration = Duration::from_millis(250);
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum BooleanIndex {
+    //
+    Cancel,
+}
+
+#[async_trait]
+///
+///
+impl BooleanAccessorModel for ReadoutProgress {
+    ///
+    ///
+    async fn get_boolean_at(&mut self, index: usize) -> Result<bool, Error> {
+        match BooleanIndex::from_repr(index) {
+            Some(BooleanIndex::Cancel) => Ok(self.cancel_requested),
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+
+    ///
+    ///
+    async fn set_boolean_at(&mut self, index: usize, value: bool) -> Result<(), Error> {
+        match BooleanIndex::from_repr(index) {
+            Some(BooleanIndex::Cancel) => {
+                self.cancel_requested = value;
+                Ok(())
+            }
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum FloatIndex {
+    //
+    Progress,
+    PointsRead,
+    PointsTotal,
+}
+
+#[async_trait]
+///
+///
+impl FloatAccessorModel for ReadoutProgress {
+    ///
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error> {
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        Ok(match idx {
+            FloatIndex::Progress => self.ratio() * 100.0,
+            FloatIndex::PointsRead => self.points_read as f64,
+            FloatIndex::PointsTotal => self.points_total as f64,
+        })
+    }
+
+    ///
+    ///
+    async fn set_float_at(&mut self, _index: usize, _value: f64) -> Result<(), Error> {
+        Err(Error::InvalidArgument("Read only value".to_string()))
+    }
+}
+
+/// Mount the readout of the whole acquisition memory
+///
+/// The screen waveforms only hold the displayed points, this readout returns
+/// every point of the memory depth in chunks.
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+) -> Result<(), Error> {
+    let mut class_memory = parent.create_class("memory").finish().await;
+
+    let logger = class_memory.logger().clone();
+    log_debug_mount_start!(logger);
+
+    let progress = Arc::new(Mutex::new(ReadoutProgress::default()));
+
+    //
+    //
+    let att_waveform = class_memory
+        .create_attribute("waveform")
+        .with_rw()
+        .with_info(
+            "Write a channel (e.g. \"CHANnel1\") to read its whole acquisition memory
+
+The acquisition is stopped during the readout and restarted afterwards.
+The waveform is published with its source:
+    { \"source\": \"CHANnel1\", \"unit\": \"V\", \"x_origin\": -0.006,
+      \"x_increment\": 1e-9, \"samples\": [...] }
+",
+        )
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => memory waveform",
+        class_memory,
+        att_waveform,
+        on_waveform_command(
+            att_waveform.clone(),
+            interface.clone(),
+            progress.clone(),
+            profile.channel_sources()
+        )
+    );
+
+    //
+    //
+    std_att_boolean::mount(
+        class_memory.clone(),
+        progress.clone(),
+        BooleanIndex::Cancel as usize,
+        "cancel",
+        "Write true to stop the running readout before its next chunk",
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_memory.clone(),
+        progress.clone(),
+        FloatIndex::Progress as usize,
+        "progress",
+        "Progress of the running (or last) readout",
+        SiFormat::new("%", 0.0, 100.0, 1),
+        PROGRESS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_memory.clone(),
+        progress.clone(),
+        FloatIndex::PointsRead as usize,
+        "points_read",
+        "Points of the running (or last) readout received so far",
+        SiFormat::new("", 0.0, 1_000_000_000.0, 0),
+        PROGRESS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_memory.clone(),
+        progress.clone(),
+        FloatIndex::PointsTotal as usize,
+        "points_total",
+        "Memory depth of the running (or last) readout",
+        SiFormat::new("", 0.0, 1_000_000_000.0, 0),
+        PROGRESS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+///
+///
+async fn on_waveform_command(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    progress: Arc<Mutex<ReadoutProgress>>,
+    sources: Vec<&'static str>,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        let source = match command
+            .as_str()
+            .and_then(|name| ScpiString::new(name).resolve(&sources))
+        {
+            Some(s) => s,
+            None => {
+                log_warn!(
+                    att.logger(),
+                    "invalid memory readout {}, expected one of {:?}",
+                    command,
+                    sources
+                );
+                continue;
+            }
+        };
+
+        //
+        // Log
+        log_debug!(att.logger(), "memory readout of {} requested", source);
+
+        //
+        // A cancellation only applies to the readout running when it is written
+        progress.lock().await.cancel_requested = false;
+
+        let waveform = interface
+            .lock()
+            .await
+            .get_memory_waveform(source, &progress)
+            .await;
+        match waveform {
+            Ok(w) => {
+                let mut value = w.to_json();
+                value["source"] = serde_json::Value::from(source);
+                att.set(value).await?
+            }
+            Err(e) => log_warn!(att.logger(), "memory readout failed '{:?}'", e),
+        }
+    }
+    Ok(())
+}
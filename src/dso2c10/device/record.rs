@@ -0,0 +1,196 @@
+mod host;
+
+use tokio::sync::Mutex;
+
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer,
+};
+use std::sync::Arc;
+
+use crate::dso2c10::device::interface::BooleanIndex;
+use crate::dso2c10::device::interface::FloatIndex;
+use crate::dso2c10::device::interface::RECORD_MAX_FRAMES;
+use crate::dso2c10::Profile;
+use crate::scpi::ScpiString;
+use crate::si as std_att_si;
+use crate::si::SiFormat;
+
+use super::interface::DSO2C10Interface;
+
+/// Mount the segmented capture
+///
+/// Models with a record function capture the frames on the scope, the 'host'
+/// class emulates it on any model by rearming SINGLE acquisitions.
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+) -> Result<(), Error> {
+    let mut class_record = parent.create_class("record").finish().await;
+
+    let logger = class_record.logger().clone();
+    log_debug_mount_start!(logger);
+
+    if profile.has_record {
+        //
+        //
+        std_att_boolean::mount(
+            class_record.clone(),
+            interface.clone(),
+            BooleanIndex::RecordEnable as usize,
+            "enable",
+            "Record the next 'frames' triggered acquisitions",
+        )
+        .await?;
+
+        //
+        //
+        std_att_si::mount(
+            class_record.clone(),
+            interface.clone(),
+            FloatIndex::RecordFrames as usize,
+            "frames",
+            "Number of frames of the record",
+            SiFormat::new("", 1.0, RECORD_MAX_FRAMES as f64, 0),
+        )
+        .await?;
+
+        //
+        //
+        std_att_si::mount(
+            class_record.clone(),
+            interface.clone(),
+            FloatIndex::RecordInterval as usize,
+            "interval",
+            "Minimal time between two recorded frames",
+            SiFormat::new("s", 0.0, 1000.0, 9),
+        )
+        .await?;
+
+        //
+        //
+        std_att_boolean::mount(
+            class_record.clone(),
+            interface.clone(),
+            BooleanIndex::RecordPlay as usize,
+            "playback",
+            "Play the recorded frames on the screen",
+        )
+        .await?;
+
+        //
+        //
+        std_att_si::mount(
+            class_record.clone(),
+            interface.clone(),
+            FloatIndex::RecordPlayFrame as usize,
+            "current_frame",
+            "Frame displayed by the playback",
+            SiFormat::new("", 1.0, RECORD_MAX_FRAMES as f64, 0),
+        )
+        .await?;
+
+        //
+        //
+        let att_frame = class_record
+            .create_attribute("frame")
+            .with_rw()
+            .with_info(
+                "Write a frame number to read it from the record
+
+The source defaults to the first channel:
+    3
+    { \"frame\": 3, \"source\": \"CHANnel2\" }
+The waveform is published with its frame, its timestamp relative to the first
+frame and its source.
+",
+            )
+            .finish_as_json()
+            .await?;
+
+        //
+        spawn_on_command!(
+            "on_command => record frame",
+            class_record,
+            att_frame,
+            on_frame_command(
+                att_frame.clone(),
+                interface.clone(),
+                profile.channel_sources()
+            )
+        );
+    }
+
+    host::mount(class_record.clone(), interface.clone(), profile).await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+/// Frame number of a frame command
+///
+/// The command is a frame number or an object with a "frame" number.
+///
+fn frame_number(command: &serde_json::Value) -> Result<usize, String> {
+    command
+        .as_u64()
+        .or(command.get("frame").and_then(|f| f.as_u64()))
+        .map(|f| f as usize)
+        .ok_or(format!("Expected a frame number, got {}", command))
+}
+
+/// Frame number and optional source of a frame command
+///
+fn frame_of(
+    command: &serde_json::Value,
+    sources: &[&'static str],
+) -> Result<(usize, Option<&'static str>), String> {
+    let frame = frame_number(command)?;
+    let source = match command.get("source") {
+        Some(name) => {
+            let name = name
+                .as_str()
+                .ok_or(format!("Expected a channel name, got {}", name))?;
+            Some(ScpiString::new(name).resolve(sources).ok_or(format!(
+                "Invalid source {:?}, expected one of {:?}",
+                name, sources
+            ))?)
+        }
+        None => None,
+    };
+    Ok((frame, source))
+}
+
+///
+///
+async fn on_frame_command(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    sources: Vec<&'static str>,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        let (frame, source) = match frame_of(&command, &sources) {
+            Ok((frame, source)) => (frame, source.unwrap_or(sources[0])),
+            Err(e) => {
+                log_warn!(att.logger(), "invalid record frame '{}'", e);
+                continue;
+            }
+        };
+
+        //
+        // Log
+        log_debug!(att.logger(), "read frame {} of {}", frame, source);
+
+        let segment = interface.lock().await.get_record_frame(frame, source).await;
+        match segment {
+            Ok(s) => att.set(s.to_json()).await?,
+            Err(e) => log_warn!(att.logger(), "record frame readout failed '{:?}'", e),
+        }
+    }
+    Ok(())
+}
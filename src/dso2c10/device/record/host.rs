@@ -0,0 +1,433 @@
+use async_trait::async_trait;
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::std::attribute::boolean::BooleanAccessorModel;
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::std::attribute::r#enum::StringAccessorModel;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer, Logger,
+};
+use strum_macros::FromRepr;
+use tokio::sync::{watch, Mutex};
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::dso2c10::device::interface::DSO2C10Interface;
+use crate::dso2c10::Profile;
+use crate::dso2c10::{SegmentRing, MAX_SEGMENTS};
+use crate::scpi::ScpiString;
+use crate::si as std_att_si;
+use crate::si::{FloatAccessorModel, SiFormat};
+use crate::waveform::Waveform;
+
+/// Segments kept by default
+///
+const DEFAULT_SEGMENTS: usize = 100;
+
+/// Period of the trigger status polling while a SINGLE acquisition is armed
+///
+const POLL_PERIOD: Duration = Duration::from_millis(10);
+
+/// Delay to observe the arming of a SINGLE acquisition before arming it again
+///
+const ARM_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Pause after a failed capture, before the next attempt
+///
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Refresh period of the counters attributes
+///
+const COUNTERS_PERIOD: Duration = Duration::from_secs(1);
+
+/// Segmented capture emulated on the host
+///
+pub struct HostRecord {
+    /// Channels that can be captured
+    ///
+    sources: Vec<&'static str>,
+
+    /// Position of the captured channel in `sources`
+    ///
+    source: usize,
+
+    /// Wakes up the capture task when the capture is enabled
+    ///
+    enable: watch::Sender<bool>,
+
+    /// Time of the enabling, origin of the timestamps
+    ///
+    started: Instant,
+
+    ///
+    ///
+    ring: SegmentRing,
+}
+
+impl HostRecord {
+    ///
+    ///
+    fn new(sources: Vec<&'static str>) -> Self {
+        Self {
+            sources,
+            source: 0,
+            enable: watch::channel(false).0,
+            started: Instant::now(),
+            ring: SegmentRing::new(DEFAULT_SEGMENTS),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum BooleanIndex {
+    //
+    Enable,
+}
+
+#[async_trait]
+///
+///
+impl BooleanAccessorModel for HostRecord {
+    ///
+    ///
+    async fn get_boolean_at(&mut self, index: usize) -> Result<bool, Error> {
+        match BooleanIndex::from_repr(index) {
+            Some(BooleanIndex::Enable) => Ok(*self.enable.borrow()),
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+
+    ///
+    /// The segments of the previous capture are dropped when it is enabled
+    ///
+    async fn set_boolean_at(&mut self, index: usize, value: bool) -> Result<(), Error> {
+        match BooleanIndex::from_repr(index) {
+            Some(BooleanIndex::Enable) => {
+                if value && !*self.enable.borrow() {
+                    self.ring.clear();
+                    self.started = Instant::now();
+                }
+                self.enable.send_replace(value);
+                Ok(())
+            }
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum StringIndex {
+    //
+    Source,
+}
+
+#[async_trait]
+///
+///
+impl StringAccessorModel for HostRecord {
+    ///
+    ///
+    async fn get_string_at(&mut self, index: usize) -> Result<String, Error> {
+        match StringIndex::from_repr(index) {
+            Some(StringIndex::Source) => Ok(self.sources[self.source].to_string()),
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+
+    ///
+    ///
+    async fn set_string_at(&mut self, index: usize, value: &String) -> Result<(), Error> {
+        match StringIndex::from_repr(index) {
+            Some(StringIndex::Source) => {
+                let source =
+                    ScpiString::new(value)
+                        .resolve(&self.sources)
+                        .ok_or(Error::InvalidArgument(format!(
+                            "Invalid source {:?}, expected one of {:?}",
+                            value, self.sources
+                        )))?;
+                self.source = self.sources.iter().position(|s| *s == source).unwrap_or(0);
+                Ok(())
+            }
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum FloatIndex {
+    //
+    Segments,
+    //
+    Captured,
+    Stored,
+}
+
+#[async_trait]
+///
+///
+impl FloatAccessorModel for HostRecord {
+    ///
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error> {
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        Ok(match idx {
+            FloatIndex::Segments => self.ring.capacity() as f64,
+            FloatIndex::Captured => self.ring.captured() as f64,
+            FloatIndex::Stored => self.ring.len() as f64,
+        })
+    }
+
+    ///
+    ///
+    async fn set_float_at(&mut self, index: usize, value: f64) -> Result<(), Error> {
+        match FloatIndex::from_repr(index) {
+            Some(FloatIndex::Segments) => {
+                if value < 1.0 || value.fract() != 0.0 || value > MAX_SEGMENTS as f64 {
+                    return Err(Error::InvalidArgument(format!(
+                        "Invalid segments {}, expected an integer from 1 to {}",
+                        value, MAX_SEGMENTS
+                    )));
+                }
+                self.ring.set_capacity(value as usize);
+                Ok(())
+            }
+            _ => Err(Error::InvalidArgument("Read only value".to_string())),
+        }
+    }
+}
+
+/// Mount the segmented capture emulated on the host
+///
+/// * enable, source, segments: settings of the capture
+/// * captured, stored: frames captured since the enabling, and still kept
+/// * frame: write a frame number to publish its waveform
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+) -> Result<(), Error> {
+    let mut class_host = parent.create_class("host").finish().await;
+
+    let logger = class_host.logger().clone();
+    log_debug_mount_start!(logger);
+
+    let record = Arc::new(Mutex::new(HostRecord::new(profile.channel_sources())));
+
+    //
+    //
+    std_att_boolean::mount(
+        class_host.clone(),
+        record.clone(),
+        BooleanIndex::Enable as usize,
+        "enable",
+        "Rearm a SINGLE acquisition after each trigger and keep the captures
+
+The trigger sweep is left in SINGle when the capture is disabled. An acquisition
+that completes before the scope is seen armed is not stored, a new one is armed.
+",
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_host.clone(),
+        record.clone(),
+        StringIndex::Source as usize,
+        "source",
+        "Channel read after each trigger",
+        profile.channel_sources(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_host.clone(),
+        record.clone(),
+        FloatIndex::Segments as usize,
+        "segments",
+        "Number of captures kept, the oldest are dropped first",
+        SiFormat::new("", 1.0, MAX_SEGMENTS as f64, 0),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_host.clone(),
+        record.clone(),
+        FloatIndex::Captured as usize,
+        "captured",
+        "Captures since the enabling, it is also the number of the last frame",
+        SiFormat::new("", 0.0, u64::MAX as f64, 0),
+        COUNTERS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_host.clone(),
+        record.clone(),
+        FloatIndex::Stored as usize,
+        "stored",
+        "Captures still kept in the ring buffer",
+        SiFormat::new("", 0.0, MAX_SEGMENTS as f64, 0),
+        COUNTERS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    let att_frame = class_host
+        .create_attribute("frame")
+        .with_rw()
+        .with_info(
+            "Write a frame number to publish its capture
+
+The waveform is published with its frame, its timestamp and its source. The
+timestamp is the time of the readout since the enabling, it follows the trigger
+by up to the polling period.
+",
+        )
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => host record frame",
+        class_host,
+        att_frame,
+        on_frame_command(att_frame.clone(), record.clone())
+    );
+
+    //
+    // The capture loop belongs to the instance and stops with it
+    class_host
+        .spawn("run => host record", run(interface, record, logger.clone()))
+        .await;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+/// Capture loop, runs while the capture is enabled and waits otherwise
+///
+async fn run(
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    record: Arc<Mutex<HostRecord>>,
+    logger: Logger,
+) -> Result<(), Error> {
+    let mut enable = record.lock().await.enable.subscribe();
+    loop {
+        while !*enable.borrow_and_update() {
+            if enable.changed().await.is_err() {
+                return Ok(());
+            }
+        }
+
+        let source = {
+            let record = record.lock().await;
+            record.sources[record.source]
+        };
+        match capture_single(&interface, source, &enable).await {
+            Ok(Some(waveform)) => {
+                let mut record = record.lock().await;
+                let timestamp = record.started.elapsed().as_secs_f64();
+                record.ring.push(timestamp, source, waveform);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log_warn!(logger, "segment capture failed '{:?}'", e);
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Arm a SINGLE acquisition and read the source once it has triggered
+///
+/// The new acquisition is only read once the scope has reported it armed (not
+/// stopped) then stopped again, a stopped status alone may still be the previous
+/// acquisition. The interface is released while waiting for the trigger.
+///
+/// None if the capture is disabled before the trigger, or if the arming is not
+/// observed within ARM_TIMEOUT (e.g. the acquisition completed between two
+/// polls), the caller arms a new one.
+///
+async fn capture_single(
+    interface: &Arc<Mutex<DSO2C10Interface>>,
+    source: &str,
+    enable: &watch::Receiver<bool>,
+) -> Result<Option<Waveform>, Error> {
+    //
+    // The first status is read right after the command, in the same lock
+    let mut armed = {
+        let interface = interface.lock().await;
+        interface.arm_single().await?;
+        !interface.is_acquisition_stopped().await?
+    };
+    let armed_at = Instant::now();
+    loop {
+        tokio::time::sleep(POLL_PERIOD).await;
+        if !*enable.borrow() {
+            return Ok(None);
+        }
+
+        let stopped = interface.lock().await.is_acquisition_stopped().await?;
+        if !stopped {
+            armed = true;
+        } else if armed {
+            return interface.lock().await.get_waveform(source).await.map(Some);
+        } else if armed_at.elapsed() >= ARM_TIMEOUT {
+            return Ok(None);
+        }
+    }
+}
+
+///
+///
+async fn on_frame_command(
+    mut att: JsonAttServer,
+    record: Arc<Mutex<HostRecord>>,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        let frame = match super::frame_number(&command) {
+            Ok(frame) => frame,
+            Err(e) => {
+                log_warn!(att.logger(), "invalid host record frame '{}'", e);
+                continue;
+            }
+        };
+
+        //
+        // Log
+        log_debug!(att.logger(), "host frame {} requested", frame);
+
+        let value = record.lock().await.ring.get(frame).map(|s| s.to_json());
+        match value {
+            Ok(v) => att.set(v).await?,
+            Err(e) => log_warn!(att.logger(), "host record frame failed '{}'", e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,315 @@
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer,
+};
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use std::sync::Arc;
+
+use crate::dso2c10::device::interface::BooleanIndex;
+use crate::dso2c10::device::interface::DSO2C10Interface;
+use crate::dso2c10::device::interface::REFERENCE_SLOTS;
+use crate::dso2c10::Profile;
+use crate::scpi::ScpiString;
+use crate::waveform::Waveform;
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+) -> Result<(), Error> {
+    let class_reference = parent.create_class("reference").finish().await;
+
+    let logger = class_reference.logger().clone();
+    log_debug_mount_start!(logger);
+
+    for slot in 1..=REFERENCE_SLOTS {
+        mount_slot(
+            class_reference.clone(),
+            slot,
+            interface.clone(),
+            profile.channel_sources(),
+        )
+        .await?;
+    }
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+///
+///
+async fn mount_slot<C: Container + 'static>(
+    mut parent: C,
+    slot: usize,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    sources: Vec<&'static str>,
+) -> Result<(), Error> {
+    let mut class_slot = parent.create_class(format!("{}", slot)).finish().await;
+
+    //
+    // Host copy of the reference, shared by the commands of the slot
+    let reference: Arc<Mutex<Option<Waveform>>> = Arc::new(Mutex::new(None));
+
+    //
+    //
+    std_att_boolean::mount(
+        class_slot.clone(),
+        interface.clone(),
+        match slot {
+            1 => BooleanIndex::Reference1Display,
+            2 => BooleanIndex::Reference2Display,
+            3 => BooleanIndex::Reference3Display,
+            4 => BooleanIndex::Reference4Display,
+            _ => BooleanIndex::Reference1Display,
+        } as usize,
+        "display",
+        "Display the reference waveform on the screen",
+    )
+    .await?;
+
+    //
+    //
+    let att_waveform = class_slot
+        .create_attribute("waveform")
+        .with_rw()
+        .with_info(
+            "Host copy of the reference, used by 'compare'
+
+Write a waveform to load it into the slot:
+    { \"unit\": \"V\", \"x_origin\": 0.0, \"x_increment\": 1e-6, \"samples\": [...],
+      \"channel\": \"CHANnel1\" }
+Samples are sent with the vertical scale of 'channel' (default the first one),
+the reference is then shown on its grid. A failed upload is published as:
+    { \"error\": \"...\" }
+",
+        )
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => reference waveform",
+        class_slot,
+        att_waveform,
+        on_waveform_command(
+            att_waveform.clone(),
+            interface.clone(),
+            reference.clone(),
+            slot,
+            sources.clone()
+        )
+    );
+
+    //
+    //
+    let att_save = class_slot
+        .create_attribute("save")
+        .with_wo()
+        .with_info("Write a channel (e.g. \"CHANnel1\") to save its waveform into the slot")
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => reference save",
+        class_slot,
+        att_save,
+        on_save_command(
+            att_save.clone(),
+            att_waveform.clone(),
+            interface.clone(),
+            reference.clone(),
+            slot,
+            sources.clone()
+        )
+    );
+
+    //
+    //
+    let att_compare = class_slot
+        .create_attribute("compare")
+        .with_rw()
+        .with_info(
+            "Write a channel (e.g. \"CHANnel1\") to capture it and compare it with the reference
+
+The result is published as:
+    { \"source\": \"CHANnel1\", \"points\": 1200, \"rms_difference\": 0.012,
+      \"max_deviation\": 0.08, \"max_deviation_time\": 0.000125 }
+",
+        )
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => reference compare",
+        class_slot,
+        att_compare,
+        on_compare_command(
+            att_compare.clone(),
+            interface.clone(),
+            reference.clone(),
+            slot,
+            sources
+        )
+    );
+
+    Ok(())
+}
+
+/// Channel of a command, resolved against the available sources
+///
+fn source_of(
+    command: &serde_json::Value,
+    sources: &[&'static str],
+) -> Result<&'static str, String> {
+    let name = command
+        .as_str()
+        .ok_or(format!("Expected a channel name, got {}", command))?;
+    ScpiString::new(name).resolve(sources).ok_or(format!(
+        "Invalid source {:?}, expected one of {:?}",
+        name, sources
+    ))
+}
+
+///
+///
+async fn on_waveform_command(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    reference: Arc<Mutex<Option<Waveform>>>,
+    slot: usize,
+    sources: Vec<&'static str>,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        let waveform = match Waveform::from_json(&command) {
+            Ok(w) => w,
+            Err(e) => {
+                log_warn!(att.logger(), "invalid reference waveform '{}'", e);
+                continue;
+            }
+        };
+        let channel = match command.get("channel") {
+            Some(c) => source_of(c, &sources),
+            None => Ok(sources[0]),
+        };
+        let channel = match channel {
+            Ok(c) => c,
+            Err(e) => {
+                log_warn!(att.logger(), "invalid reference channel '{}'", e);
+                continue;
+            }
+        };
+
+        //
+        // Log
+        log_debug!(
+            att.logger(),
+            "load reference {} from host on the scale of {}",
+            slot,
+            channel
+        );
+
+        let result = interface
+            .lock()
+            .await
+            .load_reference(slot, channel, &waveform)
+            .await;
+        match result {
+            Ok(()) => {
+                att.set(waveform.to_json()).await?;
+                *reference.lock().await = Some(waveform);
+            }
+            Err(e) => {
+                log_warn!(att.logger(), "reference load failed '{:?}'", e);
+                att.set(json!({ "error": format!("{:?}", e) })).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+///
+///
+async fn on_save_command(
+    mut att: JsonAttServer,
+    mut att_waveform: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    reference: Arc<Mutex<Option<Waveform>>>,
+    slot: usize,
+    sources: Vec<&'static str>,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        let source = match source_of(&command, &sources) {
+            Ok(s) => s,
+            Err(e) => {
+                log_warn!(att.logger(), "invalid reference save '{}'", e);
+                continue;
+            }
+        };
+
+        //
+        // Log
+        log_debug!(att.logger(), "save {} into reference {}", source, slot);
+
+        let result = interface.lock().await.save_reference(slot, source).await;
+        match result {
+            Ok(w) => {
+                att_waveform.set(w.to_json()).await?;
+                *reference.lock().await = Some(w);
+            }
+            Err(e) => log_warn!(att.logger(), "reference save failed '{:?}'", e),
+        }
+    }
+    Ok(())
+}
+
+///
+///
+async fn on_compare_command(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    reference: Arc<Mutex<Option<Waveform>>>,
+    slot: usize,
+    sources: Vec<&'static str>,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        let source = match source_of(&command, &sources) {
+            Ok(s) => s,
+            Err(e) => {
+                log_warn!(att.logger(), "invalid reference compare '{}'", e);
+                continue;
+            }
+        };
+
+        //
+        // Log
+        log_debug!(att.logger(), "compare {} with reference {}", source, slot);
+
+        let current = match reference.lock().await.clone() {
+            Some(w) => w,
+            None => {
+                log_warn!(att.logger(), "reference {} is empty", slot);
+                continue;
+            }
+        };
+
+        let result = interface.lock().await.get_waveform(source).await;
+        match result.and_then(|w| w.compare(&current).map_err(|e| Error::InvalidArgument(e))) {
+            Ok(c) => {
+                let mut value = c.to_json();
+                value["source"] = serde_json::Value::from(source);
+                att.set(value).await?
+            }
+            Err(e) => log_warn!(att.logger(), "reference compare failed '{:?}'", e),
+        }
+    }
+    Ok(())
+}
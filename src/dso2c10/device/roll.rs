@@ -0,0 +1,490 @@
+use async_trait::async_trait;
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::std::attribute::boolean::BooleanAccessorModel;
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::std::attribute::r#enum::StringAccessorModel;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer, Logger,
+};
+use strum_macros::FromRepr;
+use tokio::sync::{watch, Mutex};
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::dso2c10::device::interface::DSO2C10Interface;
+use crate::dso2c10::Profile;
+use crate::dso2c10::{RollSeries, MAX_SERIES_POINTS};
+use crate::scpi::ScpiString;
+use crate::si as std_att_si;
+use crate::si::{FloatAccessorModel, SiFormat};
+
+/// Points kept by default, the oldest are dropped first
+///
+const DEFAULT_SERIES_POINTS: usize = 1_000_000;
+
+/// Pause between two reads by default, in seconds
+///
+const DEFAULT_PERIOD: f64 = 0.2;
+
+/// Longest pause between two reads, in seconds
+///
+const MAX_PERIOD: f64 = 10.0;
+
+/// Pause after a failed read, before the next attempt
+///
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Refresh period of the counters attributes
+///
+const COUNTERS_PERIOD: Duration = Duration::from_secs(1);
+
+/// Host time series built from the screen reads of the ROLL mode
+///
+pub struct RollStream {
+    /// Channels that can be streamed
+    ///
+    sources: Vec<&'static str>,
+
+    /// Position of the streamed channel in `sources`
+    ///
+    source: usize,
+
+    /// Wakes up the read task when the streaming is enabled
+    ///
+    enable: watch::Sender<bool>,
+
+    /// Pause between two reads, in seconds
+    ///
+    period: f64,
+
+    /// Time of the start of the series, origin of the read times
+    ///
+    started: Instant,
+
+    ///
+    ///
+    series: RollSeries,
+}
+
+impl RollStream {
+    ///
+    ///
+    fn new(sources: Vec<&'static str>) -> Self {
+        Self {
+            sources,
+            source: 0,
+            enable: watch::channel(false).0,
+            period: DEFAULT_PERIOD,
+            started: Instant::now(),
+            series: RollSeries::new(DEFAULT_SERIES_POINTS),
+        }
+    }
+
+    /// Forget the points and restart the time origin
+    ///
+    fn restart(&mut self) {
+        self.series.clear();
+        self.started = Instant::now();
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum BooleanIndex {
+    //
+    Enable,
+}
+
+#[async_trait]
+///
+///
+impl BooleanAccessorModel for RollStream {
+    ///
+    ///
+    async fn get_boolean_at(&mut self, index: usize) -> Result<bool, Error> {
+        match BooleanIndex::from_repr(index) {
+            Some(BooleanIndex::Enable) => Ok(*self.enable.borrow()),
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+
+    ///
+    /// The series restarts when the streaming is enabled
+    ///
+    async fn set_boolean_at(&mut self, index: usize, value: bool) -> Result<(), Error> {
+        match BooleanIndex::from_repr(index) {
+            Some(BooleanIndex::Enable) => {
+                if value && !*self.enable.borrow() {
+                    self.restart();
+                }
+                self.enable.send_replace(value);
+                Ok(())
+            }
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum StringIndex {
+    //
+    Source,
+}
+
+#[async_trait]
+///
+///
+impl StringAccessorModel for RollStream {
+    ///
+    ///
+    async fn get_string_at(&mut self, index: usize) -> Result<String, Error> {
+        match StringIndex::from_repr(index) {
+            Some(StringIndex::Source) => Ok(self.sources[self.source].to_string()),
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+
+    ///
+    /// The series restarts when the source changes
+    ///
+    async fn set_string_at(&mut self, index: usize, value: &String) -> Result<(), Error> {
+        match StringIndex::from_repr(index) {
+            Some(StringIndex::Source) => {
+                let source =
+                    ScpiString::new(value)
+                        .resolve(&self.sources)
+                        .ok_or(Error::InvalidArgument(format!(
+                            "Invalid source {:?}, expected one of {:?}",
+                            value, self.sources
+                        )))?;
+                let position = self.sources.iter().position(|s| *s == source).unwrap_or(0);
+                if position != self.source {
+                    self.source = position;
+                    self.restart();
+                }
+                Ok(())
+            }
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum FloatIndex {
+    //
+    Period,
+    Length,
+    //
+    Points,
+    Total,
+    Gaps,
+}
+
+#[async_trait]
+///
+///
+impl FloatAccessorModel for RollStream {
+    ///
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error> {
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        Ok(match idx {
+            FloatIndex::Period => self.period,
+            FloatIndex::Length => self.series.max_points() as f64,
+            FloatIndex::Points => self.series.len() as f64,
+            FloatIndex::Total => self.series.total() as f64,
+            FloatIndex::Gaps => self.series.gap_count() as f64,
+        })
+    }
+
+    ///
+    ///
+    async fn set_float_at(&mut self, index: usize, value: f64) -> Result<(), Error> {
+        match FloatIndex::from_repr(index) {
+            Some(FloatIndex::Period) => {
+                if !(0.0..=MAX_PERIOD).contains(&value) {
+                    return Err(Error::InvalidArgument(format!(
+                        "Invalid period {}, expected 0 to {} s",
+                        value, MAX_PERIOD
+                    )));
+                }
+                self.period = value;
+                Ok(())
+            }
+            Some(FloatIndex::Length) => {
+                if !(1.0..=MAX_SERIES_POINTS as f64).contains(&value) || value.fract() != 0.0 {
+                    return Err(Error::InvalidArgument(format!(
+                        "Invalid length {}, expected an integer from 1 to {}",
+                        value, MAX_SERIES_POINTS
+                    )));
+                }
+                self.series = RollSeries::new(value as usize);
+                self.started = Instant::now();
+                Ok(())
+            }
+            _ => Err(Error::InvalidArgument("Read only value".to_string())),
+        }
+    }
+}
+
+/// Mount the streaming of the ROLL mode
+///
+/// * enable, source, period, length: settings of the streaming
+/// * samples: points appended by each read
+/// * series: write any value to publish the points kept
+/// * points, total, gaps: counters of the series
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+) -> Result<(), Error> {
+    let mut class_roll = parent.create_class("roll").finish().await;
+
+    let logger = class_roll.logger().clone();
+    log_debug_mount_start!(logger);
+
+    let roll = Arc::new(Mutex::new(RollStream::new(profile.channel_sources())));
+
+    //
+    //
+    std_att_boolean::mount(
+        class_roll.clone(),
+        roll.clone(),
+        BooleanIndex::Enable as usize,
+        "enable",
+        "Read the screen periodically and append the scrolled points to the series
+
+Set 'timebase/mode' to ROLL first. A read must happen before the points of the
+previous one have scrolled out of the screen, otherwise a gap is reported.
+",
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_roll.clone(),
+        roll.clone(),
+        StringIndex::Source as usize,
+        "source",
+        "Streamed channel, the series restarts when it changes",
+        profile.channel_sources(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_roll.clone(),
+        roll.clone(),
+        FloatIndex::Period as usize,
+        "period",
+        "Pause between two reads of the screen",
+        SiFormat::new("s", 0.0, MAX_PERIOD, 3),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_roll.clone(),
+        roll.clone(),
+        FloatIndex::Length as usize,
+        "length",
+        "Points kept, the oldest are dropped first. The series restarts when it changes",
+        SiFormat::new("", 1.0, MAX_SERIES_POINTS as f64, 0),
+    )
+    .await?;
+
+    //
+    //
+    let att_samples = class_roll
+        .create_attribute("samples")
+        .with_ro()
+        .with_info(
+            "Points appended by each read
+
+    { \"index\": 1200, \"time\": 12.0, \"x_increment\": 0.01, \"unit\": \"V\",
+      \"samples\": [ ... ], \"gap\": false }
+The index counts the points since the start of the series, the time is in
+seconds since the start. 'gap' is true when points are missing before the chunk.
+",
+        )
+        .finish_as_json()
+        .await?;
+
+    //
+    //
+    let att_series = class_roll
+        .create_attribute("series")
+        .with_rw()
+        .with_info(
+            "Write any value to publish the points kept as one waveform
+
+The waveform is published with the total number of points and the index of the
+first point after each gap among the points kept.
+",
+        )
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => roll series",
+        class_roll,
+        att_series,
+        on_series_command(att_series.clone(), roll.clone())
+    );
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_roll.clone(),
+        roll.clone(),
+        FloatIndex::Points as usize,
+        "points",
+        "Points kept in the series",
+        SiFormat::new("", 0.0, MAX_SERIES_POINTS as f64, 0),
+        COUNTERS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_roll.clone(),
+        roll.clone(),
+        FloatIndex::Total as usize,
+        "total",
+        "Points appended since the start of the series, including the dropped ones",
+        SiFormat::new("", 0.0, u64::MAX as f64, 0),
+        COUNTERS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_roll.clone(),
+        roll.clone(),
+        FloatIndex::Gaps as usize,
+        "gaps",
+        "Reads that did not overlap the previous one",
+        SiFormat::new("", 0.0, u64::MAX as f64, 0),
+        COUNTERS_PERIOD,
+    )
+    .await?;
+
+    //
+    // The read loop belongs to the instance and stops with it
+    class_roll
+        .spawn(
+            "run => roll",
+            run(interface, roll, att_samples, logger.clone()),
+        )
+        .await;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+/// Read loop, runs while the streaming is enabled and waits otherwise
+///
+async fn run(
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    roll: Arc<Mutex<RollStream>>,
+    mut att: JsonAttServer,
+    logger: Logger,
+) -> Result<(), Error> {
+    let mut enable = roll.lock().await.enable.subscribe();
+    loop {
+        while !*enable.borrow_and_update() {
+            if enable.changed().await.is_err() {
+                return Ok(());
+            }
+        }
+
+        let (source, period) = {
+            let roll = roll.lock().await;
+            (roll.sources[roll.source], roll.period)
+        };
+        let read = interface.lock().await.get_waveform(source).await;
+
+        //
+        // The series may have restarted during the read, the source is checked
+        let chunk = match read {
+            Ok(read) => {
+                let mut roll = roll.lock().await;
+                match roll.sources[roll.source] == source && *enable.borrow() {
+                    true => {
+                        let time = roll.started.elapsed().as_secs_f64();
+                        Some(roll.series.push(read, time))
+                    }
+                    false => None,
+                }
+            }
+            Err(e) => {
+                log_warn!(logger, "roll read failed '{:?}'", e);
+                tokio::time::sleep(RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        match chunk {
+            Some(Ok(chunk)) if chunk.gap || !chunk.samples.is_empty() => {
+                if let Err(e) = att.set(chunk.to_json()).await {
+                    log_warn!(logger, "roll publication failed '{:?}'", e);
+                }
+            }
+            Some(Err(e)) => log_warn!(logger, "invalid roll read '{}'", e),
+            _ => {}
+        }
+
+        tokio::time::sleep(Duration::from_secs_f64(period)).await;
+    }
+}
+
+///
+///
+async fn on_series_command(
+    mut att: JsonAttServer,
+    roll: Arc<Mutex<RollStream>>,
+) -> Result<(), Error> {
+    while let Some(_command) = att.pop_cmd().await {
+        //
+        // Log
+        log_debug!(att.logger(), "roll series requested");
+
+        let value = {
+            let roll = roll.lock().await;
+            roll.series.to_waveform().map(|w| {
+                let mut value = w.to_json();
+                value["total"] = serde_json::Value::from(roll.series.total());
+                value["gaps"] = serde_json::Value::from(roll.series.gaps().to_vec());
+                value
+            })
+        };
+        match value {
+            Some(v) => att.set(v).await?,
+            None => log_warn!(att.logger(), "roll series is empty"),
+        }
+    }
+    Ok(())
+}
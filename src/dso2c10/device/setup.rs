@@ -0,0 +1,691 @@
+mod profiles;
+
+use panduza_platform_core::std::attribute::boolean::BooleanAccessorModel;
+use panduza_platform_core::std::attribute::r#enum::StringAccessorModel;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer,
+};
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use std::sync::Arc;
+
+use crate::dso2c10::device::interface::BooleanIndex;
+use crate::dso2c10::device::interface::DSO2C10Interface;
+use crate::dso2c10::device::interface::FloatIndex;
+use crate::dso2c10::device::interface::StringIndex;
+use crate::dso2c10::device::interface::REFERENCE_SLOTS;
+use crate::dso2c10::device::interface::SETUP_SLOTS;
+use crate::dso2c10::{BandwidthLimit, Profile, SetupStore};
+use crate::si::FloatAccessorModel;
+
+/// Accessor of a mounted parameter
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Accessor {
+    Boolean(BooleanIndex),
+    String(StringIndex),
+    Float(FloatIndex),
+}
+
+/// Parameter of the instrument state, named by its attribute path
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    /// Attribute path relative to the instance (e.g. channel/1/scale)
+    ///
+    pub name: String,
+
+    ///
+    ///
+    pub accessor: Accessor,
+}
+
+impl Parameter {
+    ///
+    ///
+    fn new<A: Into<String>>(name: A, accessor: Accessor) -> Self {
+        Self {
+            name: name.into(),
+            accessor,
+        }
+    }
+}
+
+/// Parameters mounted for the profile, in the order they must be restored
+///
+/// A parameter comes after the ones that change its range or its meaning: the
+/// probe and the fine tuning before the scale, the scale before the offset, the
+/// timebase mode before its scale, the timebase before the trigger, the operator
+/// before the math scale, the sources before the cursor positions, and the
+/// decoder before the bus trigger.
+///
+pub fn parameters(profile: &Profile) -> Vec<Parameter> {
+    use Accessor::{Boolean as B, Float as F, String as S};
+    let mut list = Vec::new();
+
+    //
+    // Channels
+    for channel_id in 1..=profile.channel_count {
+        let name = |n: &str| format!("channel/{}/{}", channel_id, n);
+        let string = |i: [StringIndex; 4]| S(i[(channel_id - 1).min(3)]);
+        let boolean = |i: [BooleanIndex; 4]| B(i[(channel_id - 1).min(3)]);
+
+        list.push(Parameter::new(
+            name("probe"),
+            string([
+                StringIndex::Channel1Probe,
+                StringIndex::Channel2Probe,
+                StringIndex::Channel3Probe,
+                StringIndex::Channel4Probe,
+            ]),
+        ));
+        match &profile.bandwidth_limit {
+            BandwidthLimit::None => {}
+            BandwidthLimit::Boolean => list.push(Parameter::new(
+                name("bandwidth_limit"),
+                boolean([
+                    BooleanIndex::Channel1BwLimit,
+                    BooleanIndex::Channel2BwLimit,
+                    BooleanIndex::Channel3BwLimit,
+                    BooleanIndex::Channel4BwLimit,
+                ]),
+            )),
+            BandwidthLimit::Choices(_) => list.push(Parameter::new(
+                name("bandwidth_limit"),
+                string([
+                    StringIndex::Channel1BwLimit,
+                    StringIndex::Channel2BwLimit,
+                    StringIndex::Channel3BwLimit,
+                    StringIndex::Channel4BwLimit,
+                ]),
+            )),
+        }
+        list.push(Parameter::new(
+            name("coupling"),
+            string([
+                StringIndex::Channel1Coupling,
+                StringIndex::Channel2Coupling,
+                StringIndex::Channel3Coupling,
+                StringIndex::Channel4Coupling,
+            ]),
+        ));
+        list.push(Parameter::new(
+            name("vertical_fine_tuning"),
+            boolean([
+                BooleanIndex::Channel1Vernier,
+                BooleanIndex::Channel2Vernier,
+                BooleanIndex::Channel3Vernier,
+                BooleanIndex::Channel4Vernier,
+            ]),
+        ));
+        list.push(Parameter::new(
+            name("scale"),
+            string([
+                StringIndex::Channel1Scale,
+                StringIndex::Channel2Scale,
+                StringIndex::Channel3Scale,
+                StringIndex::Channel4Scale,
+            ]),
+        ));
+        list.push(Parameter::new(
+            name("offset"),
+            F([
+                FloatIndex::Channel1Offset,
+                FloatIndex::Channel2Offset,
+                FloatIndex::Channel3Offset,
+                FloatIndex::Channel4Offset,
+            ][(channel_id - 1).min(3)]),
+        ));
+        list.push(Parameter::new(
+            name("invert"),
+            boolean([
+                BooleanIndex::Channel1Invert,
+                BooleanIndex::Channel2Invert,
+                BooleanIndex::Channel3Invert,
+                BooleanIndex::Channel4Invert,
+            ]),
+        ));
+        list.push(Parameter::new(
+            name("display"),
+            boolean([
+                BooleanIndex::Channel1Display,
+                BooleanIndex::Channel2Display,
+                BooleanIndex::Channel3Display,
+                BooleanIndex::Channel4Display,
+            ]),
+        ));
+    }
+
+    //
+    // Timebase, then trigger
+    list.push(Parameter::new(
+        "timebase/mode",
+        S(StringIndex::TimebaseMode),
+    ));
+    list.push(Parameter::new(
+        "timebase/scale",
+        F(FloatIndex::TimebaseScale),
+    ));
+    list.push(Parameter::new(
+        "timebase/position",
+        F(FloatIndex::TimebasePosition),
+    ));
+    list.push(Parameter::new(
+        "trigger/source",
+        S(StringIndex::TriggerSource),
+    ));
+    list.push(Parameter::new(
+        "trigger/sweep",
+        S(StringIndex::TriggerSweep),
+    ));
+    list.push(Parameter::new("trigger/level", F(FloatIndex::TriggerLevel)));
+
+    //
+    // Math
+    list.push(Parameter::new(
+        "math/operator",
+        S(StringIndex::MathOperator),
+    ));
+    list.push(Parameter::new("math/source_a", S(StringIndex::MathSourceA)));
+    list.push(Parameter::new("math/source_b", S(StringIndex::MathSourceB)));
+    list.push(Parameter::new(
+        "math/fft/window",
+        S(StringIndex::MathFftWindow),
+    ));
+    list.push(Parameter::new("math/fft/unit", S(StringIndex::MathFftUnit)));
+    list.push(Parameter::new(
+        "math/fft/center",
+        F(FloatIndex::MathFftCenter),
+    ));
+    list.push(Parameter::new("math/fft/span", F(FloatIndex::MathFftSpan)));
+    list.push(Parameter::new("math/scale", F(FloatIndex::MathScale)));
+    list.push(Parameter::new("math/offset", F(FloatIndex::MathOffset)));
+    list.push(Parameter::new("math/display", B(BooleanIndex::MathDisplay)));
+
+    //
+    // Cursor
+    list.push(Parameter::new("cursor/mode", S(StringIndex::CursorMode)));
+    list.push(Parameter::new("cursor/type", S(StringIndex::CursorType)));
+    list.push(Parameter::new(
+        "cursor/source",
+        S(StringIndex::CursorSource),
+    ));
+    list.push(Parameter::new("cursor/a_time", F(FloatIndex::CursorATime)));
+    list.push(Parameter::new("cursor/b_time", F(FloatIndex::CursorBTime)));
+    list.push(Parameter::new(
+        "cursor/a_voltage",
+        F(FloatIndex::CursorAVoltage),
+    ));
+    list.push(Parameter::new(
+        "cursor/b_voltage",
+        F(FloatIndex::CursorBVoltage),
+    ));
+
+    //
+    // Decoders, the bus settings before the selection of the bus
+    let decoders: [(&str, Vec<(&str, Accessor)>); 5] = [
+        (
+            "UART",
+            vec![
+                ("uart/source", S(StringIndex::DecodeUartSource)),
+                ("uart/threshold", F(FloatIndex::DecodeUartThreshold)),
+                ("uart/baud", F(FloatIndex::DecodeUartBaud)),
+                ("uart/parity", S(StringIndex::DecodeUartParity)),
+                ("uart/stop_bits", S(StringIndex::DecodeUartStopBits)),
+                ("uart/data_bits", S(StringIndex::DecodeUartDataBits)),
+            ],
+        ),
+        (
+            "IIC",
+            vec![
+                ("i2c/scl_source", S(StringIndex::DecodeI2cSclSource)),
+                ("i2c/scl_threshold", F(FloatIndex::DecodeI2cSclThreshold)),
+                ("i2c/sda_source", S(StringIndex::DecodeI2cSdaSource)),
+                ("i2c/sda_threshold", F(FloatIndex::DecodeI2cSdaThreshold)),
+                ("i2c/address_width", S(StringIndex::DecodeI2cAddressWidth)),
+            ],
+        ),
+        (
+            "SPI",
+            vec![
+                ("spi/clk_source", S(StringIndex::DecodeSpiClockSource)),
+                ("spi/clk_threshold", F(FloatIndex::DecodeSpiClockThreshold)),
+                ("spi/mosi_source", S(StringIndex::DecodeSpiMosiSource)),
+                ("spi/mosi_threshold", F(FloatIndex::DecodeSpiMosiThreshold)),
+                ("spi/miso_source", S(StringIndex::DecodeSpiMisoSource)),
+                ("spi/miso_threshold", F(FloatIndex::DecodeSpiMisoThreshold)),
+                ("spi/cs_source", S(StringIndex::DecodeSpiCsSource)),
+                ("spi/cs_threshold", F(FloatIndex::DecodeSpiCsThreshold)),
+                ("spi/cpol", S(StringIndex::DecodeSpiCpol)),
+                ("spi/cpha", S(StringIndex::DecodeSpiCpha)),
+                ("spi/bit_order", S(StringIndex::DecodeSpiBitOrder)),
+            ],
+        ),
+        (
+            "CAN",
+            vec![
+                ("can/source", S(StringIndex::DecodeCanSource)),
+                ("can/signal", S(StringIndex::DecodeCanSignal)),
+                ("can/threshold", F(FloatIndex::DecodeCanThreshold)),
+                ("can/bit_rate", F(FloatIndex::DecodeCanBaud)),
+            ],
+        ),
+        (
+            "LIN",
+            vec![
+                ("lin/source", S(StringIndex::DecodeLinSource)),
+                ("lin/threshold", F(FloatIndex::DecodeLinThreshold)),
+                ("lin/baud", F(FloatIndex::DecodeLinBaud)),
+            ],
+        ),
+    ];
+    for (decoder, settings) in decoders.into_iter() {
+        if profile.has_decoder(decoder) {
+            for (name, accessor) in settings {
+                list.push(Parameter::new(format!("decode/{}", name), accessor));
+            }
+        }
+    }
+    if !profile.decoders.is_empty() {
+        list.push(Parameter::new("decode/type", S(StringIndex::DecodeType)));
+        list.push(Parameter::new(
+            "decode/display",
+            B(BooleanIndex::DecodeDisplay),
+        ));
+    }
+
+    //
+    // Bus triggers, checked against the decoder settings, then the trigger mode
+    let bus_triggers: [(&str, Vec<(&str, Accessor)>); 5] = [
+        (
+            "UART",
+            vec![
+                ("uart/condition", S(StringIndex::TriggerUartCondition)),
+                ("uart/data", F(FloatIndex::TriggerUartData)),
+                ("uart/data_mask", F(FloatIndex::TriggerUartDataMask)),
+            ],
+        ),
+        (
+            "IIC",
+            vec![
+                ("i2c/condition", S(StringIndex::TriggerI2cCondition)),
+                ("i2c/address", F(FloatIndex::TriggerI2cAddress)),
+                ("i2c/address_mask", F(FloatIndex::TriggerI2cAddressMask)),
+                ("i2c/data", F(FloatIndex::TriggerI2cData)),
+                ("i2c/data_mask", F(FloatIndex::TriggerI2cDataMask)),
+            ],
+        ),
+        (
+            "SPI",
+            vec![
+                ("spi/condition", S(StringIndex::TriggerSpiCondition)),
+                ("spi/data", F(FloatIndex::TriggerSpiData)),
+                ("spi/data_mask", F(FloatIndex::TriggerSpiDataMask)),
+            ],
+        ),
+        (
+            "CAN",
+            vec![
+                ("can/condition", S(StringIndex::TriggerCanCondition)),
+                ("can/id", F(FloatIndex::TriggerCanId)),
+                ("can/data", F(FloatIndex::TriggerCanData)),
+                ("can/data_mask", F(FloatIndex::TriggerCanDataMask)),
+            ],
+        ),
+        (
+            "LIN",
+            vec![
+                ("lin/condition", S(StringIndex::TriggerLinCondition)),
+                ("lin/id", F(FloatIndex::TriggerLinId)),
+                ("lin/data", F(FloatIndex::TriggerLinData)),
+                ("lin/data_mask", F(FloatIndex::TriggerLinDataMask)),
+            ],
+        ),
+    ];
+    for (decoder, settings) in bus_triggers.into_iter() {
+        if profile.has_decoder(decoder) {
+            for (name, accessor) in settings {
+                list.push(Parameter::new(format!("trigger/bus/{}", name), accessor));
+            }
+        }
+    }
+    list.push(Parameter::new("trigger/mode", S(StringIndex::TriggerMode)));
+
+    //
+    // References
+    for slot in 1..=REFERENCE_SLOTS {
+        list.push(Parameter::new(
+            format!("reference/{}/display", slot),
+            B([
+                BooleanIndex::Reference1Display,
+                BooleanIndex::Reference2Display,
+                BooleanIndex::Reference3Display,
+                BooleanIndex::Reference4Display,
+            ][(slot - 1).min(3)]),
+        ));
+    }
+
+    //
+    // Record, only its settings: recording and playback are actions
+    if profile.has_record {
+        list.push(Parameter::new("record/frames", F(FloatIndex::RecordFrames)));
+        list.push(Parameter::new(
+            "record/interval",
+            F(FloatIndex::RecordInterval),
+        ));
+    }
+
+    //
+    // Generator, the output is enabled last
+    if profile.has_generator {
+        list.push(Parameter::new(
+            "generator/waveform",
+            S(StringIndex::GeneratorWaveform),
+        ));
+        list.push(Parameter::new(
+            "generator/frequency",
+            F(FloatIndex::GeneratorFrequency),
+        ));
+        list.push(Parameter::new(
+            "generator/amplitude",
+            F(FloatIndex::GeneratorAmplitude),
+        ));
+        list.push(Parameter::new(
+            "generator/offset",
+            F(FloatIndex::GeneratorOffset),
+        ));
+        list.push(Parameter::new(
+            "generator/duty",
+            F(FloatIndex::GeneratorDuty),
+        ));
+        list.push(Parameter::new(
+            "generator/output_enable",
+            B(BooleanIndex::GeneratorOutput),
+        ));
+    }
+
+    list
+}
+
+/// Read the value of a parameter
+///
+async fn read(
+    interface: &mut DSO2C10Interface,
+    accessor: Accessor,
+) -> Result<serde_json::Value, Error> {
+    Ok(match accessor {
+        Accessor::Boolean(i) => json!(interface.get_boolean_at(i as usize).await?),
+        Accessor::String(i) => json!(interface.get_string_at(i as usize).await?),
+        Accessor::Float(i) => json!(interface.get_float_at(i as usize).await?),
+    })
+}
+
+/// Apply the value of a parameter
+///
+async fn write(
+    interface: &mut DSO2C10Interface,
+    accessor: Accessor,
+    value: &serde_json::Value,
+) -> Result<(), Error> {
+    let invalid = || Error::InvalidArgument(format!("Invalid value {}", value));
+    match accessor {
+        Accessor::Boolean(i) => {
+            let v = value.as_bool().ok_or_else(invalid)?;
+            interface.set_boolean_at(i as usize, v).await
+        }
+        Accessor::String(i) => {
+            let v = value.as_str().ok_or_else(invalid)?.to_string();
+            interface.set_string_at(i as usize, &v).await
+        }
+        Accessor::Float(i) => {
+            let v = value.as_f64().ok_or_else(invalid)?;
+            interface.set_float_at(i as usize, v).await
+        }
+    }
+}
+
+/// Read every parameter into a JSON document
+///
+/// Parameters that cannot be read are listed in `errors` instead.
+///
+pub async fn snapshot(interface: &mut DSO2C10Interface, profile: &Profile) -> serde_json::Value {
+    let mut values = serde_json::Map::new();
+    let mut errors = serde_json::Map::new();
+    for parameter in parameters(profile) {
+        match read(interface, parameter.accessor).await {
+            Ok(v) => {
+                values.insert(parameter.name, v);
+            }
+            Err(e) => {
+                errors.insert(parameter.name, json!(format!("{:?}", e)));
+            }
+        }
+    }
+    json!({
+        "model": profile.model,
+        "parameters": values,
+        "errors": errors,
+    })
+}
+
+/// Apply a snapshot document in the restore order of the parameters
+///
+/// Parameters absent from the document are left untouched. The report lists the
+/// applied parameters, the failures and the unknown names.
+///
+pub async fn restore(
+    interface: &mut DSO2C10Interface,
+    profile: &Profile,
+    document: &serde_json::Value,
+) -> Result<serde_json::Value, Error> {
+    let values = document
+        .get("parameters")
+        .unwrap_or(document)
+        .as_object()
+        .ok_or(Error::InvalidArgument(
+            "Expected a snapshot document or an object of parameters".to_string(),
+        ))?;
+
+    let list = parameters(profile);
+    let mut applied = Vec::new();
+    let mut errors = serde_json::Map::new();
+    for parameter in list.iter() {
+        if let Some(value) = values.get(&parameter.name) {
+            match write(interface, parameter.accessor, value).await {
+                Ok(_) => applied.push(parameter.name.clone()),
+                Err(e) => {
+                    errors.insert(parameter.name.clone(), json!(format!("{:?}", e)));
+                }
+            }
+        }
+    }
+    let unknown: Vec<&String> = values
+        .keys()
+        .filter(|k| !list.iter().any(|p| &p.name == *k))
+        .collect();
+
+    Ok(json!({
+        "applied": applied,
+        "errors": errors,
+        "unknown": unknown,
+    }))
+}
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+    store: SetupStore,
+) -> Result<(), Error> {
+    let mut class_setup = parent.create_class("setup").finish().await;
+
+    let logger = class_setup.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    //
+    let att_save = class_setup
+        .create_attribute("save")
+        .with_wo()
+        .with_info(&format!(
+            "Write a slot number (1 to {}) to save the current setup inside the scope",
+            SETUP_SLOTS
+        ))
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => setup save",
+        class_setup,
+        att_save,
+        on_slot_command(att_save.clone(), interface.clone(), false)
+    );
+
+    //
+    //
+    let att_recall = class_setup
+        .create_attribute("recall")
+        .with_wo()
+        .with_info(&format!(
+            "Write a slot number (1 to {}) to recall a setup saved inside the scope",
+            SETUP_SLOTS
+        ))
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => setup recall",
+        class_setup,
+        att_recall,
+        on_slot_command(att_recall.clone(), interface.clone(), true)
+    );
+
+    //
+    //
+    let att_snapshot = class_setup
+        .create_attribute("snapshot")
+        .with_rw()
+        .with_info(
+            "Write any value to read every parameter of the instance into a document
+
+    { \"model\": \"DSO2C10\", \"parameters\": { \"channel/1/scale\": \"1V\", ... }, \"errors\": {} }
+",
+        )
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => setup snapshot",
+        class_setup,
+        att_snapshot,
+        on_snapshot_command(att_snapshot.clone(), interface.clone(), profile.clone())
+    );
+
+    //
+    //
+    let att_restore = class_setup
+        .create_attribute("restore")
+        .with_rw()
+        .with_info(
+            "Write a snapshot document to apply it, the report of the restore is published
+
+Parameters are applied in a safe order: probe before scale before offset, timebase
+before trigger. The other attributes are not refreshed by a restore.
+",
+        )
+        .finish_as_json()
+        .await?;
+
+    //
+    spawn_on_command!(
+        "on_command => setup restore",
+        class_setup,
+        att_restore,
+        on_restore_command(att_restore.clone(), interface.clone(), profile.clone())
+    );
+
+    profiles::mount(class_setup.clone(), interface.clone(), profile, store).await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+///
+///
+async fn on_slot_command(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    recall: bool,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        let slot = match command.as_u64() {
+            Some(s) => s as usize,
+            None => {
+                log_warn!(att.logger(), "invalid setup slot '{}'", command);
+                continue;
+            }
+        };
+
+        //
+        // Log
+        log_debug!(att.logger(), "setup slot {} (recall: {})", slot, recall);
+
+        let interface = interface.lock().await;
+        let result = match recall {
+            true => interface.recall_setup(slot).await,
+            false => interface.save_setup(slot).await,
+        };
+        if let Err(e) = result {
+            log_warn!(att.logger(), "setup slot command failed '{:?}'", e);
+        }
+    }
+    Ok(())
+}
+
+///
+///
+async fn on_snapshot_command(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: Profile,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Log
+        log_debug!(att.logger(), "snapshot requested '{:?}'", command);
+
+        let document = snapshot(&mut *interface.lock().await, &profile).await;
+        att.set(document).await?;
+    }
+    Ok(())
+}
+
+///
+///
+async fn on_restore_command(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: Profile,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Log
+        log_debug!(att.logger(), "restore requested");
+
+        let report = restore(&mut *interface.lock().await, &profile, &command).await;
+        match report {
+            Ok(r) => att.set(r).await?,
+            Err(e) => log_warn!(att.logger(), "restore failed '{:?}'", e),
+        }
+    }
+    Ok(())
+}
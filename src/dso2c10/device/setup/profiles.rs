@@ -0,0 +1,225 @@
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer,
+};
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use std::sync::Arc;
+
+use super::{restore, snapshot};
+use crate::dso2c10::device::interface::DSO2C10Interface;
+use crate::dso2c10::setup_store::diff_parameters;
+use crate::dso2c10::{Profile, SetupStore};
+
+/// Operation requested on a named setup
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operation {
+    Save,
+    Delete,
+    Apply,
+    Diff,
+    Export,
+}
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+    store: SetupStore,
+) -> Result<(), Error> {
+    let mut class_profiles = parent.create_class("profiles").finish().await;
+
+    let logger = class_profiles.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    //
+    let att_list = class_profiles
+        .create_attribute("list")
+        .with_rw()
+        .with_info(&format!(
+            "Named setups stored in {:?}, write any value to refresh
+
+    [ {{ \"name\": \"power_ripple\", \"version\": 3, \"model\": \"DSO2C10\" }}, ... ]
+",
+            store.directory()
+        ))
+        .finish_as_json()
+        .await?;
+    publish_list(&mut att_list.clone(), &store).await?;
+
+    //
+    spawn_on_command!(
+        "on_command => profiles list",
+        class_profiles,
+        att_list,
+        on_list_command(att_list.clone(), store.clone())
+    );
+
+    //
+    //
+    for (operation, name, info, rw) in [
+        (
+            Operation::Save,
+            "save",
+            "Write a name to store the current state under it, or an object to store given
+parameters: { \"name\": \"uart_debug_115200\", \"parameters\": { ... } }
+Saving again under a name increments its version.
+",
+            false,
+        ),
+        (
+            Operation::Delete,
+            "delete",
+            "Write a name to remove the setup",
+            false,
+        ),
+        (
+            Operation::Apply,
+            "apply",
+            "Write a name to restore the setup, the report of the restore is published
+The report gives the model the setup was saved on, it may differ from this one.
+",
+            true,
+        ),
+        (
+            Operation::Diff,
+            "diff",
+            "Write a name to compare the setup with the current state
+
+    { \"name\": \"power_ripple\", \"changed\": { \"channel/1/scale\": { \"setup\": \"10mV\",
+      \"instrument\": \"1V\" } }, \"missing\": [] }
+",
+            true,
+        ),
+        (
+            Operation::Export,
+            "export",
+            "Write a name to publish the stored document of the setup",
+            true,
+        ),
+    ] {
+        let builder = class_profiles.create_attribute(name);
+        let builder = match rw {
+            true => builder.with_rw(),
+            false => builder.with_wo(),
+        };
+        let att = builder.with_info(info).finish_as_json().await?;
+
+        //
+        spawn_on_command!(
+            "on_command => profiles",
+            class_profiles,
+            att,
+            on_command(
+                att.clone(),
+                att_list.clone(),
+                interface.clone(),
+                profile.clone(),
+                store.clone(),
+                operation
+            )
+        );
+    }
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+///
+///
+async fn publish_list(att: &mut JsonAttServer, store: &SetupStore) -> Result<(), Error> {
+    let list = store.list().map_err(Error::Generic)?;
+    att.set(serde_json::Value::from(list)).await
+}
+
+///
+///
+async fn on_list_command(mut att: JsonAttServer, store: SetupStore) -> Result<(), Error> {
+    while let Some(_command) = att.pop_cmd().await {
+        if let Err(e) = publish_list(&mut att, &store).await {
+            log_warn!(att.logger(), "profiles list failed '{:?}'", e);
+        }
+    }
+    Ok(())
+}
+
+/// Perform an operation, returns the value to publish
+///
+async fn perform(
+    command: &serde_json::Value,
+    interface: &Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+    store: &SetupStore,
+    operation: Operation,
+) -> Result<Option<serde_json::Value>, Error> {
+    let name = command
+        .as_str()
+        .or_else(|| command["name"].as_str())
+        .ok_or(Error::InvalidArgument(format!(
+            "Expected a setup name, got {}",
+            command
+        )))?;
+
+    match operation {
+        Operation::Save => {
+            let parameters = match command.get("parameters") {
+                Some(p) => p.clone(),
+                None => snapshot(&mut *interface.lock().await, profile).await["parameters"].clone(),
+            };
+            store
+                .save(name, &profile.model, &parameters)
+                .map_err(Error::Generic)?;
+            Ok(None)
+        }
+        Operation::Delete => {
+            store.delete(name).map_err(Error::Generic)?;
+            Ok(None)
+        }
+        Operation::Apply => {
+            let document = store.load(name).map_err(Error::InvalidArgument)?;
+            let mut report = restore(&mut *interface.lock().await, profile, &document).await?;
+            report["name"] = json!(name);
+            report["model"] = document["model"].clone();
+            Ok(Some(report))
+        }
+        Operation::Diff => {
+            let document = store.load(name).map_err(Error::InvalidArgument)?;
+            let current = snapshot(&mut *interface.lock().await, profile).await;
+            let mut diff = diff_parameters(&document["parameters"], &current["parameters"]);
+            diff["name"] = json!(name);
+            Ok(Some(diff))
+        }
+        Operation::Export => Ok(Some(store.load(name).map_err(Error::InvalidArgument)?)),
+    }
+}
+
+///
+///
+async fn on_command(
+    mut att: JsonAttServer,
+    mut att_list: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: Profile,
+    store: SetupStore,
+    operation: Operation,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Log
+        log_debug!(att.logger(), "profiles {:?} '{:?}'", operation, command);
+
+        match perform(&command, &interface, &profile, &store, operation).await {
+            Ok(Some(value)) => att.set(value).await?,
+            Ok(None) => publish_list(&mut att_list, &store).await?,
+            Err(e) => log_warn!(att.logger(), "profiles {:?} failed '{:?}'", operation, e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use tokio::sync::Mutex;
+
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::{log_debug_mount_end, log_debug_mount_start, Container, Error};
+use std::sync::Arc;
+
+use crate::dso2c10::device::interface::FloatIndex;
+use crate::dso2c10::device::interface::StringIndex;
+use crate::dso2c10::device::interface::TIMEBASE_MODE_CHOICES;
+use crate::si as std_att_si;
+use crate::si::SiFormat;
+
+use super::interface::DSO2C10Interface;
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    let class_timebase = parent.create_class("timebase").finish().await;
+
+    let logger = class_timebase.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    //
+    std_att_enum::mount(
+        class_timebase.clone(),
+        interface.clone(),
+        StringIndex::TimebaseMode as usize,
+        "mode",
+        "
+    * MAIN: triggered acquisitions
+    * ROLL: the waveform scrolls from right to left without trigger, for slow
+      timebases (e.g. 100ms/div), see 'roll' to stream the scrolled points
+        ",
+        TIMEBASE_MODE_CHOICES.to_vec(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_timebase.clone(),
+        interface.clone(),
+        FloatIndex::TimebaseScale as usize,
+        "scale",
+        "Horizontal scale, time per division",
+        SiFormat::new("s", 0.000000002, 1000.0, 9),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_timebase.clone(),
+        interface.clone(),
+        FloatIndex::TimebasePosition as usize,
+        "position",
+        "Horizontal position of the trigger point relative to the center of the screen",
+        SiFormat::new("s", -1000.0, 1000.0, 9),
+    )
+    .await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
@@ -0,0 +1,97 @@
+mod bus;
+
+use tokio::sync::Mutex;
+
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::{log_debug_mount_end, log_debug_mount_start, Container, Error};
+use std::sync::Arc;
+
+use crate::dso2c10::device::interface::FloatIndex;
+use crate::dso2c10::device::interface::StringIndex;
+use crate::dso2c10::device::interface::TRIGGER_SWEEP_CHOICES;
+use crate::dso2c10::Profile;
+use crate::si as std_att_si;
+use crate::si::SiFormat;
+
+use super::interface::DSO2C10Interface;
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+) -> Result<(), Error> {
+    let class_trigger = parent.create_class("trigger").finish().await;
+
+    let logger = class_trigger.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    //
+    std_att_enum::mount(
+        class_trigger.clone(),
+        interface.clone(),
+        StringIndex::TriggerMode as usize,
+        "mode",
+        "
+    * EDGE: edge trigger on 'source' at 'level'
+    * UART, IIC, SPI, CAN, LIN: serial bus trigger configured in 'bus', the bus
+      lines and format are the ones of the decoder which must be set to this bus
+        ",
+        profile.trigger_modes(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_trigger.clone(),
+        interface.clone(),
+        StringIndex::TriggerSource as usize,
+        "source",
+        "Source of the edge trigger
+    * CHANnel<n>: analog channel
+    * EXT: external trigger input, on models that provide it
+        ",
+        profile.trigger_sources(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_trigger.clone(),
+        interface.clone(),
+        StringIndex::TriggerSweep as usize,
+        "sweep",
+        "
+    * AUTO: the scope triggers by itself when no trigger condition is found
+    * NORMal: the scope only triggers on the trigger condition
+    * SINGle: the scope triggers once on the trigger condition and stops
+        ",
+        TRIGGER_SWEEP_CHOICES.to_vec(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_trigger.clone(),
+        interface.clone(),
+        FloatIndex::TriggerLevel as usize,
+        "level",
+        "Level of the edge trigger",
+        SiFormat::new("V", -400.0, 400.0, 3),
+    )
+    .await?;
+
+    if !profile.decoders.is_empty() {
+        bus::mount(class_trigger.clone(), interface.clone(), profile).await?;
+    }
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
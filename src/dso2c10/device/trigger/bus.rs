@@ -0,0 +1,432 @@
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer,
+};
+use std::sync::Arc;
+
+use crate::dso2c10::device::interface::DSO2C10Interface;
+use crate::dso2c10::device::interface::FloatIndex;
+use crate::dso2c10::device::interface::StringIndex;
+use crate::dso2c10::device::interface::TRIGGER_CAN_CONDITION_CHOICES;
+use crate::dso2c10::device::interface::TRIGGER_I2C_CONDITION_CHOICES;
+use crate::dso2c10::device::interface::TRIGGER_LIN_CONDITION_CHOICES;
+use crate::dso2c10::device::interface::TRIGGER_SPI_CONDITION_CHOICES;
+use crate::dso2c10::device::interface::TRIGGER_UART_CONDITION_CHOICES;
+use crate::dso2c10::Profile;
+use crate::si as std_att_si;
+use crate::si::FloatAccessorModel;
+use crate::si::SiFormat;
+
+/// Format of a data byte and of its mask
+///
+const BYTE_FORMAT: SiFormat = SiFormat::new("", 0.0, 255.0, 0);
+
+/// Format of a UART character and of its mask
+///
+/// The range covers characters up to 9 bits, the value is checked against the
+/// data bits of the decoder when it is written.
+///
+const CHARACTER_FORMAT: SiFormat = SiFormat::new("", 0.0, 511.0, 0);
+
+/// Mount the serial bus triggers, one class per bus decoder of the model
+///
+/// The scope compares the values after applying the masks, a mask bit at 0
+/// makes the bit a don't care. Values are checked against the decoder settings
+/// (e.g. UART data bits, I2C address width) when they are written.
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    profile: &Profile,
+) -> Result<(), Error> {
+    let class_bus = parent.create_class("bus").finish().await;
+
+    let logger = class_bus.logger().clone();
+    log_debug_mount_start!(logger);
+
+    if profile.has_decoder("UART") {
+        mount_uart(class_bus.clone(), interface.clone()).await?;
+    }
+    if profile.has_decoder("IIC") {
+        mount_i2c(class_bus.clone(), interface.clone()).await?;
+    }
+    if profile.has_decoder("SPI") {
+        mount_spi(class_bus.clone(), interface.clone()).await?;
+    }
+    if profile.has_decoder("CAN") {
+        mount_can(class_bus.clone(), interface.clone()).await?;
+    }
+    if profile.has_decoder("LIN") {
+        mount_lin(class_bus.clone(), interface.clone()).await?;
+    }
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+///
+///
+async fn mount_uart<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    let class_uart = parent.create_class("uart").finish().await;
+
+    std_att_enum::mount(
+        class_uart.clone(),
+        interface.clone(),
+        StringIndex::TriggerUartCondition as usize,
+        "condition",
+        "
+    * STARt: start bit of any character
+    * STOP: stop bit of any character
+    * DATA: character matching 'data' and 'data_mask'
+    * ERRor: parity or framing error
+        ",
+        TRIGGER_UART_CONDITION_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_uart.clone(),
+        interface.clone(),
+        FloatIndex::TriggerUartData as usize,
+        "data",
+        "Character to trigger on, within the data bits of the decoder",
+        CHARACTER_FORMAT,
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_uart.clone(),
+        interface.clone(),
+        FloatIndex::TriggerUartDataMask as usize,
+        "data_mask",
+        "Bits of 'data' compared by the trigger, a bit at 0 is a don't care",
+        CHARACTER_FORMAT,
+    )
+    .await?;
+
+    Ok(())
+}
+
+///
+///
+async fn mount_i2c<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    let class_i2c = parent.create_class("i2c").finish().await;
+
+    std_att_enum::mount(
+        class_i2c.clone(),
+        interface.clone(),
+        StringIndex::TriggerI2cCondition as usize,
+        "condition",
+        "
+    * STARt, STOP, RESTart: bus conditions
+    * NACK: byte not acknowledged
+    * ADDRess: address matching 'address' and 'address_mask'
+    * DATA: byte matching 'data' and 'data_mask'
+    * ADATa: address then first data byte matching both
+        ",
+        TRIGGER_I2C_CONDITION_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_i2c.clone(),
+        interface.clone(),
+        FloatIndex::TriggerI2cAddress as usize,
+        "address",
+        "Address without the R/W bit, within the address width of the decoder",
+        SiFormat::new("", 0.0, 1023.0, 0),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_i2c.clone(),
+        interface.clone(),
+        FloatIndex::TriggerI2cAddressMask as usize,
+        "address_mask",
+        "Bits of 'address' compared by the trigger, a bit at 0 is a don't care",
+        SiFormat::new("", 0.0, 1023.0, 0),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_i2c.clone(),
+        interface.clone(),
+        FloatIndex::TriggerI2cData as usize,
+        "data",
+        "Data byte to trigger on",
+        BYTE_FORMAT,
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_i2c.clone(),
+        interface.clone(),
+        FloatIndex::TriggerI2cDataMask as usize,
+        "data_mask",
+        "Bits of 'data' compared by the trigger, a bit at 0 is a don't care",
+        BYTE_FORMAT,
+    )
+    .await?;
+
+    Ok(())
+}
+
+///
+///
+async fn mount_spi<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    let class_spi = parent.create_class("spi").finish().await;
+
+    std_att_enum::mount(
+        class_spi.clone(),
+        interface.clone(),
+        StringIndex::TriggerSpiCondition as usize,
+        "condition",
+        "
+    * STARt: chip select activation
+    * DATA: MOSI word matching 'data' and 'data_mask'
+        ",
+        TRIGGER_SPI_CONDITION_CHOICES.to_vec(),
+    )
+    .await?;
+
+    mount_word(
+        class_spi.clone(),
+        interface.clone(),
+        FloatIndex::TriggerSpiData,
+        "data",
+        "Word to trigger on, up to 32 bits in the bit order of the decoder",
+    )
+    .await?;
+
+    mount_word(
+        class_spi.clone(),
+        interface.clone(),
+        FloatIndex::TriggerSpiDataMask,
+        "data_mask",
+        "Bits of 'data' compared by the trigger, a bit at 0 is a don't care",
+    )
+    .await?;
+
+    Ok(())
+}
+
+///
+///
+async fn mount_can<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    let class_can = parent.create_class("can").finish().await;
+
+    std_att_enum::mount(
+        class_can.clone(),
+        interface.clone(),
+        StringIndex::TriggerCanCondition as usize,
+        "condition",
+        "
+    * STARt: start of frame
+    * ID: frame with the identifier 'id'
+    * DATA: frame whose first data byte matches 'data' and 'data_mask'
+    * IDData: both
+    * ERRor: error frame
+        ",
+        TRIGGER_CAN_CONDITION_CHOICES.to_vec(),
+    )
+    .await?;
+
+    mount_word(
+        class_can.clone(),
+        interface.clone(),
+        FloatIndex::TriggerCanId,
+        "id",
+        "Frame identifier, 11 bits standard or 29 bits extended",
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_can.clone(),
+        interface.clone(),
+        FloatIndex::TriggerCanData as usize,
+        "data",
+        "Data byte to trigger on",
+        BYTE_FORMAT,
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_can.clone(),
+        interface.clone(),
+        FloatIndex::TriggerCanDataMask as usize,
+        "data_mask",
+        "Bits of 'data' compared by the trigger, a bit at 0 is a don't care",
+        BYTE_FORMAT,
+    )
+    .await?;
+
+    Ok(())
+}
+
+///
+///
+async fn mount_lin<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+) -> Result<(), Error> {
+    let class_lin = parent.create_class("lin").finish().await;
+
+    std_att_enum::mount(
+        class_lin.clone(),
+        interface.clone(),
+        StringIndex::TriggerLinCondition as usize,
+        "condition",
+        "
+    * SYNC: sync field of any frame
+    * ID: frame with the identifier 'id'
+    * DATA: frame whose first data byte matches 'data' and 'data_mask'
+    * IDData: both
+    * ERRor: checksum or sync error
+        ",
+        TRIGGER_LIN_CONDITION_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_lin.clone(),
+        interface.clone(),
+        FloatIndex::TriggerLinId as usize,
+        "id",
+        "Frame identifier, 6 bits",
+        SiFormat::new("", 0.0, 63.0, 0),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_lin.clone(),
+        interface.clone(),
+        FloatIndex::TriggerLinData as usize,
+        "data",
+        "Data byte to trigger on",
+        BYTE_FORMAT,
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_lin.clone(),
+        interface.clone(),
+        FloatIndex::TriggerLinDataMask as usize,
+        "data_mask",
+        "Bits of 'data' compared by the trigger, a bit at 0 is a don't care",
+        BYTE_FORMAT,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Mount a bus value wider than the precision of a SI attribute (e.g. a 32
+/// bits SPI word, a 29 bits CAN identifier)
+///
+/// The value is published as a JSON integer. An integer or a hexadecimal string
+/// (e.g. "0x1FFFFFFF") is accepted when it is written.
+///
+async fn mount_word<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    index: FloatIndex,
+    name: &str,
+    info: &str,
+) -> Result<(), Error> {
+    let mut att = parent
+        .create_attribute(name)
+        .with_rw()
+        .with_info(info)
+        .finish_as_json()
+        .await?;
+
+    // Set the value, an instrument error leaves it unset without failing the mount
+    let value = interface.lock().await.get_float_at(index as usize).await;
+    match value {
+        Ok(v) => att.set(json!(v as u64)).await?,
+        Err(e) => log_warn!(att.logger(), "bus value initial read failed '{:?}'", e),
+    }
+
+    //
+    spawn_on_command!(
+        "on_command => bus value",
+        parent,
+        att,
+        on_word_command(att.clone(), interface.clone(), index)
+    );
+
+    Ok(())
+}
+
+/// Value of a command, a JSON integer or a decimal or hexadecimal string
+///
+fn word_of(command: &serde_json::Value) -> Result<u64, String> {
+    if let Some(v) = command.as_u64() {
+        return Ok(v);
+    }
+    let text = command
+        .as_str()
+        .ok_or(format!("Expected an integer, got {}", command))?
+        .trim();
+    let result = match text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => text.parse::<u64>(),
+    };
+    result.map_err(|_| format!("Invalid integer {:?}", text))
+}
+
+///
+///
+async fn on_word_command(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<DSO2C10Interface>>,
+    index: FloatIndex,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        let value = match word_of(&command) {
+            Ok(v) => v,
+            Err(e) => {
+                log_warn!(att.logger(), "invalid bus value '{}'", e);
+                continue;
+            }
+        };
+
+        //
+        // Log
+        log_debug!(att.logger(), "bus value command received '{:#X}'", value);
+
+        //
+        // The interface keeps the value as f64, exact up to 2^53
+        let mut interface = interface.lock().await;
+        if let Err(e) = interface.set_float_at(index as usize, value as f64).await {
+            log_warn!(att.logger(), "bus value command failed '{:?}'", e);
+            continue;
+        }
+
+        //
+        // Set the read back value
+        match interface.get_float_at(index as usize).await {
+            Ok(v) => att.set(json!(v as u64)).await?,
+            Err(e) => log_warn!(att.logger(), "bus value read back failed '{:?}'", e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,39 @@
+/// Identity of the instrument, as returned by `*IDN?`
+///
+/// The reply is `<manufacturer>,<model>,<serial>,<firmware>`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identity {
+    /// Manufacturer name
+    ///
+    pub manufacturer: String,
+
+    /// Model name (e.g. DSO2C10)
+    ///
+    pub model: String,
+
+    /// Serial number
+    ///
+    pub serial: String,
+
+    /// Firmware version string
+    ///
+    pub firmware: String,
+}
+
+impl Identity {
+    /// Parse a `*IDN?` reply
+    ///
+    pub fn parse(idn: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = idn.trim().splitn(4, ',').map(|f| f.trim()).collect();
+        if fields.len() < 4 {
+            return Err(format!("Invalid IDN reply: {:?}", idn));
+        }
+        Ok(Self {
+            manufacturer: fields[0].to_string(),
+            model: fields[1].to_uppercase(),
+            serial: fields[2].to_string(),
+            firmware: fields[3].to_string(),
+        })
+    }
+}
@@ -0,0 +1,133 @@
+/// Points read per `WAVeform:DATA?` during a memory readout
+///
+pub const MEMORY_CHUNK_POINTS: usize = 250_000;
+
+/// Readout of the acquisition memory in chunks of points
+///
+/// Chunks are requested in order with `WAVeform:STARt` and `WAVeform:STOP`, both
+/// 1-based and inclusive, and appended into one contiguous buffer.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryReadout {
+    /// Number of points of the record, from the preamble
+    ///
+    total: usize,
+
+    /// Maximal number of points of a chunk
+    ///
+    chunk_points: usize,
+
+    /// Codes received so far
+    ///
+    data: Vec<u8>,
+}
+
+impl MemoryReadout {
+    /// Prepare the readout of `total` points
+    ///
+    pub fn new(total: usize, chunk_points: usize) -> Result<Self, String> {
+        if total == 0 {
+            return Err("The acquisition memory is empty".to_string());
+        }
+        if chunk_points == 0 {
+            return Err("Chunks must contain at least one point".to_string());
+        }
+        Ok(Self {
+            total,
+            chunk_points,
+            data: Vec::with_capacity(total),
+        })
+    }
+
+    /// Number of points of the record
+    ///
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Number of points received so far
+    ///
+    pub fn points_read(&self) -> usize {
+        self.data.len()
+    }
+
+    /// First and last points of the next chunk, None once the record is complete
+    ///
+    pub fn next_chunk(&self) -> Option<(usize, usize)> {
+        if self.data.len() >= self.total {
+            return None;
+        }
+        let start = self.data.len() + 1;
+        let stop = (self.data.len() + self.chunk_points).min(self.total);
+        Some((start, stop))
+    }
+
+    /// Append the codes of the chunk given by `next_chunk`
+    ///
+    pub fn push(&mut self, codes: &[u8]) -> Result<(), String> {
+        let (start, stop) = self
+            .next_chunk()
+            .ok_or("The record is already complete".to_string())?;
+        let expected = stop - start + 1;
+        if codes.len() != expected {
+            return Err(format!(
+                "Chunk {}..{} has {} points, {} expected",
+                start,
+                stop,
+                codes.len(),
+                expected
+            ));
+        }
+        self.data.extend_from_slice(codes);
+        Ok(())
+    }
+
+    /// Codes of the whole record, checked against the number of points
+    ///
+    pub fn finish(self) -> Result<Vec<u8>, String> {
+        if self.data.len() != self.total {
+            return Err(format!(
+                "Readout incomplete, {} points received out of {}",
+                self.data.len(),
+                self.total
+            ));
+        }
+        Ok(self.data)
+    }
+}
+
+/// Progress of a memory readout, shared with the attributes while the readout
+/// runs
+///
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReadoutProgress {
+    /// Points received by the running (or last) readout
+    ///
+    pub points_read: usize,
+
+    /// Points of the running (or last) readout, 0 before the first one
+    ///
+    pub points_total: usize,
+
+    /// Set to stop the running readout before its next chunk
+    ///
+    pub cancel_requested: bool,
+}
+
+impl ReadoutProgress {
+    /// Restart the progress for a readout of `total` points
+    ///
+    pub fn start(&mut self, total: usize) {
+        self.points_read = 0;
+        self.points_total = total;
+    }
+
+    /// Ratio of the points received, from 0 to 1
+    ///
+    pub fn ratio(&self) -> f64 {
+        match self.points_total {
+            0 => 0.0,
+            total => self.points_read as f64 / total as f64,
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use super::Identity;
+
+/// How the channels limit their bandwidth
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum BandwidthLimit {
+    /// No bandwidth limit
+    ///
+    None,
+    /// 20MHz limit switched ON/OFF
+    ///
+    Boolean,
+    /// Selectable limits (SCPI mnemonics, including OFF)
+    ///
+    Choices(Vec<&'static str>),
+}
+
+/// Capability profile of an instrument
+///
+/// Drives which classes and attributes are mounted for a given model. The
+/// capabilities are keyed on the model only, the firmware of the identity is
+/// logged but does not select any of them.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    /// Model name the profile has been built for
+    ///
+    pub model: String,
+
+    /// Number of analog channels
+    ///
+    pub channel_count: usize,
+
+    /// Analog bandwidth in MHz
+    ///
+    pub bandwidth_mhz: u32,
+
+    /// Bandwidth limit provided by the channels
+    ///
+    pub bandwidth_limit: BandwidthLimit,
+
+    /// True if the instrument has an external trigger input
+    ///
+    pub has_external_trigger: bool,
+
+    /// True if the instrument embeds a DDS function generator
+    ///
+    pub has_generator: bool,
+
+    /// Bus decoders available on the instrument (SCPI mnemonics)
+    ///
+    pub decoders: Vec<&'static str>,
+
+    /// True if the instrument records sequences of triggered frames
+    ///
+    pub has_record: bool,
+}
+
+impl Profile {
+    /// Build the profile matching an identity
+    ///
+    pub fn from_identity(identity: &Identity) -> Self {
+        Self::from_model(&identity.model)
+    }
+
+    /// Build the profile from the model name only
+    ///
+    /// DSO2000 models are named `DSO2<C|D><bandwidth/10>`, the `D` variants embed
+    /// the generator.
+    /// DSO4000C models are named `DSO4<bandwidth/10><channels>C`, they provide an
+    /// external trigger input and extra bandwidth limits.
+    /// Unknown models get a conservative 2 channels profile.
+    ///
+    pub fn from_model(model: &str) -> Self {
+        let model = model.to_uppercase();
+        let mut profile = Self::conservative(&model);
+
+        if let Some(variant) = model.strip_prefix("DSO2") {
+            let mut chars = variant.chars();
+            let kind = chars.next();
+            if let Ok(bw) = chars.as_str().parse::<u32>() {
+                profile.bandwidth_mhz = bw * 10;
+            }
+            profile.has_generator = kind == Some('D');
+            profile.decoders = vec!["UART", "IIC", "SPI", "CAN", "LIN"];
+            profile.has_record = true;
+        } else if let Some(variant) = model.strip_prefix("DSO4").and_then(|v| v.strip_suffix('C')) {
+            if variant.len() >= 2 {
+                let (bw, channels) = variant.split_at(variant.len() - 1);
+                if let (Ok(bw), Ok(channels)) = (bw.parse::<u32>(), channels.parse::<usize>()) {
+                    profile.bandwidth_mhz = bw * 10;
+                    profile.channel_count = channels.clamp(1, 4);
+                }
+            }
+            profile.bandwidth_limit = match profile.bandwidth_mhz > 100 {
+                true => BandwidthLimit::Choices(vec!["OFF", "20M", "100M"]),
+                false => BandwidthLimit::Choices(vec!["OFF", "20M"]),
+            };
+            profile.has_external_trigger = true;
+            profile.decoders = vec!["UART", "IIC", "SPI", "CAN", "LIN"];
+            profile.has_record = true;
+        }
+
+        profile
+    }
+
+    /// Profile used when the model is unknown
+    ///
+    pub fn conservative(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            channel_count: 2,
+            bandwidth_mhz: 100,
+            bandwidth_limit: BandwidthLimit::Boolean,
+            has_external_trigger: false,
+            has_generator: false,
+            decoders: Vec::new(),
+            has_record: false,
+        }
+    }
+
+    /// Analog channels available on the instrument (SCPI mnemonics)
+    ///
+    pub fn channel_sources(&self) -> Vec<&'static str> {
+        ["CHANnel1", "CHANnel2", "CHANnel3", "CHANnel4"]
+            .into_iter()
+            .take(self.channel_count)
+            .collect()
+    }
+
+    /// Trigger sources available on the instrument (SCPI mnemonics)
+    ///
+    pub fn trigger_sources(&self) -> Vec<&'static str> {
+        let mut sources = self.channel_sources();
+        if self.has_external_trigger {
+            sources.push("EXT");
+        }
+        sources
+    }
+
+    /// Trigger modes available on the instrument, the edge trigger then a trigger
+    /// for each bus decoder
+    ///
+    pub fn trigger_modes(&self) -> Vec<&'static str> {
+        let mut modes = vec!["EDGE"];
+        modes.extend(self.decoders.iter().cloned());
+        modes
+    }
+
+    /// True if the given bus decoder is available
+    ///
+    pub fn has_decoder(&self, decoder: &str) -> bool {
+        self.decoders
+            .iter()
+            .any(|d| d.eq_ignore_ascii_case(decoder))
+    }
+}
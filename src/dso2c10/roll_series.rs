@@ -0,0 +1,396 @@
+use serde_json::json;
+use std::collections::VecDeque;
+
+use crate::waveform::Waveform;
+
+/// Largest number of points kept by a roll series, the oldest are dropped first
+///
+pub const MAX_SERIES_POINTS: usize = 10_000_000;
+
+/// Points that must match to align two successive reads
+///
+const MIN_OVERLAP: usize = 16;
+
+/// Largest difference between two samples considered as the same point, the
+/// points of both reads come from the same ADC codes
+///
+const MATCH_TOLERANCE: f64 = 1e-9;
+
+/// Largest difference between the matched shift and the shift expected from the
+/// host time, relative to the expected shift. The host time also counts the
+/// transfer of the reads.
+///
+const SHIFT_TOLERANCE: f64 = 0.25;
+
+/// Lower bound of the shift tolerance, in points, for reads close in time
+///
+const MIN_SHIFT_TOLERANCE: f64 = 16.0;
+
+/// Points appended to a roll series by a read
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollChunk {
+    /// Index of the first new point in the series, counted from the start
+    ///
+    pub index: usize,
+
+    /// Time of the first new point, in seconds since the start of the series
+    ///
+    pub time: f64,
+
+    /// Time between two points, in seconds
+    ///
+    pub x_increment: f64,
+
+    /// Unit of the samples (e.g. V)
+    ///
+    pub unit: String,
+
+    /// New samples, the oldest first
+    ///
+    pub samples: Vec<f64>,
+
+    /// True if the read did not overlap the previous one, points are missing
+    /// before this chunk
+    ///
+    pub gap: bool,
+}
+
+impl RollChunk {
+    /// JSON representation published on the attributes
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "index": self.index,
+            "time": self.time,
+            "x_increment": self.x_increment,
+            "unit": self.unit,
+            "samples": self.samples,
+            "gap": self.gap,
+        })
+    }
+}
+
+/// Continuous record built from the successive screen reads of the ROLL mode
+///
+/// Each read holds the whole screen, it overlaps the previous read except for
+/// the points scrolled in meanwhile. The overlap is found by matching the
+/// samples near the shift expected from the host time between the reads, which
+/// also selects the shift when several match (e.g. a flat or periodic signal).
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollSeries {
+    /// Maximal number of points kept
+    ///
+    max_points: usize,
+
+    /// Last read and its host time, in seconds since the start of the series
+    ///
+    previous: Option<(Waveform, f64)>,
+
+    /// Points kept, the oldest first
+    ///
+    samples: VecDeque<f64>,
+
+    /// Points dropped from the start to respect `max_points`
+    ///
+    dropped: usize,
+
+    /// Time of the next point, in seconds since the start of the series
+    ///
+    next_time: f64,
+
+    /// Index of the first point after each gap, for the points kept
+    ///
+    gaps: Vec<usize>,
+
+    /// Gaps since the start, including the ones before the points kept
+    ///
+    gap_count: usize,
+}
+
+impl RollSeries {
+    /// Create an empty series, `max_points` is clamped to 1..=MAX_SERIES_POINTS
+    ///
+    pub fn new(max_points: usize) -> Self {
+        Self {
+            max_points: max_points.clamp(1, MAX_SERIES_POINTS),
+            previous: None,
+            samples: VecDeque::new(),
+            dropped: 0,
+            next_time: 0.0,
+            gaps: Vec::new(),
+            gap_count: 0,
+        }
+    }
+
+    /// Forget the points and restart the series
+    ///
+    pub fn clear(&mut self) {
+        *self = Self::new(self.max_points);
+    }
+
+    /// Maximal number of points kept
+    ///
+    pub fn max_points(&self) -> usize {
+        self.max_points
+    }
+
+    /// Number of points appended since the start, including the dropped ones
+    ///
+    pub fn total(&self) -> usize {
+        self.dropped + self.samples.len()
+    }
+
+    /// Number of points kept
+    ///
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// True if no point is kept
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Index of the first point after each gap, for the points kept
+    ///
+    pub fn gaps(&self) -> &[usize] {
+        &self.gaps
+    }
+
+    /// Number of gaps since the start, including the dropped ones
+    ///
+    pub fn gap_count(&self) -> usize {
+        self.gap_count
+    }
+
+    /// Append the points of a read that are not in the previous one
+    ///
+    /// `time` is the host time of the read, in seconds since the start of the
+    /// series. A change of timebase or unit is a gap, the points kept before it
+    /// are dropped.
+    ///
+    pub fn push(&mut self, read: Waveform, time: f64) -> Result<RollChunk, String> {
+        if read.is_empty() {
+            return Err("The read has no point".to_string());
+        }
+        if !read.x_increment.is_finite() || read.x_increment <= 0.0 {
+            return Err(format!("Invalid x_increment {}", read.x_increment));
+        }
+
+        let (new_points, gap) = match &self.previous {
+            None => (read.len(), false),
+            Some((previous, previous_time)) => {
+                let same_increment = (previous.x_increment - read.x_increment).abs()
+                    <= read.x_increment * MATCH_TOLERANCE;
+                let expected = (time - previous_time) / read.x_increment;
+                match same_increment && previous.unit == read.unit {
+                    true => match new_points(&previous.samples, &read.samples, expected) {
+                        Some(n) => (n, false),
+                        None => (read.len(), true),
+                    },
+                    //
+                    // The kept points cannot share a time axis with the new ones
+                    false => {
+                        self.dropped += self.samples.len();
+                        self.samples.clear();
+                        (read.len(), true)
+                    }
+                }
+            }
+        };
+
+        //
+        // After a gap the time of the chunk is estimated from the host time
+        let index = self.total();
+        if gap {
+            self.gaps.push(index);
+            self.gap_count += 1;
+            let estimated = time - read.duration();
+            self.next_time = self.next_time.max(estimated);
+        }
+        let chunk = RollChunk {
+            index,
+            time: self.next_time,
+            x_increment: read.x_increment,
+            unit: read.unit.clone(),
+            samples: read.samples[read.len() - new_points..].to_vec(),
+            gap,
+        };
+
+        self.samples.extend(chunk.samples.iter());
+        self.next_time += new_points as f64 * read.x_increment;
+        while self.samples.len() > self.max_points {
+            self.samples.pop_front();
+            self.dropped += 1;
+        }
+
+        //
+        // Gaps before the points kept are only counted
+        let obsolete = self.gaps.partition_point(|&g| g < self.dropped);
+        self.gaps.drain(..obsolete);
+        self.previous = Some((read, time));
+        Ok(chunk)
+    }
+
+    /// Points kept as one waveform, the time origin is the start of the series
+    ///
+    /// The time axis is exact for the points after the last gap, it assumes
+    /// continuous points so the points before a gap appear later than captured.
+    ///
+    pub fn to_waveform(&self) -> Option<Waveform> {
+        let (previous, _) = self.previous.as_ref()?;
+        let first_time = self.next_time - self.samples.len() as f64 * previous.x_increment;
+        Some(Waveform::new(
+            self.samples.iter().cloned().collect(),
+            first_time,
+            previous.x_increment,
+            previous.unit.clone(),
+        ))
+    }
+}
+
+/// Number of points at the end of `current` that are not in `previous`
+///
+/// A shift is valid when the last points of `previous` match the first points of
+/// `current` and it is close to `expected`. Among the valid shifts the closest to
+/// `expected` is selected. None if no shift is valid or if more points than the
+/// screen holds were expected, the reads do not overlap.
+///
+fn new_points(previous: &[f64], current: &[f64], expected: f64) -> Option<usize> {
+    let min_overlap = MIN_OVERLAP.min(current.len()).min(previous.len());
+    if min_overlap == 0 {
+        return None;
+    }
+
+    let first_shift = current.len().saturating_sub(previous.len());
+    let last_shift = current.len() - min_overlap;
+    if expected > last_shift as f64 {
+        return None;
+    }
+
+    let matches = |shift: usize| {
+        let overlap = current.len() - shift;
+        previous[previous.len() - overlap..]
+            .iter()
+            .zip(&current[..overlap])
+            .all(|(a, b)| (a - b).abs() <= MATCH_TOLERANCE)
+    };
+
+    let tolerance = (expected * SHIFT_TOLERANCE).max(MIN_SHIFT_TOLERANCE);
+    (first_shift..=last_shift)
+        .filter(|shift| (*shift as f64 - expected).abs() <= tolerance)
+        .filter(|shift| matches(*shift))
+        .min_by(|a, b| {
+            let da = (*a as f64 - expected).abs();
+            let db = (*b as f64 - expected).abs();
+            da.total_cmp(&db)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Screen of `len` points of a signal, starting at the point `offset`
+    ///
+    fn screen(signal: impl Fn(usize) -> f64, offset: usize, len: usize) -> Waveform {
+        let samples = (offset..offset + len).map(signal).collect();
+        Waveform::new(samples, 0.0, 0.01, "V")
+    }
+
+    fn ramp(i: usize) -> f64 {
+        i as f64 * 1e-3
+    }
+
+    fn ripple(i: usize) -> f64 {
+        ((i % 101) as f64 / 101.0 * std::f64::consts::TAU).sin()
+    }
+
+    #[test]
+    fn append_overlapping_reads() {
+        let mut series = RollSeries::new(1000);
+        let first = series.push(screen(ramp, 0, 100), 1.0).unwrap();
+        assert_eq!(first.samples.len(), 100);
+        assert!(!first.gap);
+
+        let chunk = series.push(screen(ramp, 30, 100), 1.3).unwrap();
+        assert_eq!(chunk.index, 100);
+        assert_eq!(chunk.samples, (100..130).map(ramp).collect::<Vec<f64>>());
+        assert!(!chunk.gap);
+        assert!((chunk.time - 1.0).abs() < 1e-9);
+        assert_eq!(series.total(), 130);
+        assert!(series.gaps().is_empty());
+    }
+
+    #[test]
+    fn report_reads_without_overlap() {
+        let mut series = RollSeries::new(1000);
+        series.push(screen(ramp, 0, 100), 1.0).unwrap();
+        let chunk = series.push(screen(ramp, 500, 100), 6.0).unwrap();
+        assert!(chunk.gap);
+        assert_eq!(chunk.samples.len(), 100);
+        assert_eq!(series.gaps(), &[100]);
+        assert_eq!(series.gap_count(), 1);
+        //
+        // The time of the chunk is estimated from the host time
+        assert!((chunk.time - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn align_flat_signal_on_host_time() {
+        let mut series = RollSeries::new(1000);
+        series.push(screen(|_| 3.7, 0, 100), 1.0).unwrap();
+        let chunk = series.push(screen(|_| 3.7, 20, 100), 1.2).unwrap();
+        assert!(!chunk.gap);
+        assert_eq!(chunk.samples.len(), 20);
+    }
+
+    #[test]
+    fn report_periodic_signal_scrolled_past_the_screen() {
+        let mut series = RollSeries::new(10_000);
+        series.push(screen(ripple, 400, 700), 4.0).unwrap();
+        let chunk = series.push(screen(ripple, 3000, 700), 30.0).unwrap();
+        assert!(chunk.gap);
+        assert_eq!(chunk.samples.len(), 700);
+        assert!((chunk.time - (30.0 - 7.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn align_periodic_signal_near_host_time() {
+        let mut series = RollSeries::new(10_000);
+        series.push(screen(ripple, 400, 700), 4.0).unwrap();
+        //
+        // Shifts of 57, 158, 259... all match, the host time selects 259
+        let chunk = series.push(screen(ripple, 659, 700), 6.65).unwrap();
+        assert!(!chunk.gap);
+        assert_eq!(chunk.samples.len(), 259);
+    }
+
+    #[test]
+    fn restart_on_unit_change() {
+        let mut series = RollSeries::new(1000);
+        series.push(screen(ramp, 0, 100), 1.0).unwrap();
+        let mut read = screen(ramp, 10, 100);
+        read.unit = "A".to_string();
+        let chunk = series.push(read, 1.1).unwrap();
+        assert!(chunk.gap);
+        assert_eq!(series.len(), 100);
+        assert_eq!(series.total(), 200);
+        assert_eq!(series.to_waveform().unwrap().unit, "A");
+    }
+
+    #[test]
+    fn drop_oldest_points() {
+        let mut series = RollSeries::new(150);
+        series.push(screen(ramp, 0, 100), 1.0).unwrap();
+        series.push(screen(ramp, 500, 100), 6.0).unwrap();
+        series.push(screen(ramp, 560, 100), 6.6).unwrap();
+        assert_eq!(series.len(), 150);
+        assert_eq!(series.total(), 260);
+        assert!(series.gaps().is_empty());
+        assert_eq!(series.gap_count(), 1);
+    }
+}
@@ -0,0 +1,74 @@
+/// Width of the waveform area, in cursor pixels
+///
+pub const SCREEN_WIDTH: f64 = 700.0;
+
+/// Height of the waveform area, in cursor pixels
+///
+pub const SCREEN_HEIGHT: f64 = 400.0;
+
+/// Number of horizontal divisions
+///
+pub const HORIZONTAL_DIVISIONS: f64 = 10.0;
+
+/// Number of vertical divisions
+///
+pub const VERTICAL_DIVISIONS: f64 = 8.0;
+
+/// Conversion between the screen coordinates of the cursors and physical units
+///
+/// The horizontal axis depends on the timebase (scale per division, position of
+/// the trigger point relative to the center), the vertical axis on the channel
+/// (scale per division, offset). Pixels grow to the right and to the bottom.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Screen {
+    /// Horizontal scale, seconds per division
+    ///
+    pub time_scale: f64,
+
+    /// Horizontal position of the trigger point, in seconds
+    ///
+    pub time_position: f64,
+
+    /// Vertical scale, volts per division
+    ///
+    pub voltage_scale: f64,
+
+    /// Vertical offset, in volts
+    ///
+    pub voltage_offset: f64,
+}
+
+impl Screen {
+    /// Time of a horizontal pixel, in seconds
+    ///
+    pub fn time_at(&self, x: f64) -> f64 {
+        (x - SCREEN_WIDTH / 2.0) * self.time_scale * HORIZONTAL_DIVISIONS / SCREEN_WIDTH
+            - self.time_position
+    }
+
+    /// Horizontal pixel of a time, clamped to the screen
+    ///
+    pub fn x_of(&self, time: f64) -> f64 {
+        let x = (time + self.time_position) * SCREEN_WIDTH
+            / (self.time_scale * HORIZONTAL_DIVISIONS)
+            + SCREEN_WIDTH / 2.0;
+        x.round().clamp(0.0, SCREEN_WIDTH - 1.0)
+    }
+
+    /// Voltage of a vertical pixel, in volts
+    ///
+    pub fn voltage_at(&self, y: f64) -> f64 {
+        (SCREEN_HEIGHT / 2.0 - y) * self.voltage_scale * VERTICAL_DIVISIONS / SCREEN_HEIGHT
+            - self.voltage_offset
+    }
+
+    /// Vertical pixel of a voltage, clamped to the screen
+    ///
+    pub fn y_of(&self, voltage: f64) -> f64 {
+        let y = SCREEN_HEIGHT / 2.0
+            - (voltage + self.voltage_offset) * SCREEN_HEIGHT
+                / (self.voltage_scale * VERTICAL_DIVISIONS);
+        y.round().clamp(0.0, SCREEN_HEIGHT - 1.0)
+    }
+}
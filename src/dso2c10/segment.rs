@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+
+use crate::waveform::Waveform;
+
+/// Largest number of segments kept by the host ring buffer
+///
+pub const MAX_SEGMENTS: usize = 1000;
+
+/// Triggered frame of a segmented capture
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Number of the frame in the capture, starting at 1
+    ///
+    pub frame: usize,
+
+    /// Time of the frame relative to the first frame of the capture, in seconds
+    ///
+    pub timestamp: f64,
+
+    /// Name of the captured source
+    ///
+    pub source: &'static str,
+
+    ///
+    ///
+    pub waveform: Waveform,
+}
+
+impl Segment {
+    /// JSON representation published on the attributes, the waveform with the
+    /// frame number, the timestamp and the source
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut value = self.waveform.to_json();
+        value["frame"] = serde_json::Value::from(self.frame);
+        value["timestamp"] = serde_json::Value::from(self.timestamp);
+        value["source"] = serde_json::Value::from(self.source);
+        value
+    }
+}
+
+/// Ring buffer of the last segments captured on the host
+///
+/// Frames keep their number when older frames are dropped.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentRing {
+    /// Maximal number of segments kept
+    ///
+    capacity: usize,
+
+    /// Segments kept, the oldest first
+    ///
+    segments: VecDeque<Segment>,
+
+    /// Number of segments pushed since the last clear
+    ///
+    captured: usize,
+}
+
+impl SegmentRing {
+    /// Create an empty ring, the capacity is clamped to 1..=MAX_SEGMENTS
+    ///
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.clamp(1, MAX_SEGMENTS),
+            segments: VecDeque::new(),
+            captured: 0,
+        }
+    }
+
+    ///
+    ///
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the capacity, the oldest segments are dropped when it shrinks
+    ///
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.clamp(1, MAX_SEGMENTS);
+        while self.segments.len() > self.capacity {
+            self.segments.pop_front();
+        }
+    }
+
+    /// Number of segments pushed since the last clear
+    ///
+    pub fn captured(&self) -> usize {
+        self.captured
+    }
+
+    /// Number of segments kept
+    ///
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// True if no segment is kept
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Forget the segments and restart the numbering
+    ///
+    pub fn clear(&mut self) {
+        self.segments.clear();
+        self.captured = 0;
+    }
+
+    /// Store a new segment, dropping the oldest one when full
+    ///
+    /// Returns the number of the new frame.
+    ///
+    pub fn push(&mut self, timestamp: f64, source: &'static str, waveform: Waveform) -> usize {
+        if self.segments.len() == self.capacity {
+            self.segments.pop_front();
+        }
+        self.captured += 1;
+        self.segments.push_back(Segment {
+            frame: self.captured,
+            timestamp,
+            source,
+            waveform,
+        });
+        self.captured
+    }
+
+    /// Segment of a frame number, if still kept
+    ///
+    pub fn get(&self, frame: usize) -> Result<&Segment, String> {
+        let first = self.segments.front().map(|s| s.frame);
+        match first {
+            Some(first) if frame >= first && frame <= self.captured => {
+                Ok(&self.segments[frame - first])
+            }
+            Some(first) => Err(format!(
+                "Frame {} is not kept, frames {} to {} are available",
+                frame, first, self.captured
+            )),
+            None => Err("No frame captured".to_string()),
+        }
+    }
+}
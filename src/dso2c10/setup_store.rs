@@ -0,0 +1,207 @@
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+/// Format version of the stored documents
+///
+pub const SETUP_FORMAT_VERSION: u64 = 1;
+
+/// Relative tolerance of the float comparisons in a diff
+///
+const FLOAT_TOLERANCE: f64 = 1e-6;
+
+/// Named setups stored on the host, one JSON document per setup
+///
+/// A document holds the parameters of a snapshot, the model it was taken on and a
+/// version incremented each time the setup is saved again:
+///
+/// `{ "format": 1, "name": "power_ripple", "version": 3, "model": "DSO2C10",
+///    "parameters": { "channel/1/scale": "10mV", ... } }`
+///
+#[derive(Debug, Clone)]
+pub struct SetupStore {
+    /// Directory of the documents
+    ///
+    directory: PathBuf,
+}
+
+impl SetupStore {
+    /// Store in the given directory, created on the first save
+    ///
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// Default directory of the store, `$HOME/.panduza/hantek/setups`
+    ///
+    pub fn default_directory() -> PathBuf {
+        std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(".panduza")
+            .join("hantek")
+            .join("setups")
+    }
+
+    ///
+    ///
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Names are limited to letters, digits, '_' and '-' to stay valid file names
+    ///
+    fn check_name(name: &str) -> Result<(), String> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!(
+                "Invalid setup name {:?}, use letters, digits, '_' and '-'",
+                name
+            ));
+        }
+        Ok(())
+    }
+
+    ///
+    ///
+    fn path_of(&self, name: &str) -> PathBuf {
+        self.directory.join(format!("{}.json", name))
+    }
+
+    /// Summary of the stored setups, sorted by name
+    ///
+    pub fn list(&self) -> Result<Vec<serde_json::Value>, String> {
+        let entries = match std::fs::read_dir(&self.directory) {
+            Ok(e) => e,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Cannot read {:?}: {}", self.directory, e)),
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                e.file_name()
+                    .to_str()
+                    .and_then(|n| n.strip_suffix(".json"))
+                    .map(|n| n.to_string())
+            })
+            .filter(|n| Self::check_name(n).is_ok())
+            .collect();
+        names.sort();
+        Ok(names
+            .iter()
+            .filter_map(|n| self.load(n).ok())
+            .map(|d| {
+                json!({
+                    "name": d["name"],
+                    "version": d["version"],
+                    "model": d["model"],
+                })
+            })
+            .collect())
+    }
+
+    /// Load the document of a setup
+    ///
+    pub fn load(&self, name: &str) -> Result<serde_json::Value, String> {
+        Self::check_name(name)?;
+        let path = self.path_of(name);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Cannot read setup {:?}: {}", name, e))?;
+        let document: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid setup {:?}: {}", name, e))?;
+        if !document["parameters"].is_object() {
+            return Err(format!("Invalid setup {:?}: no parameters", name));
+        }
+        Ok(document)
+    }
+
+    /// Save parameters under a name, the version follows the stored one
+    ///
+    pub fn save(
+        &self,
+        name: &str,
+        model: &str,
+        parameters: &serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        Self::check_name(name)?;
+        if !parameters.is_object() {
+            return Err("Parameters must be an object".to_string());
+        }
+        let version = self
+            .load(name)
+            .ok()
+            .and_then(|d| d["version"].as_u64())
+            .unwrap_or(0)
+            + 1;
+        let document = json!({
+            "format": SETUP_FORMAT_VERSION,
+            "name": name,
+            "version": version,
+            "model": model,
+            "parameters": parameters,
+        });
+
+        std::fs::create_dir_all(&self.directory)
+            .map_err(|e| format!("Cannot create {:?}: {}", self.directory, e))?;
+        let content = serde_json::to_string_pretty(&document)
+            .map_err(|e| format!("Cannot serialize setup {:?}: {}", name, e))?;
+        std::fs::write(self.path_of(name), content)
+            .map_err(|e| format!("Cannot write setup {:?}: {}", name, e))?;
+        Ok(document)
+    }
+
+    /// Remove a setup
+    ///
+    pub fn delete(&self, name: &str) -> Result<(), String> {
+        Self::check_name(name)?;
+        std::fs::remove_file(self.path_of(name))
+            .map_err(|e| format!("Cannot delete setup {:?}: {}", name, e))
+    }
+}
+
+/// True if two parameter values are the same, floats with a relative tolerance
+///
+fn same_value(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => (x - y).abs() <= FLOAT_TOLERANCE * x.abs().max(y.abs()).max(1.0),
+        _ => match (a.as_str(), b.as_str()) {
+            (Some(x), Some(y)) => x.eq_ignore_ascii_case(y),
+            _ => a == b,
+        },
+    }
+}
+
+/// Differences between the parameters of a setup and the current ones
+///
+/// `{ "changed": { name: { "setup": x, "instrument": y } },
+///    "missing": [names absent from the instrument] }`
+///
+pub fn diff_parameters(
+    setup: &serde_json::Value,
+    current: &serde_json::Value,
+) -> serde_json::Value {
+    let empty = serde_json::Map::new();
+    let setup = setup.as_object().unwrap_or(&empty);
+    let current = current.as_object().unwrap_or(&empty);
+
+    let mut changed = serde_json::Map::new();
+    let mut missing = Vec::new();
+    for (name, expected) in setup.iter() {
+        match current.get(name) {
+            Some(actual) if same_value(expected, actual) => {}
+            Some(actual) => {
+                changed.insert(
+                    name.clone(),
+                    json!({ "setup": expected, "instrument": actual }),
+                );
+            }
+            None => missing.push(name.clone()),
+        }
+    }
+    json!({ "changed": changed, "missing": missing })
+}
@@ -0,0 +1,104 @@
+use crate::scpi::ScpiNumeric;
+use crate::waveform::Waveform;
+
+/// Preamble of a waveform readout, reply of `WAVeform:PREamble?`
+///
+/// `<format>,<type>,<points>,<count>,<xincrement>,<xorigin>,<xreference>,
+/// <yincrement>,<yorigin>,<yreference>`
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaveformPreamble {
+    /// Number of points of the readout
+    ///
+    pub points: usize,
+
+    /// Time between two points, in seconds
+    ///
+    pub x_increment: f64,
+
+    /// Time of the reference point, in seconds
+    ///
+    pub x_origin: f64,
+
+    /// Index of the reference point
+    ///
+    pub x_reference: f64,
+
+    /// Voltage of one ADC step
+    ///
+    pub y_increment: f64,
+
+    /// Vertical offset, in ADC steps
+    ///
+    pub y_origin: f64,
+
+    /// ADC code of the vertical reference
+    ///
+    pub y_reference: f64,
+}
+
+impl WaveformPreamble {
+    /// Parse the preamble reply
+    ///
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let fields = s
+            .trim()
+            .split(',')
+            .map(|f| {
+                ScpiNumeric::from_str_case_insensitive(f)
+                    .and_then(|n| n.to_f64())
+                    .map_err(|e| format!("Invalid preamble {:?}: {}", s, e))
+            })
+            .collect::<Result<Vec<f64>, String>>()?;
+        if fields.len() < 10 {
+            return Err(format!(
+                "Invalid preamble {:?}: 10 fields expected, {} received",
+                s,
+                fields.len()
+            ));
+        }
+        Ok(Self {
+            points: fields[2] as usize,
+            x_increment: fields[4],
+            x_origin: fields[5],
+            x_reference: fields[6],
+            y_increment: fields[7],
+            y_origin: fields[8],
+            y_reference: fields[9],
+        })
+    }
+
+    /// Voltage of an ADC code
+    ///
+    pub fn voltage(&self, code: u8) -> f64 {
+        (code as f64 - self.y_origin - self.y_reference) * self.y_increment
+    }
+
+    /// ADC code of a voltage, clamped to the BYTE range
+    ///
+    pub fn code(&self, voltage: f64) -> u8 {
+        (voltage / self.y_increment + self.y_origin + self.y_reference)
+            .round()
+            .clamp(0.0, 255.0) as u8
+    }
+
+    /// Encode the samples of a waveform as BYTE data, reverse of `decode`
+    ///
+    pub fn encode(&self, waveform: &Waveform) -> Vec<u8> {
+        waveform.samples.iter().map(|v| self.code(*v)).collect()
+    }
+
+    /// Decode the BYTE data of a readout
+    ///
+    /// `first_point` is the index of the first point of `data` in the whole
+    /// record, it shifts the time origin of chunked readouts.
+    ///
+    pub fn decode(&self, data: &[u8], first_point: usize) -> Waveform {
+        Waveform::new(
+            data.iter().map(|c| self.voltage(*c)).collect(),
+            self.x_origin + (first_point as f64 - self.x_reference) * self.x_increment,
+            self.x_increment,
+            "V",
+        )
+    }
+}
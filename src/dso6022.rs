@@ -0,0 +1,54 @@
+mod device;
+mod protocol;
+
+use device::Device;
+use panduza_platform_core::{DriverOperations, Producer};
+
+/// Producer of the Hantek 6022 PC-USB oscilloscopes
+///
+pub struct Package {
+    /// Model name (e.g. DSO6022BE)
+    ///
+    model: String,
+
+    /// USB product id, identical before and after the firmware load
+    ///
+    product_id: u16,
+}
+
+impl Package {
+    pub fn new<A: Into<String>>(model: A, product_id: u16) -> Self {
+        Self {
+            model: model.into(),
+            product_id,
+        }
+    }
+
+    pub fn boxed(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Producer for Package {
+    fn manufacturer(&self) -> String {
+        "hantek".to_string()
+    }
+
+    fn model(&self) -> String {
+        self.model.clone()
+    }
+
+    fn description(&self) -> String {
+        "2 channels PC-USB oscilloscope".to_string()
+    }
+
+    fn props(&self) -> panduza_platform_core::Props {
+        let props = panduza_platform_core::Props::default();
+
+        props
+    }
+
+    fn produce(&self) -> Result<Box<dyn DriverOperations>, panduza_platform_core::Error> {
+        return Ok(Box::new(Device::new(self.product_id)));
+    }
+}
@@ -0,0 +1,165 @@
+mod channel;
+mod interface;
+mod timebase;
+
+use interface::{DSO6022Interface, WaveformIndex};
+
+use crate::bus;
+use crate::dso6022::protocol;
+use crate::export;
+use async_trait::async_trait;
+use panduza_platform_core::{
+    log_debug, log_info, Container, DriverOperations, Error, Instance, Logger,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+/// Time given to the scope to re-enumerate after the firmware load
+///
+const RENUMERATION_DELAY: Duration = Duration::from_secs(2);
+
+///
+/// Device to control the Hantek 6022 PC-USB oscilloscopes
+///
+pub struct Device {
+    /// USB product id of the model
+    ///
+    product_id: u16,
+}
+
+impl Device {
+    ///
+    ///
+    pub fn new(product_id: u16) -> Self {
+        Self { product_id }
+    }
+
+    /// Find the scope with the given vendor id
+    ///
+    fn find(&self, vendor_id: u16) -> Result<Option<nusb::DeviceInfo>, Error> {
+        Ok(nusb::list_devices()
+            .map_err(|e| Error::Generic(format!("Unable to list USB devices: {:?}", e)))?
+            .find(|d| d.vendor_id() == vendor_id && d.product_id() == self.product_id))
+    }
+
+    /// Load the firmware into a scope that runs the FX2 loader
+    ///
+    async fn load_firmware(
+        &self,
+        logger: &Logger,
+        info: nusb::DeviceInfo,
+        settings: &serde_json::Value,
+    ) -> Result<(), Error> {
+        //
+        // Read the firmware
+        let path = settings
+            .get("firmware_path")
+            .and_then(|p| p.as_str())
+            .ok_or(Error::BadSettings(
+                "'firmware_path' is required to load the scope firmware".to_string(),
+            ))?;
+        let hex = std::fs::read_to_string(path)
+            .map_err(|e| Error::BadSettings(format!("Unable to read {:?}: {:?}", path, e)))?;
+        let requests = protocol::firmware_requests(&hex).map_err(Error::BadSettings)?;
+        log_info!(
+            logger,
+            "Load firmware {:?} ({} requests)",
+            path,
+            requests.len()
+        );
+
+        //
+        // Write the RAM through the loader
+        let usb = info
+            .open()
+            .and_then(|d| d.claim_interface(0))
+            .map_err(|e| Error::Generic(format!("Unable to open the loader: {:?}", e)))?;
+        let loader = DSO6022Interface::loader(usb, logger.clone());
+        for request in requests.iter() {
+            loader.control(request).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DriverOperations for Device {
+    ///
+    /// Mount the device instance
+    ///
+    async fn mount(&mut self, mut instance: Instance) -> Result<(), Error> {
+        //
+        //
+        let logger = instance.logger.clone();
+
+        //
+        // Settings, only required to load the firmware
+        let settings = instance.settings().await.unwrap_or_default();
+
+        //
+        // Load the firmware if the scope just powered up
+        if self.find(protocol::VENDOR_ID)?.is_none() {
+            let loader = self
+                .find(protocol::VENDOR_ID_LOADER)?
+                .ok_or(Error::Generic(format!(
+                    "No scope found with product id {:04x}",
+                    self.product_id
+                )))?;
+            self.load_firmware(&logger, loader, &settings).await?;
+            sleep(RENUMERATION_DELAY).await;
+        }
+
+        //
+        // Open the scope
+        let info = self.find(protocol::VENDOR_ID)?.ok_or(Error::Generic(
+            "Scope did not re-enumerate after the firmware load".to_string(),
+        ))?;
+        log_debug!(logger, "Try to open USB interface on {:?}", &info);
+        let usb = info
+            .open()
+            .and_then(|d| d.claim_interface(0))
+            .map_err(|e| Error::Generic(format!("Unable to open the scope: {:?}", e)))?;
+
+        let interface: Arc<Mutex<DSO6022Interface>> = Arc::new(Mutex::new(
+            DSO6022Interface::new(usb, logger.clone()).await?,
+        ));
+
+        let class_channels = instance.create_class("channel").finish().await;
+        for i in 1..=2 {
+            channel::mount(class_channels.clone(), i, interface.clone()).await?;
+        }
+
+        timebase::mount(instance.clone(), interface.clone()).await?;
+
+        let sources = vec![
+            ("CH1", WaveformIndex::Channel1 as usize),
+            ("CH2", WaveformIndex::Channel2 as usize),
+        ];
+        bus::mount(instance.clone(), interface.clone(), sources.clone()).await?;
+
+        //
+        // Exports are written in 'export_directory' if given
+        let export_directory = match settings.get("export_directory").and_then(|d| d.as_str()) {
+            Some(directory) => PathBuf::from(directory),
+            None => export::default_directory(),
+        };
+        export::mount(
+            instance.clone(),
+            interface.clone(),
+            sources,
+            export_directory,
+        )
+        .await?;
+
+        Ok(())
+    }
+    ///
+    /// Easiest way to implement the reboot event
+    ///
+    async fn wait_reboot_event(&mut self, mut _device: Instance) {
+        sleep(Duration::from_secs(5)).await;
+    }
+}
@@ -0,0 +1,73 @@
+use tokio::sync::Mutex;
+
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::{log_debug_mount_end, log_debug_mount_start, Container, Error};
+use std::sync::Arc;
+
+use crate::dso6022::device::interface::StringIndex;
+use crate::dso6022::device::interface::WaveformIndex;
+use crate::dso6022::device::interface::CHANNEL_SCALE_CHOICES;
+
+use super::interface::DSO6022Interface;
+use crate::spectrum as std_att_spectrum;
+use crate::waveform as std_att_waveform;
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    channel_id: usize,
+    interface: Arc<Mutex<DSO6022Interface>>,
+) -> Result<(), Error> {
+    let class_chan = parent
+        .create_class(format!("{}", channel_id))
+        .finish()
+        .await;
+
+    let logger = class_chan.logger().clone();
+    log_debug_mount_start!(logger);
+
+    std_att_enum::mount(
+        class_chan.clone(),
+        interface.clone(),
+        match channel_id {
+            1 => StringIndex::Channel1Scale,
+            2 => StringIndex::Channel2Scale,
+            _ => StringIndex::Channel1Scale,
+        } as usize,
+        "scale",
+        "Vertical scale of the channel, volts per division (x1 probe)",
+        CHANNEL_SCALE_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_waveform::mount(
+        class_chan.clone(),
+        interface.clone(),
+        match channel_id {
+            1 => WaveformIndex::Channel1,
+            2 => WaveformIndex::Channel2,
+            _ => WaveformIndex::Channel1,
+        } as usize,
+        "waveform",
+        "Write any value to capture both channels, the waveform of this channel is published",
+    )
+    .await?;
+
+    std_att_spectrum::mount(
+        class_chan.clone(),
+        interface.clone(),
+        match channel_id {
+            1 => WaveformIndex::Channel1,
+            2 => WaveformIndex::Channel2,
+            _ => WaveformIndex::Channel1,
+        } as usize,
+        "spectrum",
+    )
+    .await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
@@ -0,0 +1,371 @@
+use async_trait::async_trait;
+use nusb::transfer::{ControlOut, ControlType, Recipient, RequestBuffer};
+
+use panduza_platform_core::std::attribute::r#enum::StringAccessorModel;
+use panduza_platform_core::{log_info, log_trace, Error, Logger};
+use strum_macros::FromRepr;
+
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+
+use crate::dso6022::protocol;
+use crate::dso6022::protocol::ControlRequest;
+use crate::si::FloatAccessorModel;
+use crate::waveform::Waveform;
+use crate::waveform::WaveformAccessorModel;
+
+/// Number of samples per channel of a capture
+///
+pub const CAPTURE_POINTS: usize = 10240;
+
+/// Longest wait for the samples of a capture, the slowest rate fills a capture
+/// in about 100ms
+///
+const BULK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Number of horizontal divisions, used to convert the timebase scale
+///
+const HORIZONTAL_DIVISIONS: f64 = 10.0;
+
+/// Choices of the channel scale, in the order of `protocol::GAINS`
+///
+/// The +/- 5V / gain input range is displayed on 10 divisions.
+///
+pub const CHANNEL_SCALE_CHOICES: [&str; 4] = ["1V", "500mV", "200mV", "100mV"];
+
+/// Interface to communicate with the 6022 device
+///
+pub struct DSO6022Interface {
+    /// Lower level interface (USB claimed interface)
+    ///
+    usb: nusb::Interface,
+
+    /// Logger for the driver
+    ///
+    logger: Logger,
+
+    /// Gain of each channel, the device cannot be read back
+    ///
+    gains: [u8; 2],
+
+    /// Sample rate, the device cannot be read back
+    ///
+    sample_rate: f64,
+}
+
+///
+///
+impl DSO6022Interface {
+    ///
+    ///
+    pub async fn new(usb: nusb::Interface, logger: Logger) -> Result<Self, Error> {
+        //
+        // Log
+        log_info!(logger, "Create DSO6022Interface with USB sub_interface",);
+
+        //
+        // Build the object and push the initial settings
+        let interface = DSO6022Interface {
+            usb,
+            logger,
+            gains: [protocol::GAINS[0]; 2],
+            sample_rate: protocol::SAMPLE_RATES[0].0,
+        };
+        interface.apply_settings().await?;
+        Ok(interface)
+    }
+
+    /// Interface on the FX2 loader, only able to send control requests
+    ///
+    pub fn loader(usb: nusb::Interface, logger: Logger) -> Self {
+        DSO6022Interface {
+            usb,
+            logger,
+            gains: [protocol::GAINS[0]; 2],
+            sample_rate: protocol::SAMPLE_RATES[0].0,
+        }
+    }
+
+    /// Send a vendor control request
+    ///
+    pub async fn control(&self, request: &ControlRequest) -> Result<(), Error> {
+        log_trace!(
+            self.logger,
+            "CTRL => {:02x?} {:02x?}",
+            request.setup_packet(),
+            request.data
+        );
+        self.usb
+            .control_out(ControlOut {
+                control_type: ControlType::Vendor,
+                recipient: Recipient::Device,
+                request: request.request,
+                value: request.value,
+                index: 0,
+                data: &request.data,
+            })
+            .await
+            .into_result()
+            .map_err(|e| Error::Generic(format!("Control request failed: {:?}", e)))?;
+        Ok(())
+    }
+
+    ///
+    ///
+    fn protocol_error(e: String) -> Error {
+        Error::InvalidArgument(e)
+    }
+
+    /// Push gains, sample rate and channel count
+    ///
+    async fn apply_settings(&self) -> Result<(), Error> {
+        for (i, gain) in self.gains.iter().enumerate() {
+            let request = protocol::gain_request(i + 1, *gain).map_err(Self::protocol_error)?;
+            self.control(&request).await?;
+        }
+        let request =
+            protocol::sample_rate_request(self.sample_rate).map_err(Self::protocol_error)?;
+        self.control(&request).await?;
+        self.control(&protocol::channels_request(2)).await
+    }
+
+    ///
+    ///
+    pub async fn set_channel_gain(&mut self, channel_id: usize, gain: u8) -> Result<(), Error> {
+        let request = protocol::gain_request(channel_id, gain).map_err(Self::protocol_error)?;
+        self.control(&request).await?;
+        self.gains[channel_id - 1] = gain;
+        Ok(())
+    }
+
+    ///
+    ///
+    pub async fn set_timebase_scale(&mut self, scale: f64) -> Result<(), Error> {
+        let rate = protocol::sample_rate_for_duration(scale * HORIZONTAL_DIVISIONS, CAPTURE_POINTS);
+        let request = protocol::sample_rate_request(rate).map_err(Self::protocol_error)?;
+        self.control(&request).await?;
+        self.sample_rate = rate;
+        Ok(())
+    }
+
+    ///
+    ///
+    pub fn get_timebase_scale(&self) -> f64 {
+        CAPTURE_POINTS as f64 / self.sample_rate / HORIZONTAL_DIVISIONS
+    }
+
+    /// Capture both channels
+    ///
+    pub async fn capture(&self) -> Result<[Waveform; 2], Error> {
+        //
+        // Measure perfs
+        let start = Instant::now();
+
+        //
+        // Acquire
+        self.control(&protocol::start_request()).await?;
+        //
+        // A stalled scope must not hold the interface, the transfer is cancelled
+        // when the timeout drops it
+        let transfer = self.usb.bulk_in(
+            protocol::ENDPOINT_SAMPLES,
+            RequestBuffer::new(CAPTURE_POINTS * 2),
+        );
+        let raw = match timeout(BULK_TIMEOUT, transfer).await {
+            Ok(completion) => completion
+                .into_result()
+                .map_err(|e| Error::Generic(format!("Bulk read failed: {:?}", e))),
+            Err(_) => Err(Error::Generic(format!(
+                "No samples received within {:?}",
+                BULK_TIMEOUT
+            ))),
+        };
+        self.control(&protocol::stop_request()).await?;
+        let raw = raw?;
+
+        //
+        // Log
+        log_trace!(
+            self.logger,
+            "BULK <= {} bytes - {:.2?}",
+            raw.len(),
+            start.elapsed()
+        );
+
+        //
+        // Convert
+        let (ch1, ch2) = protocol::deinterleave(&raw);
+        let x_increment = 1.0 / self.sample_rate;
+        let to_waveform = |codes: Vec<u8>, gain: u8| {
+            Waveform::new(
+                codes
+                    .into_iter()
+                    .map(|c| protocol::code_to_volts(c, gain))
+                    .collect(),
+                0.0,
+                x_increment,
+                "V",
+            )
+        };
+        Ok([
+            to_waveform(ch1, self.gains[0]),
+            to_waveform(ch2, self.gains[1]),
+        ])
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum StringIndex {
+    //
+    Channel1Scale,
+    Channel2Scale,
+}
+
+#[async_trait]
+///
+///
+impl StringAccessorModel for DSO6022Interface {
+    ///
+    ///
+    async fn get_string_at(&mut self, index: usize) -> Result<String, Error> {
+        //
+        // Get the index
+        let idx = StringIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        let channel_id = match idx {
+            StringIndex::Channel1Scale => 1,
+            StringIndex::Channel2Scale => 2,
+        };
+
+        //
+        // Label of the current gain
+        let gain = self.gains[channel_id - 1];
+        let position = protocol::GAINS.iter().position(|g| *g == gain).unwrap_or(0);
+        Ok(CHANNEL_SCALE_CHOICES[position].to_string())
+    }
+
+    ///
+    ///
+    async fn set_string_at(&mut self, index: usize, value: &String) -> Result<(), Error> {
+        //
+        // Get the index
+        let idx = StringIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        let channel_id = match idx {
+            StringIndex::Channel1Scale => 1,
+            StringIndex::Channel2Scale => 2,
+        };
+
+        //
+        // Gain of the label
+        let position = CHANNEL_SCALE_CHOICES
+            .iter()
+            .position(|c| c == value)
+            .ok_or(Error::InvalidArgument(format!("Invalid scale {:?}", value)))?;
+        self.set_channel_gain(channel_id, protocol::GAINS[position])
+            .await
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum FloatIndex {
+    //
+    TimebaseScale,
+}
+
+#[async_trait]
+///
+///
+impl FloatAccessorModel for DSO6022Interface {
+    ///
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error> {
+        //
+        // Get the index
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            FloatIndex::TimebaseScale => Ok(self.get_timebase_scale()),
+        }
+    }
+
+    ///
+    ///
+    async fn set_float_at(&mut self, index: usize, value: f64) -> Result<(), Error> {
+        //
+        // Get the index
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            FloatIndex::TimebaseScale => self.set_timebase_scale(value).await,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum WaveformIndex {
+    //
+    Channel1,
+    Channel2,
+}
+
+#[async_trait]
+///
+///
+impl WaveformAccessorModel for DSO6022Interface {
+    ///
+    ///
+    async fn get_waveform_at(&mut self, index: usize) -> Result<Waveform, Error> {
+        //
+        // Get the index
+        let idx = WaveformIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Both channels are always captured together
+        let [ch1, ch2] = self.capture().await?;
+        match idx {
+            WaveformIndex::Channel1 => Ok(ch1),
+            WaveformIndex::Channel2 => Ok(ch2),
+        }
+    }
+
+    /// Both channels come from a single capture
+    ///
+    async fn get_waveforms_at(&mut self, indexes: &[usize]) -> Result<Vec<Waveform>, Error> {
+        let indexes = indexes
+            .iter()
+            .map(|i| {
+                WaveformIndex::from_repr(*i)
+                    .ok_or(Error::InvalidArgument("Invalid Index".to_string()))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let [ch1, ch2] = self.capture().await?;
+        Ok(indexes
+            .iter()
+            .map(|idx| match idx {
+                WaveformIndex::Channel1 => ch1.clone(),
+                WaveformIndex::Channel2 => ch2.clone(),
+            })
+            .collect())
+    }
+}
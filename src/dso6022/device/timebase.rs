@@ -0,0 +1,39 @@
+use tokio::sync::Mutex;
+
+use panduza_platform_core::{log_debug_mount_end, log_debug_mount_start, Container, Error};
+use std::sync::Arc;
+
+use crate::dso6022::device::interface::FloatIndex;
+use crate::si as std_att_si;
+use crate::si::SiFormat;
+
+use super::interface::DSO6022Interface;
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<DSO6022Interface>>,
+) -> Result<(), Error> {
+    let class_timebase = parent.create_class("timebase").finish().await;
+
+    let logger = class_timebase.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    // The scale selects the sample rate, the closest supported one is applied
+    std_att_si::mount(
+        class_timebase.clone(),
+        interface.clone(),
+        FloatIndex::TimebaseScale as usize,
+        "scale",
+        "Horizontal scale, time per division",
+        SiFormat::new("s", 0.00002, 0.01, 9),
+    )
+    .await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
@@ -0,0 +1,390 @@
+// Vendor protocol of the Hantek 6022 PC-USB oscilloscopes
+//
+// The scope is a Cypress FX2 without firmware at power up. The firmware is loaded
+// in RAM with the FX2 `0xA0` request, then the scope re-enumerates with the
+// configured vendor id. Settings are vendor control requests with a single byte of
+// data, samples are read on a bulk endpoint, interleaved CH1/CH2.
+//
+// The request codes, the sample rate codes and the sample endpoint are the ones of
+// the libsigrok hantek-6xxx driver (protocol.h) and of the open 6022 firmware it
+// loads. The firmware load is the FX2 `0xA0` RAM write of the Cypress EZ-USB
+// technical reference manual.
+//
+
+/// Vendor id before the firmware is loaded
+///
+pub const VENDOR_ID_LOADER: u16 = 0x04b4;
+
+/// Vendor id once the firmware is running
+///
+pub const VENDOR_ID: u16 = 0x04b5;
+
+/// bmRequestType of the vendor requests, host to device
+///
+pub const REQUEST_TYPE_VENDOR_OUT: u8 = 0x40;
+
+/// FX2 request to write the RAM
+///
+pub const REQUEST_FIRMWARE: u8 = 0xA0;
+
+/// Gain of the channel 1
+///
+pub const REQUEST_CH1_GAIN: u8 = 0xE0;
+
+/// Gain of the channel 2
+///
+pub const REQUEST_CH2_GAIN: u8 = 0xE1;
+
+/// Sample rate
+///
+pub const REQUEST_SAMPLE_RATE: u8 = 0xE2;
+
+/// Start (1) or stop (0) the acquisition
+///
+pub const REQUEST_TRIGGER: u8 = 0xE3;
+
+/// Number of channels to acquire
+///
+pub const REQUEST_CHANNELS: u8 = 0xE4;
+
+/// Bulk endpoint of the samples
+///
+pub const ENDPOINT_SAMPLES: u8 = 0x86;
+
+/// Address of the FX2 CPU control register, holds the CPU in reset while loading
+///
+pub const CPUCS_ADDRESS: u16 = 0xE600;
+
+/// Supported sample rates (samples/s) and their protocol code
+///
+pub const SAMPLE_RATES: [(f64, u8); 10] = [
+    (100e3, 10),
+    (200e3, 20),
+    (500e3, 50),
+    (1e6, 1),
+    (4e6, 4),
+    (8e6, 8),
+    (16e6, 16),
+    (24e6, 24),
+    (30e6, 30),
+    (48e6, 48),
+];
+
+/// Supported gains, the input range is +/- 5V / gain
+///
+pub const GAINS: [u8; 4] = [1, 2, 5, 10];
+
+/// ADC code of 0V
+///
+pub const ADC_ZERO: f64 = 128.0;
+
+/// Vendor control request, out direction
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlRequest {
+    /// bRequest
+    ///
+    pub request: u8,
+
+    /// wValue
+    ///
+    pub value: u16,
+
+    /// Data stage
+    ///
+    pub data: Vec<u8>,
+}
+
+impl ControlRequest {
+    ///
+    ///
+    pub fn new(request: u8, value: u16, data: Vec<u8>) -> Self {
+        Self {
+            request,
+            value,
+            data,
+        }
+    }
+
+    /// SETUP stage of the request as sent on the wire
+    ///
+    /// bmRequestType, bRequest, wValue, wIndex and wLength, little endian.
+    ///
+    pub fn setup_packet(&self) -> [u8; 8] {
+        let value = self.value.to_le_bytes();
+        let length = (self.data.len() as u16).to_le_bytes();
+        [
+            REQUEST_TYPE_VENDOR_OUT,
+            self.request,
+            value[0],
+            value[1],
+            0x00,
+            0x00,
+            length[0],
+            length[1],
+        ]
+    }
+}
+
+/// Request to set the gain of a channel
+///
+pub fn gain_request(channel_id: usize, gain: u8) -> Result<ControlRequest, String> {
+    if !GAINS.contains(&gain) {
+        return Err(format!("Unsupported gain {}", gain));
+    }
+    let request = match channel_id {
+        1 => REQUEST_CH1_GAIN,
+        2 => REQUEST_CH2_GAIN,
+        _ => return Err(format!("Invalid channel {}", channel_id)),
+    };
+    Ok(ControlRequest::new(request, 0, vec![gain]))
+}
+
+/// Request to set the sample rate
+///
+pub fn sample_rate_request(rate: f64) -> Result<ControlRequest, String> {
+    let code = SAMPLE_RATES
+        .iter()
+        .find(|(r, _)| *r == rate)
+        .map(|(_, c)| *c)
+        .ok_or(format!("Unsupported sample rate {}", rate))?;
+    Ok(ControlRequest::new(REQUEST_SAMPLE_RATE, 0, vec![code]))
+}
+
+/// Request to select the number of acquired channels
+///
+pub fn channels_request(count: u8) -> ControlRequest {
+    ControlRequest::new(REQUEST_CHANNELS, 0, vec![count])
+}
+
+/// Request to start the acquisition
+///
+pub fn start_request() -> ControlRequest {
+    ControlRequest::new(REQUEST_TRIGGER, 0, vec![0x01])
+}
+
+/// Request to stop the acquisition
+///
+pub fn stop_request() -> ControlRequest {
+    ControlRequest::new(REQUEST_TRIGGER, 0, vec![0x00])
+}
+
+/// Requests that load an Intel HEX firmware into the FX2 RAM
+///
+/// The CPU is held in reset during the load and released at the end.
+///
+pub fn firmware_requests(hex: &str) -> Result<Vec<ControlRequest>, String> {
+    let mut requests = vec![ControlRequest::new(
+        REQUEST_FIRMWARE,
+        CPUCS_ADDRESS,
+        vec![0x01],
+    )];
+
+    for (n, line) in hex.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record = parse_hex_record(line).map_err(|e| format!("Line {}: {}", n + 1, e))?;
+        match record.kind {
+            0x00 => requests.push(ControlRequest::new(
+                REQUEST_FIRMWARE,
+                record.address,
+                record.data,
+            )),
+            0x01 => break,
+            kind => return Err(format!("Line {}: unsupported record type {}", n + 1, kind)),
+        }
+    }
+
+    requests.push(ControlRequest::new(
+        REQUEST_FIRMWARE,
+        CPUCS_ADDRESS,
+        vec![0x00],
+    ));
+    Ok(requests)
+}
+
+/// Record of an Intel HEX file
+///
+struct HexRecord {
+    address: u16,
+    kind: u8,
+    data: Vec<u8>,
+}
+
+/// Parse a `:LLAAAATT<data>CC` record and check its checksum
+///
+fn parse_hex_record(line: &str) -> Result<HexRecord, String> {
+    let digits = line
+        .strip_prefix(':')
+        .ok_or("record must start with ':'".to_string())?;
+    if digits.len() % 2 != 0 {
+        return Err("odd number of digits".to_string());
+    }
+    let bytes = (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|e| format!("invalid digit ({})", e))?;
+    if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+        return Err("length does not match the record".to_string());
+    }
+    let sum = bytes.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+    if sum != 0 {
+        return Err("bad checksum".to_string());
+    }
+    Ok(HexRecord {
+        address: u16::from_be_bytes([bytes[1], bytes[2]]),
+        kind: bytes[3],
+        data: bytes[4..bytes.len() - 1].to_vec(),
+    })
+}
+
+/// Split interleaved CH1/CH2 samples
+///
+pub fn deinterleave(raw: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    raw.chunks_exact(2).map(|pair| (pair[0], pair[1])).unzip()
+}
+
+/// Voltage of an ADC code for a gain
+///
+pub fn code_to_volts(code: u8, gain: u8) -> f64 {
+    (code as f64 - ADC_ZERO) / ADC_ZERO * 5.0 / gain as f64
+}
+
+/// Highest sample rate that covers `duration` seconds with `points` samples
+///
+/// The lowest rate is returned if none covers the duration.
+///
+pub fn sample_rate_for_duration(duration: f64, points: usize) -> f64 {
+    SAMPLE_RATES
+        .iter()
+        .map(|(r, _)| *r)
+        .filter(|r| points as f64 / r >= duration)
+        .fold(SAMPLE_RATES[0].0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_settings() {
+        assert_eq!(
+            gain_request(1, 5).unwrap(),
+            ControlRequest::new(0xE0, 0, vec![0x05])
+        );
+        assert_eq!(
+            gain_request(2, 10).unwrap(),
+            ControlRequest::new(0xE1, 0, vec![0x0A])
+        );
+        assert!(gain_request(1, 3).is_err());
+        assert!(gain_request(3, 1).is_err());
+        assert_eq!(
+            sample_rate_request(100e3).unwrap(),
+            ControlRequest::new(0xE2, 0, vec![0x0A])
+        );
+        assert_eq!(
+            sample_rate_request(48e6).unwrap(),
+            ControlRequest::new(0xE2, 0, vec![0x30])
+        );
+        assert!(sample_rate_request(2e6).is_err());
+        assert_eq!(
+            channels_request(2),
+            ControlRequest::new(0xE4, 0, vec![0x02])
+        );
+        assert_eq!(start_request(), ControlRequest::new(0xE3, 0, vec![0x01]));
+        assert_eq!(stop_request(), ControlRequest::new(0xE3, 0, vec![0x00]));
+    }
+
+    #[test]
+    fn encode_firmware() {
+        let hex = ":030000000200609B\n:02006000E4FFBB\n:00000001FF\n";
+        assert_eq!(
+            firmware_requests(hex).unwrap(),
+            vec![
+                ControlRequest::new(0xA0, 0xE600, vec![0x01]),
+                ControlRequest::new(0xA0, 0x0000, vec![0x02, 0x00, 0x60]),
+                ControlRequest::new(0xA0, 0x0060, vec![0xE4, 0xFF]),
+                ControlRequest::new(0xA0, 0xE600, vec![0x00]),
+            ]
+        );
+        assert!(firmware_requests(":030000000200609C\n").is_err());
+    }
+
+    /// Parse a hex dump (e.g. "40 e2 00 00")
+    ///
+    fn bytes(dump: &str) -> Vec<u8> {
+        dump.split_whitespace()
+            .map(|b| u8::from_str_radix(b, 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn serialize_setup_packets() {
+        //
+        // SETUP and DATA stages of the settings pushed before a capture, as libsigrok
+        // issues them: vendor request to the device, wValue and wIndex 0, one byte
+        let wire = [
+            (gain_request(1, 1).unwrap(), "40 e0 00 00 00 00 01 00", "01"),
+            (gain_request(2, 5).unwrap(), "40 e1 00 00 00 00 01 00", "05"),
+            (
+                sample_rate_request(1e6).unwrap(),
+                "40 e2 00 00 00 00 01 00",
+                "01",
+            ),
+            (channels_request(2), "40 e4 00 00 00 00 01 00", "02"),
+            (start_request(), "40 e3 00 00 00 00 01 00", "01"),
+            (stop_request(), "40 e3 00 00 00 00 01 00", "00"),
+        ];
+        for (request, setup, data) in wire {
+            assert_eq!(request.setup_packet().to_vec(), bytes(setup));
+            assert_eq!(request.data, bytes(data));
+        }
+
+        //
+        // Firmware load, the address is in wValue
+        let hex = ":03010000010203F6\n:00000001FF\n";
+        let requests = firmware_requests(hex).unwrap();
+        assert_eq!(
+            requests[1].setup_packet().to_vec(),
+            bytes("40 a0 00 01 00 00 03 00")
+        );
+        assert_eq!(requests[1].data, bytes("01 02 03"));
+        assert_eq!(
+            requests[0].setup_packet().to_vec(),
+            bytes("40 a0 00 e6 00 00 01 00")
+        );
+    }
+
+    #[test]
+    fn decode_bulk_payload() {
+        //
+        // Synthetic interleaved CH1/CH2 payload of the samples endpoint, CH1 on a
+        // square wave and CH2 grounded
+        let payload = bytes("c0 80 c0 81 c0 80 40 7f 40 80 40 80");
+        let (ch1, ch2) = deinterleave(&payload);
+        assert_eq!(ch1, bytes("c0 c0 c0 40 40 40"));
+        assert_eq!(ch2, bytes("80 81 80 7f 80 80"));
+        let volts: Vec<f64> = ch1.iter().map(|c| code_to_volts(*c, 2)).collect();
+        assert_eq!(volts, vec![1.25, 1.25, 1.25, -1.25, -1.25, -1.25]);
+    }
+
+    #[test]
+    fn decode_samples() {
+        let (ch1, ch2) = deinterleave(&[0x80, 0x00, 0xFF, 0x40, 0x01]);
+        assert_eq!(ch1, vec![0x80, 0xFF]);
+        assert_eq!(ch2, vec![0x00, 0x40]);
+        assert_eq!(code_to_volts(0x80, 1), 0.0);
+        assert_eq!(code_to_volts(0x00, 1), -5.0);
+        assert_eq!(code_to_volts(0x40, 10), -0.25);
+    }
+
+    #[test]
+    fn select_sample_rate() {
+        assert_eq!(sample_rate_for_duration(1e-3, 10240), 8e6);
+        assert_eq!(sample_rate_for_duration(10.0, 10240), 100e3);
+        assert_eq!(sample_rate_for_duration(1e-6, 10240), 48e6);
+    }
+}
@@ -0,0 +1,469 @@
+mod csv;
+mod npy;
+mod sigrok;
+mod vcd;
+mod wav;
+mod zip;
+
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer,
+};
+use serde_json::json;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::bus::{LogicSignal, Threshold};
+use crate::waveform::{Waveform, WaveformAccessorModel};
+
+/// Names of the export formats, as published on the attributes
+///
+pub const EXPORT_FORMAT_CHOICES: [&str; 6] = ["CSV", "NPY", "WAV16", "WAV_FLOAT", "SR", "VCD"];
+
+/// Logic threshold of a flat channel, which has no swing for the automatic one
+///
+const FLAT_CHANNEL_LEVEL: f64 = 1.5;
+
+/// File formats of the export
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    /// Text, one row per sample with the time first, metadata in `#` comments
+    ///
+    Csv,
+
+    /// NumPy float64 array (time, channels...), metadata in a JSON file next to it
+    ///
+    Npy,
+
+    /// 16 bits PCM WAV, normalized on the largest sample
+    ///
+    Wav16,
+
+    /// 32 bits float WAV, samples in the unit of the channels
+    ///
+    WavFloat,
+
+    /// sigrok session (PulseView), thresholded logic and analog traces
+    ///
+    Sigrok,
+
+    /// Value Change Dump, thresholded logic wires and real variables
+    ///
+    Vcd,
+}
+
+impl ExportFormat {
+    ///
+    ///
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_uppercase().as_str() {
+            "CSV" => Ok(Self::Csv),
+            "NPY" => Ok(Self::Npy),
+            "WAV16" => Ok(Self::Wav16),
+            "WAV_FLOAT" => Ok(Self::WavFloat),
+            "SR" => Ok(Self::Sigrok),
+            "VCD" => Ok(Self::Vcd),
+            _ => Err(format!(
+                "Invalid export format {:?}, expected one of {:?}",
+                name, EXPORT_FORMAT_CHOICES
+            )),
+        }
+    }
+
+    ///
+    ///
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Csv => EXPORT_FORMAT_CHOICES[0],
+            Self::Npy => EXPORT_FORMAT_CHOICES[1],
+            Self::Wav16 => EXPORT_FORMAT_CHOICES[2],
+            Self::WavFloat => EXPORT_FORMAT_CHOICES[3],
+            Self::Sigrok => EXPORT_FORMAT_CHOICES[4],
+            Self::Vcd => EXPORT_FORMAT_CHOICES[5],
+        }
+    }
+
+    /// Format matching the extension of a path, WAV files default to 16 bits
+    ///
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "npy" => Some(Self::Npy),
+            "wav" => Some(Self::Wav16),
+            "sr" => Some(Self::Sigrok),
+            "vcd" => Some(Self::Vcd),
+            _ => None,
+        }
+    }
+}
+
+/// Named waveform of an export
+///
+#[derive(Debug, Clone, Copy)]
+pub struct ExportChannel<'a> {
+    /// Name of the channel (e.g. CHANnel1)
+    ///
+    pub name: &'a str,
+
+    ///
+    ///
+    pub waveform: &'a Waveform,
+
+    /// Threshold of the logic view, in the middle of the swing if None
+    ///
+    pub threshold: Option<Threshold>,
+}
+
+impl<'a> ExportChannel<'a> {
+    ///
+    ///
+    pub fn new(name: &'a str, waveform: &'a Waveform) -> Self {
+        Self {
+            name,
+            waveform,
+            threshold: None,
+        }
+    }
+
+    /// Set the threshold of the logic view
+    ///
+    pub fn with_threshold(mut self, threshold: Threshold) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Threshold used for the logic view
+    ///
+    pub fn threshold(&self) -> Threshold {
+        self.threshold
+            .or_else(|| Threshold::auto(&self.waveform.samples))
+            .unwrap_or(Threshold::new(FLAT_CHANNEL_LEVEL, 0.0))
+    }
+
+    /// Logic view of the channel
+    ///
+    pub fn logic(&self) -> LogicSignal {
+        LogicSignal::from_waveform(self.waveform, self.threshold())
+    }
+}
+
+/// Metadata of the export as JSON
+///
+pub fn metadata(channels: &[ExportChannel]) -> serde_json::Value {
+    let reference = channels.first().map(|c| c.waveform);
+    json!({
+        "channels": channels.iter().map(|c| json!({
+            "name": c.name,
+            "unit": c.waveform.unit,
+        })).collect::<Vec<_>>(),
+        "x_origin": reference.map(|w| w.x_origin),
+        "x_increment": reference.map(|w| w.x_increment),
+        "points": reference.map(|w| w.len()),
+    })
+}
+
+/// Metadata of the export on a single line, for the formats with a comment field
+///
+fn metadata_comment(channels: &[ExportChannel]) -> String {
+    let reference = channels[0].waveform;
+    format!(
+        "channels: {}; x_origin: {}; x_increment: {}",
+        channels
+            .iter()
+            .map(|c| format!("{} ({})", c.name, c.waveform.unit))
+            .collect::<Vec<_>>()
+            .join(", "),
+        reference.x_origin,
+        reference.x_increment
+    )
+}
+
+/// Check that the channels share the same time axis
+///
+fn check_channels(channels: &[ExportChannel]) -> Result<(), String> {
+    let reference = channels
+        .first()
+        .ok_or("No channel to export".to_string())?
+        .waveform;
+    for c in channels.iter().skip(1) {
+        if c.waveform.len() != reference.len()
+            || c.waveform.x_increment != reference.x_increment
+            || c.waveform.x_origin != reference.x_origin
+        {
+            return Err(format!(
+                "{} does not share the time axis of {}",
+                c.name, channels[0].name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Encode the channels in a format
+///
+/// The channels must come from the same acquisition (same time axis).
+///
+pub fn encode_waveforms(
+    format: ExportFormat,
+    channels: &[ExportChannel],
+) -> Result<Vec<u8>, String> {
+    check_channels(channels)?;
+    match format {
+        ExportFormat::Csv => Ok(csv::encode(channels)),
+        ExportFormat::Npy => Ok(npy::encode(channels)),
+        ExportFormat::Wav16 => wav::encode_pcm16(channels),
+        ExportFormat::WavFloat => wav::encode_float(channels),
+        ExportFormat::Sigrok => sigrok::encode(channels),
+        ExportFormat::Vcd => Ok(vcd::encode(channels)),
+    }
+}
+
+/// Default directory of the exports, `$HOME/.panduza/hantek/exports`
+///
+pub fn default_directory() -> PathBuf {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join(".panduza")
+        .join("hantek")
+        .join("exports")
+}
+
+/// Path of an export requested by a client, resolved under the export directory
+///
+/// Relative paths are joined to the directory, absolute paths must be inside it.
+/// Paths with `..` are rejected, they could leave the directory.
+///
+pub fn resolve_path(directory: &Path, path: &str) -> Result<PathBuf, String> {
+    let requested = Path::new(path);
+    if requested
+        .components()
+        .any(|c| matches!(c, Component::ParentDir))
+    {
+        return Err(format!(
+            "Invalid export path {:?}, '..' is not allowed",
+            path
+        ));
+    }
+    let resolved = match requested.is_absolute() {
+        true => requested.to_path_buf(),
+        false => directory.join(requested),
+    };
+    if !resolved.starts_with(directory) || resolved == directory {
+        return Err(format!(
+            "Invalid export path {:?}, expected a file in {:?}",
+            path, directory
+        ));
+    }
+    Ok(resolved)
+}
+
+/// Write the channels into a file
+///
+/// For NPY the metadata is written next to the array, in a file with the same
+/// name and the `json` extension. Return the paths of the written files.
+///
+pub fn export_waveforms<P: AsRef<Path>>(
+    path: P,
+    format: ExportFormat,
+    channels: &[ExportChannel],
+) -> Result<Vec<PathBuf>, String> {
+    let path = path.as_ref();
+    let data = encode_waveforms(format, channels)?;
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Unable to create {:?}: {}", parent, e))?;
+    }
+    std::fs::write(path, data).map_err(|e| format!("Unable to write {:?}: {}", path, e))?;
+    let mut written = vec![path.to_path_buf()];
+
+    if format == ExportFormat::Npy {
+        let sidecar = path.with_extension("json");
+        let content = serde_json::to_string_pretty(&metadata(channels))
+            .map_err(|e| format!("Unable to serialize the metadata: {}", e))?;
+        std::fs::write(&sidecar, content)
+            .map_err(|e| format!("Unable to write {:?}: {}", sidecar, e))?;
+        written.push(sidecar);
+    }
+
+    Ok(written)
+}
+
+/// Mount the export attribute
+///
+/// `sources` names the waveforms of the interface that can be exported, the
+/// files are written in `directory`.
+///
+pub async fn mount<C: Container + 'static, I: WaveformAccessorModel + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<I>>,
+    sources: Vec<(&'static str, usize)>,
+    directory: PathBuf,
+) -> Result<(), Error> {
+    //
+    //
+    let att = parent
+        .create_attribute("export")
+        .with_rw()
+        .with_info(
+            "Capture waveforms and write them into a file on the host
+
+    { \"path\": \"capture.csv\", \"format\": \"CSV\", \"channels\": [\"CHANnel1\", \"CHANnel2\"] }
+The path is relative to the export directory of the instance ('export_directory'
+setting, $HOME/.panduza/hantek/exports by default), '..' is not allowed.
+The format is one of CSV, NPY, WAV16, WAV_FLOAT, SR (sigrok session) or VCD, it
+defaults to the extension of the path. The channels default to all the channels,
+they are captured from the same acquisition. The attribute publishes the result
+of each export:
+    { \"path\": \"<export directory>/capture.csv\", \"format\": \"CSV\", \"files\": [...], \"metadata\": {...} }
+    { \"error\": \"...\" }
+SR and VCD also hold a logic view of the channels, thresholded in the middle of
+the signal swing unless a threshold is given:
+    \"thresholds\": { \"CHANnel1\": { \"level\": 1.4, \"hysteresis\": 0.2 } }
+",
+        )
+        .finish_as_json()
+        .await?;
+    let logger = att.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    spawn_on_command!(
+        "on_command => export",
+        parent,
+        att,
+        on_command(
+            att.clone(),
+            interface.clone(),
+            sources.clone(),
+            directory.clone()
+        )
+    );
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+/// Capture the requested channels and export them
+///
+async fn capture_and_export<I: WaveformAccessorModel + 'static>(
+    interface: &Arc<Mutex<I>>,
+    sources: &[(&'static str, usize)],
+    directory: &Path,
+    command: &serde_json::Value,
+) -> Result<serde_json::Value, Error> {
+    let path = command
+        .get("path")
+        .and_then(|p| p.as_str())
+        .ok_or(Error::InvalidArgument(
+            "Export requires a 'path' string".to_string(),
+        ))?;
+    let path = resolve_path(directory, path).map_err(Error::InvalidArgument)?;
+    let format = match command.get("format").and_then(|f| f.as_str()) {
+        Some(name) => ExportFormat::from_name(name).map_err(Error::InvalidArgument)?,
+        None => ExportFormat::from_path(&path).ok_or(Error::InvalidArgument(format!(
+            "No format given and unknown extension for {:?}",
+            path
+        )))?,
+    };
+
+    //
+    // Selected channels, all of them by default
+    let selected: Vec<(&'static str, usize)> = match command.get("channels") {
+        Some(serde_json::Value::Array(names)) => names
+            .iter()
+            .map(|n| {
+                let name = n.as_str().unwrap_or_default();
+                sources
+                    .iter()
+                    .find(|(s, _)| s.eq_ignore_ascii_case(name))
+                    .cloned()
+                    .ok_or(Error::InvalidArgument(format!(
+                        "Invalid channel {:?}, expected one of {:?}",
+                        n,
+                        sources.iter().map(|(s, _)| *s).collect::<Vec<_>>()
+                    )))
+            })
+            .collect::<Result<_, Error>>()?,
+        _ => sources.to_vec(),
+    };
+
+    //
+    // Thresholds of the logic views, by channel name
+    let mut thresholds = Vec::with_capacity(selected.len());
+    for (name, _) in selected.iter() {
+        let threshold = command
+            .get("thresholds")
+            .and_then(|t| t.as_object())
+            .and_then(|t| t.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)))
+            .map(|(_, t)| parse_threshold(t))
+            .transpose()
+            .map_err(Error::InvalidArgument)?;
+        thresholds.push(threshold);
+    }
+
+    let indexes: Vec<usize> = selected.iter().map(|(_, i)| *i).collect();
+    let waveforms = interface.lock().await.get_waveforms_at(&indexes).await?;
+    let channels: Vec<ExportChannel> = selected
+        .iter()
+        .zip(waveforms.iter())
+        .zip(thresholds)
+        .map(|(((name, _), w), threshold)| match threshold {
+            Some(t) => ExportChannel::new(name, w).with_threshold(t),
+            None => ExportChannel::new(name, w),
+        })
+        .collect();
+
+    let written = export_waveforms(&path, format, &channels).map_err(Error::InvalidArgument)?;
+    Ok(json!({
+        "path": path.to_string_lossy(),
+        "format": format.name(),
+        "files": written.iter().map(|p| p.to_string_lossy()).collect::<Vec<_>>(),
+        "metadata": metadata(&channels),
+    }))
+}
+
+/// Threshold of the export command, `{ "level": 1.4, "hysteresis": 0.2 }`
+///
+fn parse_threshold(value: &serde_json::Value) -> Result<Threshold, String> {
+    let level = value
+        .get("level")
+        .and_then(|l| l.as_f64())
+        .ok_or("A threshold requires a 'level' number".to_string())?;
+    let hysteresis = value
+        .get("hysteresis")
+        .and_then(|h| h.as_f64())
+        .unwrap_or(0.0);
+    Ok(Threshold::new(level, hysteresis))
+}
+
+///
+///
+async fn on_command<I: WaveformAccessorModel + 'static>(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<I>>,
+    sources: Vec<(&'static str, usize)>,
+    directory: PathBuf,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Log
+        log_debug!(att.logger(), "export requested '{:?}'", command);
+
+        match capture_and_export(&interface, &sources, &directory, &command).await {
+            Ok(v) => att.set(v).await?,
+            Err(e) => {
+                log_warn!(att.logger(), "export failed '{:?}'", e);
+                att.set(json!({ "error": format!("{:?}", e) })).await?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::fmt::Write;
+
+use super::ExportChannel;
+
+/// Encode the channels as CSV, one row per sample with the time first
+///
+/// The metadata is written as `#` comment lines before the header row.
+///
+pub fn encode(channels: &[ExportChannel]) -> Vec<u8> {
+    let reference = channels[0].waveform;
+    let mut text = String::new();
+
+    let _ = writeln!(
+        text,
+        "# channels: {}",
+        channels
+            .iter()
+            .map(|c| c.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let _ = writeln!(
+        text,
+        "# units: {}",
+        channels
+            .iter()
+            .map(|c| c.waveform.unit.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let _ = writeln!(text, "# x_origin: {}", reference.x_origin);
+    let _ = writeln!(text, "# x_increment: {}", reference.x_increment);
+    let _ = writeln!(text, "# points: {}", reference.len());
+
+    text.push_str("time (s)");
+    for c in channels {
+        let _ = write!(text, ",{} ({})", c.name, c.waveform.unit);
+    }
+    text.push('\n');
+
+    for i in 0..reference.len() {
+        let _ = write!(text, "{}", reference.time_at(i));
+        for c in channels {
+            let _ = write!(text, ",{}", c.waveform.samples[i]);
+        }
+        text.push('\n');
+    }
+
+    text.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waveform::Waveform;
+
+    #[test]
+    fn encode_rows() {
+        let ch1 = Waveform::new(vec![0.5, -1.0], 0.0, 0.25, "V");
+        let ch2 = Waveform::new(vec![1.0, 2.0], 0.0, 0.25, "A");
+        let text = String::from_utf8(encode(&[
+            ExportChannel::new("CH1", &ch1),
+            ExportChannel::new("CH2", &ch2),
+        ]))
+        .unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "# channels: CH1, CH2",
+                "# units: V, A",
+                "# x_origin: 0",
+                "# x_increment: 0.25",
+                "# points: 2",
+                "time (s),CH1 (V),CH2 (A)",
+                "0,0.5,1",
+                "0.25,-1,2",
+            ]
+        );
+    }
+}
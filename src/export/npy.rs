@@ -0,0 +1,76 @@
+use super::ExportChannel;
+
+/// Magic string and version 1.0 of the NumPy format
+///
+const MAGIC: &[u8] = b"\x93NUMPY\x01\x00";
+
+/// The data starts on a multiple of this size
+///
+const ALIGNMENT: usize = 64;
+
+/// Encode the channels as a NumPy array of little endian float64
+///
+/// The array has one row per sample, the time in the first column then one
+/// column per channel.
+///
+pub fn encode(channels: &[ExportChannel]) -> Vec<u8> {
+    let reference = channels[0].waveform;
+    let rows = reference.len();
+    let columns = channels.len() + 1;
+
+    //
+    // Header dictionary, padded with spaces and ended by a newline
+    let mut header = format!(
+        "{{'descr': '<f8', 'fortran_order': False, 'shape': ({}, {}), }}",
+        rows, columns
+    );
+    let unpadded = MAGIC.len() + 2 + header.len() + 1;
+    header.push_str(&" ".repeat((ALIGNMENT - unpadded % ALIGNMENT) % ALIGNMENT));
+    header.push('\n');
+
+    let mut data = Vec::with_capacity(MAGIC.len() + 2 + header.len() + rows * columns * 8);
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    data.extend_from_slice(header.as_bytes());
+
+    for i in 0..rows {
+        data.extend_from_slice(&reference.time_at(i).to_le_bytes());
+        for c in channels {
+            data.extend_from_slice(&c.waveform.samples[i].to_le_bytes());
+        }
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waveform::Waveform;
+
+    #[test]
+    fn encode_aligned_array() {
+        let ch1 = Waveform::new(vec![0.5, -1.0, 2.25], 1e-3, 1e-6, "V");
+        let ch2 = Waveform::new(vec![1.0, 2.0, 3.0], 1e-3, 1e-6, "A");
+        let data = encode(&[
+            ExportChannel::new("CH1", &ch1),
+            ExportChannel::new("CH2", &ch2),
+        ]);
+
+        assert_eq!(&data[..8], MAGIC);
+        let header_len = u16::from_le_bytes([data[8], data[9]]) as usize;
+        let start = 10 + header_len;
+        assert_eq!(start % ALIGNMENT, 0);
+        let header = std::str::from_utf8(&data[10..start]).unwrap();
+        assert!(header.starts_with("{'descr': '<f8', 'fortran_order': False, 'shape': (3, 3), }"));
+        assert!(header.ends_with(" \n"));
+
+        let values: Vec<f64> = data[start..]
+            .chunks_exact(8)
+            .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(values.len(), 9);
+        assert_eq!(&values[..3], &[1e-3, 0.5, 1.0]);
+        assert_eq!(&values[6..], &[ch1.time_at(2), 2.25, 3.0]);
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt::Write;
+
+use super::zip::ZipWriter;
+use super::ExportChannel;
+
+/// Version of the sigrok session format
+///
+const SESSION_VERSION: &str = "2";
+
+/// Encode the channels as a sigrok session, each channel as a logic trace and
+/// as an analog trace
+///
+/// Logic channels are packed in one byte per sample, the channel `n` in the bit
+/// `n`. Analog channels are stored as little endian float32.
+///
+pub fn encode(channels: &[ExportChannel]) -> Result<Vec<u8>, String> {
+    if channels.len() > 8 {
+        return Err(format!(
+            "{} channels, a session holds up to 8 logic channels",
+            channels.len()
+        ));
+    }
+    let reference = channels[0].waveform;
+    let rate = reference.sample_rate().round();
+    if !(1.0..=u64::MAX as f64).contains(&rate) {
+        return Err(format!("Invalid sample rate {}", rate));
+    }
+
+    //
+    // Logic, one bit per channel
+    let mut logic = vec![0u8; reference.len()];
+    for (bit, c) in channels.iter().enumerate() {
+        for (i, level) in c.logic().levels.iter().enumerate() {
+            logic[i] |= (*level as u8) << bit;
+        }
+    }
+
+    //
+    // Metadata, analog channels are numbered after the logic ones
+    let mut metadata = String::new();
+    let _ = writeln!(metadata, "[global]");
+    let _ = writeln!(metadata, "sigrok version=0.5.2");
+    let _ = writeln!(metadata);
+    let _ = writeln!(metadata, "[device 1]");
+    let _ = writeln!(metadata, "capturefile=logic-1");
+    let _ = writeln!(metadata, "total probes={}", channels.len());
+    let _ = writeln!(metadata, "samplerate={} Hz", rate as u64);
+    let _ = writeln!(metadata, "total analog={}", channels.len());
+    for (i, c) in channels.iter().enumerate() {
+        let _ = writeln!(metadata, "probe{}={}", i + 1, c.name);
+    }
+    for (i, c) in channels.iter().enumerate() {
+        let _ = writeln!(
+            metadata,
+            "analog{}={} ({})",
+            channels.len() + i + 1,
+            c.name,
+            c.waveform.unit
+        );
+    }
+    let _ = writeln!(metadata, "unitsize=1");
+
+    let mut zip = ZipWriter::default();
+    zip.add("version", SESSION_VERSION.as_bytes())?;
+    zip.add("metadata", metadata.as_bytes())?;
+    zip.add("logic-1-1", &logic)?;
+    for (i, c) in channels.iter().enumerate() {
+        let samples: Vec<u8> = c
+            .waveform
+            .samples
+            .iter()
+            .flat_map(|s| (*s as f32).to_le_bytes())
+            .collect();
+        zip.add(&format!("analog-1-{}-1", channels.len() + i + 1), &samples)?;
+    }
+    zip.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::Threshold;
+    use crate::waveform::Waveform;
+
+    /// Entries of a stored ZIP archive, from the local headers
+    fn unzip(data: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let u16_at = |i: usize| u16::from_le_bytes([data[i], data[i + 1]]) as usize;
+        let u32_at = |i: usize| u32::from_le_bytes(data[i..i + 4].try_into().unwrap()) as usize;
+        let mut entries = Vec::new();
+        let mut i = 0;
+        while data[i..i + 4] == *b"PK\x03\x04" {
+            let size = u32_at(i + 18);
+            let name_len = u16_at(i + 26);
+            let start = i + 30 + name_len + u16_at(i + 28);
+            let name = String::from_utf8(data[i + 30..i + 30 + name_len].to_vec()).unwrap();
+            entries.push((name, data[start..start + size].to_vec()));
+            i = start + size;
+        }
+        entries
+    }
+
+    #[test]
+    fn round_trip_session() {
+        let clk = Waveform::new(vec![0.0, 3.3, 0.0, 3.3, 0.0], 0.0, 1e-6, "V");
+        let data = Waveform::new(vec![3.3, 3.3, 0.0, 0.0, 3.3], 0.0, 1e-6, "V");
+        let channels = [
+            ExportChannel::new("CH1", &clk),
+            ExportChannel::new("CH2", &data).with_threshold(Threshold::new(1.5, 0.2)),
+        ];
+        let entries = unzip(&encode(&channels).unwrap());
+        let names: Vec<&str> = entries.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "version",
+                "metadata",
+                "logic-1-1",
+                "analog-1-3-1",
+                "analog-1-4-1"
+            ]
+        );
+        assert_eq!(entries[0].1, b"2");
+
+        let metadata = String::from_utf8(entries[1].1.clone()).unwrap();
+        for line in [
+            "capturefile=logic-1",
+            "total probes=2",
+            "samplerate=1000000 Hz",
+            "total analog=2",
+            "probe1=CH1",
+            "probe2=CH2",
+            "analog3=CH1 (V)",
+            "analog4=CH2 (V)",
+            "unitsize=1",
+        ] {
+            assert!(metadata.lines().any(|l| l == line), "{}", line);
+        }
+
+        //
+        // CH1 in the bit 0, CH2 in the bit 1
+        assert_eq!(entries[2].1, vec![0b10, 0b11, 0b00, 0b01, 0b10]);
+
+        let analog: Vec<f32> = entries[4]
+            .1
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(analog, vec![3.3, 3.3, 0.0, 0.0, 3.3]);
+    }
+
+    #[test]
+    fn reject_too_many_channels() {
+        let w = Waveform::new(vec![0.0, 1.0], 0.0, 1e-6, "V");
+        let channels = vec![ExportChannel::new("CH", &w); 9];
+        assert!(encode(&channels).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt::Write;
+
+use super::ExportChannel;
+
+/// Time unit of the dump, in seconds
+///
+const TIMESCALE: f64 = 1e-12;
+
+/// Identifier of the variable `n`, printable characters from '!'
+///
+fn identifier(n: usize) -> String {
+    let mut n = n;
+    let mut id = String::new();
+    loop {
+        id.push((b'!' + (n % 94) as u8) as char);
+        n /= 94;
+        if n == 0 {
+            return id;
+        }
+        n -= 1;
+    }
+}
+
+/// Encode the channels as a Value Change Dump, each channel as a logic wire and
+/// as a real variable
+///
+/// Times are in picoseconds from the first sample, the time of the first sample
+/// and the thresholds are written in the comment.
+///
+pub fn encode(channels: &[ExportChannel]) -> Vec<u8> {
+    let reference = channels[0].waveform;
+    let logic: Vec<Vec<bool>> = channels.iter().map(|c| c.logic().levels).collect();
+    let wires: Vec<String> = (0..channels.len()).map(identifier).collect();
+    let reals: Vec<String> = (0..channels.len())
+        .map(|i| identifier(channels.len() + i))
+        .collect();
+
+    let mut text = String::new();
+    let _ = writeln!(text, "$version pza-plugin-hantek $end");
+    let _ = writeln!(
+        text,
+        "$comment {}; thresholds: {} $end",
+        super::metadata_comment(channels),
+        channels
+            .iter()
+            .map(|c| {
+                let t = c.threshold();
+                format!("{} {} +/- {}", c.name, t.level, t.hysteresis / 2.0)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let _ = writeln!(text, "$timescale 1 ps $end");
+    let _ = writeln!(text, "$scope module scope $end");
+    for (i, c) in channels.iter().enumerate() {
+        let _ = writeln!(text, "$var wire 1 {} {} $end", wires[i], c.name);
+        let _ = writeln!(
+            text,
+            "$var real 64 {} {}_{} $end",
+            reals[i], c.name, c.waveform.unit
+        );
+    }
+    let _ = writeln!(text, "$upscope $end");
+    let _ = writeln!(text, "$enddefinitions $end");
+
+    //
+    // Initial values then the changes only
+    for i in 0..reference.len() {
+        let mut changes = String::new();
+        for (c, channel) in channels.iter().enumerate() {
+            if i == 0 || logic[c][i] != logic[c][i - 1] {
+                let _ = writeln!(changes, "{}{}", logic[c][i] as u8, wires[c]);
+            }
+            let sample = channel.waveform.samples[i];
+            if i == 0 || sample != channel.waveform.samples[i - 1] {
+                let _ = writeln!(changes, "r{} {}", sample, reals[c]);
+            }
+        }
+        if changes.is_empty() {
+            continue;
+        }
+        let time = (i as f64 * reference.x_increment / TIMESCALE).round() as u64;
+        let _ = writeln!(text, "#{}", time);
+        match i {
+            0 => {
+                let _ = write!(text, "$dumpvars\n{}$end\n", changes);
+            }
+            _ => text.push_str(&changes),
+        }
+    }
+
+    text.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waveform::Waveform;
+    use std::collections::HashMap;
+
+    /// Record the current value of each variable
+    fn snapshot(values: &HashMap<String, String>, history: &mut HashMap<String, Vec<String>>) {
+        for (id, v) in values.iter() {
+            history.entry(id.clone()).or_default().push(v.clone());
+        }
+    }
+
+    /// Values of the variables at each time of the dump, by variable name
+    fn replay(text: &str) -> (Vec<u64>, HashMap<String, Vec<String>>) {
+        let mut names = HashMap::new();
+        let mut values: HashMap<String, String> = HashMap::new();
+        let mut times = Vec::new();
+        let mut history: HashMap<String, Vec<String>> = HashMap::new();
+        for line in text.lines() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.first() {
+                Some(&"$var") => {
+                    names.insert(words[3].to_string(), words[4].to_string());
+                }
+                Some(w) if w.starts_with('#') => {
+                    if !times.is_empty() {
+                        snapshot(&values, &mut history);
+                    }
+                    times.push(w[1..].parse().unwrap());
+                }
+                Some(w) if w.starts_with('r') => {
+                    values.insert(words[1].to_string(), w[1..].to_string());
+                }
+                Some(w) if w.starts_with('0') || w.starts_with('1') => {
+                    values.insert(w[1..].to_string(), w[..1].to_string());
+                }
+                _ => {}
+            }
+        }
+        snapshot(&values, &mut history);
+        let by_name = history
+            .into_iter()
+            .map(|(id, v)| (names[&id].clone(), v))
+            .collect();
+        (times, by_name)
+    }
+
+    #[test]
+    fn assign_identifiers() {
+        assert_eq!(identifier(0), "!");
+        assert_eq!(identifier(93), "~");
+        assert_eq!(identifier(94), "!!");
+        assert_eq!(identifier(95), "\"!");
+    }
+
+    #[test]
+    fn round_trip_value_changes() {
+        let clk = Waveform::new(vec![0.0, 3.3, 3.3, 0.0, 0.0], 0.0, 2e-9, "V");
+        let cur = Waveform::new(vec![0.5, 0.5, 0.5, 0.5, 0.25], 0.0, 2e-9, "A");
+        let text = String::from_utf8(encode(&[
+            ExportChannel::new("CH1", &clk),
+            ExportChannel::new("CH2", &cur),
+        ]))
+        .unwrap();
+        assert!(text.contains("$timescale 1 ps $end"));
+        assert!(text.contains("$var wire 1 ! CH1 $end"));
+        assert!(text.contains("$var real 64 # CH1_V $end"));
+
+        //
+        // Only the samples with a change are dumped
+        let (times, values) = replay(&text);
+        assert_eq!(times, vec![0, 2000, 6000, 8000]);
+        assert_eq!(values["CH1"], vec!["0", "1", "0", "0"]);
+        assert_eq!(values["CH1_V"], vec!["0", "3.3", "0", "0"]);
+        assert_eq!(values["CH2"], vec!["1", "1", "1", "0"]);
+        assert_eq!(values["CH2_A"], vec!["0.5", "0.5", "0.5", "0.25"]);
+    }
+}
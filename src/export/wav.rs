@@ -0,0 +1,215 @@
+use super::ExportChannel;
+
+/// PCM format tag
+///
+const FORMAT_PCM: u16 = 1;
+
+/// IEEE float format tag
+///
+const FORMAT_IEEE_FLOAT: u16 = 3;
+
+/// Encode the channels as a 16 bits PCM WAV
+///
+/// The samples are scaled so that the largest absolute value of all the
+/// channels is full scale, the scale is recorded in the comment.
+///
+pub fn encode_pcm16(channels: &[ExportChannel]) -> Result<Vec<u8>, String> {
+    let full_scale = channels
+        .iter()
+        .flat_map(|c| c.waveform.samples.iter())
+        .fold(0.0f64, |m, s| m.max(s.abs()));
+    let gain = match full_scale > 0.0 {
+        true => i16::MAX as f64 / full_scale,
+        false => 0.0,
+    };
+
+    let mut data = Vec::with_capacity(channels.len() * channels[0].waveform.len() * 2);
+    for i in 0..channels[0].waveform.len() {
+        for c in channels {
+            let v = (c.waveform.samples[i] * gain).round();
+            let v = v.clamp(i16::MIN as f64, i16::MAX as f64) as i16;
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+
+    let comment = format!(
+        "{}; full_scale: {}",
+        super::metadata_comment(channels),
+        full_scale
+    );
+    encode(channels, FORMAT_PCM, 16, &data, &comment)
+}
+
+/// Encode the channels as a 32 bits float WAV, samples in the unit of the
+/// channels
+///
+pub fn encode_float(channels: &[ExportChannel]) -> Result<Vec<u8>, String> {
+    let mut data = Vec::with_capacity(channels.len() * channels[0].waveform.len() * 4);
+    for i in 0..channels[0].waveform.len() {
+        for c in channels {
+            data.extend_from_slice(&(c.waveform.samples[i] as f32).to_le_bytes());
+        }
+    }
+
+    let comment = super::metadata_comment(channels);
+    encode(channels, FORMAT_IEEE_FLOAT, 32, &data, &comment)
+}
+
+/// Append a RIFF chunk, padded to an even size
+///
+fn push_chunk(out: &mut Vec<u8>, id: &[u8; 4], payload: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(payload);
+    if payload.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+/// Build the RIFF file around interleaved sample data
+///
+fn encode(
+    channels: &[ExportChannel],
+    format: u16,
+    bits: u16,
+    data: &[u8],
+    comment: &str,
+) -> Result<Vec<u8>, String> {
+    let rate = channels[0].waveform.sample_rate().round();
+    if !(1.0..=u32::MAX as f64).contains(&rate) {
+        return Err(format!("Sample rate {} not representable in WAV", rate));
+    }
+    let rate = rate as u32;
+    let count = channels.len() as u16;
+    let block_align = count * bits / 8;
+    let byte_rate = rate
+        .checked_mul(block_align as u32)
+        .ok_or(format!("Sample rate {} too high for WAV", rate))?;
+    let frames = channels[0].waveform.len() as u32;
+
+    let mut fmt = Vec::with_capacity(18);
+    fmt.extend_from_slice(&format.to_le_bytes());
+    fmt.extend_from_slice(&count.to_le_bytes());
+    fmt.extend_from_slice(&rate.to_le_bytes());
+    fmt.extend_from_slice(&byte_rate.to_le_bytes());
+    fmt.extend_from_slice(&block_align.to_le_bytes());
+    fmt.extend_from_slice(&bits.to_le_bytes());
+    if format != FORMAT_PCM {
+        // Non PCM formats carry the size of the extension
+        fmt.extend_from_slice(&0u16.to_le_bytes());
+    }
+
+    let mut info = b"INFO".to_vec();
+    let mut text = comment.as_bytes().to_vec();
+    text.push(0);
+    push_chunk(&mut info, b"ICMT", &text);
+
+    let mut body = b"WAVE".to_vec();
+    push_chunk(&mut body, b"fmt ", &fmt);
+    if format != FORMAT_PCM {
+        push_chunk(&mut body, b"fact", &frames.to_le_bytes());
+    }
+    push_chunk(&mut body, b"LIST", &info);
+    push_chunk(&mut body, b"data", data);
+
+    let mut out = Vec::with_capacity(body.len() + 8);
+    push_chunk(&mut out, b"RIFF", &body);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waveform::Waveform;
+
+    /// Chunks of a RIFF WAVE file, by id
+    fn chunks(data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+        assert_eq!(&data[..4], b"RIFF");
+        assert_eq!(u32_at(data, 4) as usize, data.len() - 8);
+        assert_eq!(&data[8..12], b"WAVE");
+        let mut chunks = Vec::new();
+        let mut i = 12;
+        while i < data.len() {
+            let size = u32_at(data, i + 4) as usize;
+            chunks.push((
+                data[i..i + 4].try_into().unwrap(),
+                &data[i + 8..i + 8 + size],
+            ));
+            i += 8 + size + size % 2;
+        }
+        chunks
+    }
+
+    fn u32_at(data: &[u8], i: usize) -> u32 {
+        u32::from_le_bytes(data[i..i + 4].try_into().unwrap())
+    }
+
+    fn u16_at(data: &[u8], i: usize) -> u16 {
+        u16::from_le_bytes(data[i..i + 2].try_into().unwrap())
+    }
+
+    fn chunk<'a>(chunks: &[([u8; 4], &'a [u8])], id: &[u8; 4]) -> &'a [u8] {
+        chunks.iter().find(|(i, _)| i == id).unwrap().1
+    }
+
+    #[test]
+    fn encode_pcm16_header() {
+        let ch1 = Waveform::new(vec![0.0, 2.0, -1.0], 0.0, 1e-3, "V");
+        let ch2 = Waveform::new(vec![-2.0, 1.0, 0.0], 0.0, 1e-3, "V");
+        let data = encode_pcm16(&[
+            ExportChannel::new("CH1", &ch1),
+            ExportChannel::new("CH2", &ch2),
+        ])
+        .unwrap();
+        let chunks = chunks(&data);
+
+        let fmt = chunk(&chunks, b"fmt ");
+        assert_eq!(fmt.len(), 16);
+        assert_eq!(u16_at(fmt, 0), FORMAT_PCM);
+        assert_eq!(u16_at(fmt, 2), 2);
+        assert_eq!(u32_at(fmt, 4), 1000);
+        assert_eq!(u32_at(fmt, 8), 4000);
+        assert_eq!(u16_at(fmt, 12), 4);
+        assert_eq!(u16_at(fmt, 14), 16);
+
+        let samples: Vec<i16> = chunk(&chunks, b"data")
+            .chunks_exact(2)
+            .map(|c| i16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        assert_eq!(samples, vec![0, -32767, 32767, 16384, -16384, 0]);
+
+        let info = chunk(&chunks, b"LIST");
+        assert_eq!(&info[..8], b"INFOICMT");
+        let comment = std::str::from_utf8(&info[12..]).unwrap();
+        assert!(comment.contains("CH1 (V), CH2 (V)"));
+        assert!(comment.contains("full_scale: 2"));
+    }
+
+    #[test]
+    fn encode_float_header() {
+        let ch1 = Waveform::new(vec![0.25, -3.5], 0.0, 1e-6, "A");
+        let data = encode_float(&[ExportChannel::new("CH1", &ch1)]).unwrap();
+        let chunks = chunks(&data);
+
+        let fmt = chunk(&chunks, b"fmt ");
+        assert_eq!(fmt.len(), 18);
+        assert_eq!(u16_at(fmt, 0), FORMAT_IEEE_FLOAT);
+        assert_eq!(u16_at(fmt, 2), 1);
+        assert_eq!(u32_at(fmt, 4), 1_000_000);
+        assert_eq!(u16_at(fmt, 12), 4);
+        assert_eq!(u16_at(fmt, 14), 32);
+        assert_eq!(u32_at(chunk(&chunks, b"fact"), 0), 2);
+
+        let samples: Vec<f32> = chunk(&chunks, b"data")
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(samples, vec![0.25, -3.5]);
+    }
+
+    #[test]
+    fn reject_unrepresentable_rate() {
+        let ch1 = Waveform::new(vec![0.0, 1.0], 0.0, 10.0, "V");
+        assert!(encode_float(&[ExportChannel::new("CH1", &ch1)]).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+/// Version needed to extract, 2.0
+///
+const VERSION: u16 = 20;
+
+/// MS-DOS date of the entries, 1980-01-01
+///
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+/// CRC-32 (IEEE) of the data
+///
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB8_8320,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+/// ZIP archive built in memory, entries are stored without compression
+///
+#[derive(Default)]
+pub struct ZipWriter {
+    /// Local headers and data of the entries
+    ///
+    data: Vec<u8>,
+
+    /// Central directory headers of the entries
+    ///
+    directory: Vec<u8>,
+
+    ///
+    ///
+    entries: u16,
+}
+
+impl ZipWriter {
+    /// Append a file
+    ///
+    pub fn add(&mut self, name: &str, content: &[u8]) -> Result<(), String> {
+        let size = u32::try_from(content.len())
+            .map_err(|_| format!("{} is too large for the archive", name))?;
+        let offset =
+            u32::try_from(self.data.len()).map_err(|_| "The archive is too large".to_string())?;
+        let crc = crc32(content);
+
+        //
+        // Fields shared by the local and central headers, from the version needed
+        let mut common = Vec::with_capacity(26);
+        common.extend_from_slice(&VERSION.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes()); // flags
+        common.extend_from_slice(&0u16.to_le_bytes()); // stored
+        common.extend_from_slice(&0u16.to_le_bytes()); // time
+        common.extend_from_slice(&DOS_DATE.to_le_bytes());
+        common.extend_from_slice(&crc.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes()); // extra field
+
+        self.data.extend_from_slice(b"PK\x03\x04");
+        self.data.extend_from_slice(&common);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(content);
+
+        self.directory.extend_from_slice(b"PK\x01\x02");
+        self.directory.extend_from_slice(&VERSION.to_le_bytes()); // made by
+        self.directory.extend_from_slice(&common);
+        self.directory.extend_from_slice(&0u16.to_le_bytes()); // comment
+        self.directory.extend_from_slice(&0u16.to_le_bytes()); // disk
+        self.directory.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        self.directory.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        self.directory.extend_from_slice(&offset.to_le_bytes());
+        self.directory.extend_from_slice(name.as_bytes());
+
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Close the archive and return its content
+    ///
+    pub fn finish(mut self) -> Result<Vec<u8>, String> {
+        let offset =
+            u32::try_from(self.data.len()).map_err(|_| "The archive is too large".to_string())?;
+        let size = self.directory.len() as u32;
+
+        self.data.extend_from_slice(&self.directory);
+        self.data.extend_from_slice(b"PK\x05\x06");
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // disk
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // disk of the directory
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&size.to_le_bytes());
+        self.data.extend_from_slice(&offset.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // comment
+        Ok(self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(data: &[u8], i: usize) -> u16 {
+        u16::from_le_bytes(data[i..i + 2].try_into().unwrap())
+    }
+
+    fn u32_at(data: &[u8], i: usize) -> u32 {
+        u32::from_le_bytes(data[i..i + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn compute_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn write_central_directory() {
+        let files: [(&str, &[u8]); 2] = [("version", b"2"), ("logic-1-1", b"\x01\x00\x03")];
+        let mut zip = ZipWriter::default();
+        for (name, content) in files {
+            zip.add(name, content).unwrap();
+        }
+        let data = zip.finish().unwrap();
+
+        //
+        // End of central directory
+        let end = data.len() - 22;
+        assert_eq!(&data[end..end + 4], b"PK\x05\x06");
+        assert_eq!(u16_at(&data, end + 8), 2);
+        assert_eq!(u16_at(&data, end + 10), 2);
+        let size = u32_at(&data, end + 12) as usize;
+        let offset = u32_at(&data, end + 16) as usize;
+        assert_eq!(offset + size, end);
+
+        //
+        // Each central header points to a local header holding the same entry
+        let mut i = offset;
+        for (name, content) in files {
+            assert_eq!(&data[i..i + 4], b"PK\x01\x02");
+            let crc = u32_at(&data, i + 16);
+            assert_eq!(crc, crc32(content));
+            assert_eq!(u32_at(&data, i + 20) as usize, content.len());
+            let name_len = u16_at(&data, i + 28) as usize;
+            assert_eq!(&data[i + 46..i + 46 + name_len], name.as_bytes());
+
+            let local = u32_at(&data, i + 42) as usize;
+            assert_eq!(&data[local..local + 4], b"PK\x03\x04");
+            assert_eq!(u16_at(&data, local + 8), 0);
+            assert_eq!(u32_at(&data, local + 14), crc);
+            let start = local + 30 + name_len;
+            assert_eq!(&data[local + 30..start], name.as_bytes());
+            assert_eq!(&data[start..start + content.len()], content);
+
+            i += 46 + name_len;
+        }
+        assert_eq!(i, end);
+    }
+}
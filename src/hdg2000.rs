@@ -0,0 +1,48 @@
+mod device;
+
+use device::Device;
+use panduza_platform_core::{DriverOperations, Producer};
+
+/// Producer of the Hantek HDG2000 function/arbitrary waveform generators
+///
+pub struct Package {
+    /// Model name (e.g. HDG2002, HDG2012)
+    ///
+    model: String,
+}
+
+impl Package {
+    pub fn new<A: Into<String>>(model: A) -> Self {
+        Self {
+            model: model.into(),
+        }
+    }
+
+    pub fn boxed(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Producer for Package {
+    fn manufacturer(&self) -> String {
+        "hantek".to_string()
+    }
+
+    fn model(&self) -> String {
+        self.model.clone()
+    }
+
+    fn description(&self) -> String {
+        "2 channels function/arbitrary waveform generator".to_string()
+    }
+
+    fn props(&self) -> panduza_platform_core::Props {
+        let props = panduza_platform_core::Props::default();
+
+        props
+    }
+
+    fn produce(&self) -> Result<Box<dyn DriverOperations>, panduza_platform_core::Error> {
+        return Ok(Box::new(Device::new(self.model.clone())));
+    }
+}
@@ -0,0 +1,102 @@
+mod channel;
+mod interface;
+mod sync;
+
+use interface::HDG2000Interface;
+
+use async_trait::async_trait;
+use panduza_platform_core::connector::usb::tmc::Driver as UsbTmcDriver;
+use panduza_platform_core::connector::usb::Settings as UsbSettings;
+use panduza_platform_core::{log_debug, Container, DriverOperations, Error, Instance};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+/// Number of output channels of the HDG2000 generators
+///
+const CHANNEL_COUNT: usize = 2;
+
+///
+/// Device to control the Hantek HDG2000 generators
+///
+pub struct Device {
+    /// Model requested by the producer
+    ///
+    model: String,
+}
+
+impl Device {
+    ///
+    ///
+    pub fn new(model: String) -> Self {
+        Self { model }
+    }
+
+    /// Maximal frequency of the sine waveform, in Hz
+    ///
+    fn max_frequency(&self) -> f64 {
+        match self.model.to_uppercase().as_str() {
+            "HDG2002" | "HDG2002B" => 5_000_000.0,
+            "HDG2012" | "HDG2012B" => 10_000_000.0,
+            _ => 5_000_000.0,
+        }
+    }
+}
+
+#[async_trait]
+impl DriverOperations for Device {
+    ///
+    /// Mount the device instance
+    ///
+    async fn mount(&mut self, mut instance: Instance) -> Result<(), Error> {
+        //
+        //
+        let logger = instance.logger.clone();
+
+        //
+        // Usb settings
+        let settings = instance.settings().await.ok_or(Error::BadSettings(
+            "Usb Settings are required for this instance".to_string(),
+        ))?;
+
+        //
+        // Compose USB settings
+        let usb_settings = UsbSettings::from_json_settings(&settings);
+        log_debug!(logger, "Try to open SCPI interface on {:?}", &usb_settings);
+
+        //
+        // Mount the driver
+        let driver = UsbTmcDriver::open(&usb_settings)?.into_arc_mutex();
+
+        let interface: Arc<Mutex<HDG2000Interface>> =
+            Arc::new(Mutex::new(HDG2000Interface::new(driver, logger.clone())));
+
+        panduza_platform_core::std::class::repl::mount("repl", instance.clone(), interface.clone())
+            .await?;
+
+        panduza_platform_core::std::attribute::idn::mount(instance.clone(), interface.clone())
+            .await?;
+
+        let class_channels = instance.create_class("channel").finish().await;
+        for i in 1..=CHANNEL_COUNT {
+            channel::mount(
+                class_channels.clone(),
+                i,
+                interface.clone(),
+                self.max_frequency(),
+            )
+            .await?;
+        }
+
+        sync::mount(instance.clone(), interface.clone()).await?;
+
+        Ok(())
+    }
+    ///
+    /// Easiest way to implement the reboot event
+    ///
+    async fn wait_reboot_event(&mut self, mut _device: Instance) {
+        sleep(Duration::from_secs(5)).await;
+    }
+}
@@ -0,0 +1,306 @@
+use tokio::sync::Mutex;
+
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::{log_debug_mount_end, log_debug_mount_start, Container, Error};
+use std::sync::Arc;
+
+use super::interface::BooleanIndex;
+use super::interface::FloatIndex;
+use super::interface::HDG2000Interface;
+use super::interface::StringIndex;
+use super::interface::{
+    BURST_MODE_CHOICES, MODULATION_TYPE_CHOICES, SWEEP_SPACING_CHOICES, WAVEFORM_CHOICES,
+};
+use crate::si as std_att_si;
+use crate::si::SiFormat;
+
+/// Select the index of the channel
+///
+fn by_channel(channel_id: usize, channel1: usize, channel2: usize) -> usize {
+    match channel_id {
+        2 => channel2,
+        _ => channel1,
+    }
+}
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    channel_id: usize,
+    interface: Arc<Mutex<HDG2000Interface>>,
+    max_frequency: f64,
+) -> Result<(), Error> {
+    let mut class_chan = parent
+        .create_class(format!("{}", channel_id))
+        .finish()
+        .await;
+
+    let logger = class_chan.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    //
+    std_att_boolean::mount(
+        class_chan.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            BooleanIndex::Channel1Output as usize,
+            BooleanIndex::Channel2Output as usize,
+        ),
+        "output_enable",
+        "Enable or disable the output of the channel",
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_chan.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            StringIndex::Channel1Waveform as usize,
+            StringIndex::Channel2Waveform as usize,
+        ),
+        "waveform",
+        "Waveform generated on the channel",
+        WAVEFORM_CHOICES.to_vec(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_chan.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1Frequency as usize,
+            FloatIndex::Channel2Frequency as usize,
+        ),
+        "frequency",
+        "Frequency of the waveform",
+        SiFormat::new("Hz", 0.000001, max_frequency, 6),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_chan.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1Amplitude as usize,
+            FloatIndex::Channel2Amplitude as usize,
+        ),
+        "amplitude",
+        "Peak to peak amplitude of the waveform",
+        SiFormat::new("V", 0.001, 20.0, 3),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_chan.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1Offset as usize,
+            FloatIndex::Channel2Offset as usize,
+        ),
+        "offset",
+        "DC offset of the waveform",
+        SiFormat::new("V", -10.0, 10.0, 3),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_chan.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1Phase as usize,
+            FloatIndex::Channel2Phase as usize,
+        ),
+        "phase",
+        "Phase of the waveform",
+        SiFormat::new("°", 0.0, 360.0, 1),
+    )
+    .await?;
+
+    //
+    // Sweep
+    let class_sweep = class_chan.create_class("sweep").finish().await;
+    std_att_boolean::mount(
+        class_sweep.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            BooleanIndex::Channel1SweepEnable as usize,
+            BooleanIndex::Channel2SweepEnable as usize,
+        ),
+        "enable",
+        "Enable the frequency sweep",
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_sweep.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            StringIndex::Channel1SweepSpacing as usize,
+            StringIndex::Channel2SweepSpacing as usize,
+        ),
+        "spacing",
+        "Linear or logarithmic frequency sweep",
+        SWEEP_SPACING_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_sweep.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1SweepStart as usize,
+            FloatIndex::Channel2SweepStart as usize,
+        ),
+        "start",
+        "Start frequency of the sweep",
+        SiFormat::new("Hz", 0.000001, max_frequency, 6),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_sweep.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1SweepStop as usize,
+            FloatIndex::Channel2SweepStop as usize,
+        ),
+        "stop",
+        "Stop frequency of the sweep",
+        SiFormat::new("Hz", 0.000001, max_frequency, 6),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_sweep.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1SweepTime as usize,
+            FloatIndex::Channel2SweepTime as usize,
+        ),
+        "time",
+        "Duration of the sweep",
+        SiFormat::new("s", 0.001, 500.0, 3),
+    )
+    .await?;
+
+    //
+    // Burst
+    let class_burst = class_chan.create_class("burst").finish().await;
+    std_att_boolean::mount(
+        class_burst.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            BooleanIndex::Channel1BurstEnable as usize,
+            BooleanIndex::Channel2BurstEnable as usize,
+        ),
+        "enable",
+        "Enable the burst mode",
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_burst.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            StringIndex::Channel1BurstMode as usize,
+            StringIndex::Channel2BurstMode as usize,
+        ),
+        "mode",
+        "
+    * TRIGgered: output a number of cycles on each trigger
+    * GATed: output while the gate signal is active
+    * INFinity: output continuously once triggered
+        ",
+        BURST_MODE_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_burst.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1BurstCycles as usize,
+            FloatIndex::Channel2BurstCycles as usize,
+        ),
+        "cycles",
+        "Number of cycles of a triggered burst",
+        SiFormat::new("", 1.0, 1_000_000.0, 0),
+    )
+    .await?;
+
+    //
+    // Modulation
+    let class_modulation = class_chan.create_class("modulation").finish().await;
+    std_att_boolean::mount(
+        class_modulation.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            BooleanIndex::Channel1ModulationEnable as usize,
+            BooleanIndex::Channel2ModulationEnable as usize,
+        ),
+        "enable",
+        "Enable the modulation",
+    )
+    .await?;
+
+    std_att_enum::mount(
+        class_modulation.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            StringIndex::Channel1ModulationType as usize,
+            StringIndex::Channel2ModulationType as usize,
+        ),
+        "type",
+        "Type of modulation",
+        MODULATION_TYPE_CHOICES.to_vec(),
+    )
+    .await?;
+
+    std_att_si::mount(
+        class_modulation.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1ModulationFrequency as usize,
+            FloatIndex::Channel2ModulationFrequency as usize,
+        ),
+        "frequency",
+        "Frequency of the internal modulating signal",
+        SiFormat::new("Hz", 0.002, 20_000.0, 3),
+    )
+    .await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
@@ -0,0 +1,566 @@
+use async_trait::async_trait;
+use panduza_platform_core::connector::usb::tmc::Driver as UsbTmcInterface;
+
+use panduza_platform_core::std::attribute::boolean::BooleanAccessorModel;
+use panduza_platform_core::std::attribute::idn::IdnReader;
+use panduza_platform_core::std::attribute::r#enum::StringAccessorModel;
+use panduza_platform_core::std::class::repl::ReplProtocol;
+use panduza_platform_core::{log_info, Error, Logger};
+use strum_macros::FromRepr;
+use tokio::sync::Mutex;
+
+use std::sync::Arc;
+
+use crate::scpi::ScpiString;
+use crate::scpi::ScpiTmc;
+use crate::si::FloatAccessorModel;
+
+/// Choices of the channel waveform
+///
+pub const WAVEFORM_CHOICES: [&str; 7] = [
+    "SINusoid",
+    "SQUare",
+    "RAMP",
+    "PULSe",
+    "NOISe",
+    "DC",
+    "ARBitrary",
+];
+
+/// Choices of the sweep spacing
+///
+pub const SWEEP_SPACING_CHOICES: [&str; 2] = ["LINear", "LOGarithmic"];
+
+/// Choices of the burst mode
+///
+pub const BURST_MODE_CHOICES: [&str; 3] = ["TRIGgered", "GATed", "INFinity"];
+
+/// Choices of the modulation type
+///
+pub const MODULATION_TYPE_CHOICES: [&str; 7] = ["AM", "FM", "PM", "ASK", "FSK", "PSK", "PWM"];
+
+#[derive(Clone)]
+/// Interface to communicate with the HDG2000 device
+///
+pub struct HDG2000Interface {
+    /// SCPI commands and queries over the USB TMC connection
+    ///
+    scpi: ScpiTmc,
+}
+
+///
+///
+impl HDG2000Interface {
+    ///
+    ///
+    pub fn new(sub_interface: Arc<Mutex<UsbTmcInterface>>, logger: Logger) -> Self {
+        //
+        // Log
+        log_info!(logger, "Create HDG2000Interface with USBTMC sub_interface",);
+
+        //
+        // Build the object
+        HDG2000Interface {
+            scpi: ScpiTmc::new(sub_interface, logger),
+        }
+    }
+
+    /// Align the phases of both channels
+    ///
+    pub async fn synchronize_channels(&self) -> Result<(), Error> {
+        self.scpi.send("SOURce1:PHASe:SYNChronize").await
+    }
+}
+
+#[async_trait]
+impl ReplProtocol for HDG2000Interface {
+    ///
+    ///
+    async fn eval(&mut self, command: String) -> Result<String, Error> {
+        self.scpi.eval(command).await
+    }
+}
+
+#[async_trait]
+/// Implement IDN Protocol
+///
+impl IdnReader for HDG2000Interface {
+    async fn read_idn(&mut self) -> Result<String, Error> {
+        let response = self.scpi.ask("*IDN?".as_bytes()).await?;
+        match ScpiString::from_vec_ascii(&response) {
+            Ok(s) => Ok(s.into()),
+            Err(_) => Ok("Cannot convert the payload into string".to_string()),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum BooleanIndex {
+    //
+    Channel1Output,
+    Channel1SweepEnable,
+    Channel1BurstEnable,
+    Channel1ModulationEnable,
+    //
+    Channel2Output,
+    Channel2SweepEnable,
+    Channel2BurstEnable,
+    Channel2ModulationEnable,
+}
+
+#[async_trait]
+///
+///
+impl BooleanAccessorModel for HDG2000Interface {
+    ///
+    ///
+    async fn get_boolean_at(&mut self, index: usize) -> Result<bool, Error> {
+        //
+        // Get the index
+        let idx = BooleanIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            //
+            BooleanIndex::Channel1Output => self.scpi.get_boolean_parameter(b"OUTPut1?").await,
+            BooleanIndex::Channel1SweepEnable => {
+                self.scpi
+                    .get_boolean_parameter(b"SOURce1:SWEep:STATe?")
+                    .await
+            }
+            BooleanIndex::Channel1BurstEnable => {
+                self.scpi
+                    .get_boolean_parameter(b"SOURce1:BURSt:STATe?")
+                    .await
+            }
+            BooleanIndex::Channel1ModulationEnable => {
+                self.scpi.get_boolean_parameter(b"SOURce1:MOD:STATe?").await
+            }
+            //
+            BooleanIndex::Channel2Output => self.scpi.get_boolean_parameter(b"OUTPut2?").await,
+            BooleanIndex::Channel2SweepEnable => {
+                self.scpi
+                    .get_boolean_parameter(b"SOURce2:SWEep:STATe?")
+                    .await
+            }
+            BooleanIndex::Channel2BurstEnable => {
+                self.scpi
+                    .get_boolean_parameter(b"SOURce2:BURSt:STATe?")
+                    .await
+            }
+            BooleanIndex::Channel2ModulationEnable => {
+                self.scpi.get_boolean_parameter(b"SOURce2:MOD:STATe?").await
+            }
+        }
+    }
+
+    ///
+    ///
+    async fn set_boolean_at(&mut self, index: usize, value: bool) -> Result<(), Error> {
+        //
+        // Get the index
+        let idx = BooleanIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            //
+            BooleanIndex::Channel1Output => self.scpi.set_boolean_parameter("OUTPut1", value).await,
+            BooleanIndex::Channel1SweepEnable => {
+                self.scpi
+                    .set_boolean_parameter("SOURce1:SWEep:STATe", value)
+                    .await
+            }
+            BooleanIndex::Channel1BurstEnable => {
+                self.scpi
+                    .set_boolean_parameter("SOURce1:BURSt:STATe", value)
+                    .await
+            }
+            BooleanIndex::Channel1ModulationEnable => {
+                self.scpi
+                    .set_boolean_parameter("SOURce1:MOD:STATe", value)
+                    .await
+            }
+            //
+            BooleanIndex::Channel2Output => self.scpi.set_boolean_parameter("OUTPut2", value).await,
+            BooleanIndex::Channel2SweepEnable => {
+                self.scpi
+                    .set_boolean_parameter("SOURce2:SWEep:STATe", value)
+                    .await
+            }
+            BooleanIndex::Channel2BurstEnable => {
+                self.scpi
+                    .set_boolean_parameter("SOURce2:BURSt:STATe", value)
+                    .await
+            }
+            BooleanIndex::Channel2ModulationEnable => {
+                self.scpi
+                    .set_boolean_parameter("SOURce2:MOD:STATe", value)
+                    .await
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum StringIndex {
+    //
+    Channel1Waveform,
+    Channel1SweepSpacing,
+    Channel1BurstMode,
+    Channel1ModulationType,
+    //
+    Channel2Waveform,
+    Channel2SweepSpacing,
+    Channel2BurstMode,
+    Channel2ModulationType,
+}
+
+#[async_trait]
+///
+///
+impl StringAccessorModel for HDG2000Interface {
+    ///
+    ///
+    async fn get_string_at(&mut self, index: usize) -> Result<String, Error> {
+        //
+        // Get the index
+        let idx = StringIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            //
+            StringIndex::Channel1Waveform => {
+                self.scpi
+                    .get_enum_parameter(b"SOURce1:FUNCtion?", &WAVEFORM_CHOICES)
+                    .await
+            }
+            StringIndex::Channel1SweepSpacing => {
+                self.scpi
+                    .get_enum_parameter(b"SOURce1:SWEep:SPACing?", &SWEEP_SPACING_CHOICES)
+                    .await
+            }
+            StringIndex::Channel1BurstMode => {
+                self.scpi
+                    .get_enum_parameter(b"SOURce1:BURSt:MODE?", &BURST_MODE_CHOICES)
+                    .await
+            }
+            StringIndex::Channel1ModulationType => {
+                self.scpi
+                    .get_enum_parameter(b"SOURce1:MOD:TYPE?", &MODULATION_TYPE_CHOICES)
+                    .await
+            }
+            //
+            StringIndex::Channel2Waveform => {
+                self.scpi
+                    .get_enum_parameter(b"SOURce2:FUNCtion?", &WAVEFORM_CHOICES)
+                    .await
+            }
+            StringIndex::Channel2SweepSpacing => {
+                self.scpi
+                    .get_enum_parameter(b"SOURce2:SWEep:SPACing?", &SWEEP_SPACING_CHOICES)
+                    .await
+            }
+            StringIndex::Channel2BurstMode => {
+                self.scpi
+                    .get_enum_parameter(b"SOURce2:BURSt:MODE?", &BURST_MODE_CHOICES)
+                    .await
+            }
+            StringIndex::Channel2ModulationType => {
+                self.scpi
+                    .get_enum_parameter(b"SOURce2:MOD:TYPE?", &MODULATION_TYPE_CHOICES)
+                    .await
+            }
+        }
+    }
+
+    ///
+    ///
+    async fn set_string_at(&mut self, index: usize, value: &String) -> Result<(), Error> {
+        //
+        // Get the index
+        let idx = StringIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            //
+            StringIndex::Channel1Waveform => {
+                self.scpi
+                    .set_string_parameter("SOURce1:FUNCtion", value)
+                    .await
+            }
+            StringIndex::Channel1SweepSpacing => {
+                self.scpi
+                    .set_string_parameter("SOURce1:SWEep:SPACing", value)
+                    .await
+            }
+            StringIndex::Channel1BurstMode => {
+                self.scpi
+                    .set_string_parameter("SOURce1:BURSt:MODE", value)
+                    .await
+            }
+            StringIndex::Channel1ModulationType => {
+                self.scpi
+                    .set_string_parameter("SOURce1:MOD:TYPE", value)
+                    .await
+            }
+            //
+            StringIndex::Channel2Waveform => {
+                self.scpi
+                    .set_string_parameter("SOURce2:FUNCtion", value)
+                    .await
+            }
+            StringIndex::Channel2SweepSpacing => {
+                self.scpi
+                    .set_string_parameter("SOURce2:SWEep:SPACing", value)
+                    .await
+            }
+            StringIndex::Channel2BurstMode => {
+                self.scpi
+                    .set_string_parameter("SOURce2:BURSt:MODE", value)
+                    .await
+            }
+            StringIndex::Channel2ModulationType => {
+                self.scpi
+                    .set_string_parameter("SOURce2:MOD:TYPE", value)
+                    .await
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum FloatIndex {
+    //
+    Channel1Frequency,
+    Channel1Amplitude,
+    Channel1Offset,
+    Channel1Phase,
+    Channel1SweepStart,
+    Channel1SweepStop,
+    Channel1SweepTime,
+    Channel1BurstCycles,
+    Channel1ModulationFrequency,
+    //
+    Channel2Frequency,
+    Channel2Amplitude,
+    Channel2Offset,
+    Channel2Phase,
+    Channel2SweepStart,
+    Channel2SweepStop,
+    Channel2SweepTime,
+    Channel2BurstCycles,
+    Channel2ModulationFrequency,
+}
+
+#[async_trait]
+///
+///
+impl FloatAccessorModel for HDG2000Interface {
+    ///
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error> {
+        //
+        // Get the index
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            //
+            FloatIndex::Channel1Frequency => {
+                self.scpi.get_float_parameter(b"SOURce1:FREQuency?").await
+            }
+            FloatIndex::Channel1Amplitude => {
+                self.scpi.get_float_parameter(b"SOURce1:VOLTage?").await
+            }
+            FloatIndex::Channel1Offset => {
+                self.scpi
+                    .get_float_parameter(b"SOURce1:VOLTage:OFFSet?")
+                    .await
+            }
+            FloatIndex::Channel1Phase => self.scpi.get_float_parameter(b"SOURce1:PHASe?").await,
+            FloatIndex::Channel1SweepStart => {
+                self.scpi
+                    .get_float_parameter(b"SOURce1:FREQuency:STARt?")
+                    .await
+            }
+            FloatIndex::Channel1SweepStop => {
+                self.scpi
+                    .get_float_parameter(b"SOURce1:FREQuency:STOP?")
+                    .await
+            }
+            FloatIndex::Channel1SweepTime => {
+                self.scpi.get_float_parameter(b"SOURce1:SWEep:TIME?").await
+            }
+            FloatIndex::Channel1BurstCycles => {
+                self.scpi
+                    .get_float_parameter(b"SOURce1:BURSt:NCYCles?")
+                    .await
+            }
+            FloatIndex::Channel1ModulationFrequency => {
+                self.scpi
+                    .get_float_parameter(b"SOURce1:MOD:FREQuency?")
+                    .await
+            }
+            //
+            FloatIndex::Channel2Frequency => {
+                self.scpi.get_float_parameter(b"SOURce2:FREQuency?").await
+            }
+            FloatIndex::Channel2Amplitude => {
+                self.scpi.get_float_parameter(b"SOURce2:VOLTage?").await
+            }
+            FloatIndex::Channel2Offset => {
+                self.scpi
+                    .get_float_parameter(b"SOURce2:VOLTage:OFFSet?")
+                    .await
+            }
+            FloatIndex::Channel2Phase => self.scpi.get_float_parameter(b"SOURce2:PHASe?").await,
+            FloatIndex::Channel2SweepStart => {
+                self.scpi
+                    .get_float_parameter(b"SOURce2:FREQuency:STARt?")
+                    .await
+            }
+            FloatIndex::Channel2SweepStop => {
+                self.scpi
+                    .get_float_parameter(b"SOURce2:FREQuency:STOP?")
+                    .await
+            }
+            FloatIndex::Channel2SweepTime => {
+                self.scpi.get_float_parameter(b"SOURce2:SWEep:TIME?").await
+            }
+            FloatIndex::Channel2BurstCycles => {
+                self.scpi
+                    .get_float_parameter(b"SOURce2:BURSt:NCYCles?")
+                    .await
+            }
+            FloatIndex::Channel2ModulationFrequency => {
+                self.scpi
+                    .get_float_parameter(b"SOURce2:MOD:FREQuency?")
+                    .await
+            }
+        }
+    }
+
+    ///
+    ///
+    async fn set_float_at(&mut self, index: usize, value: f64) -> Result<(), Error> {
+        //
+        // Get the index
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            //
+            FloatIndex::Channel1Frequency => {
+                self.scpi
+                    .set_float_parameter("SOURce1:FREQuency", value)
+                    .await
+            }
+            FloatIndex::Channel1Amplitude => {
+                self.scpi
+                    .set_float_parameter("SOURce1:VOLTage", value)
+                    .await
+            }
+            FloatIndex::Channel1Offset => {
+                self.scpi
+                    .set_float_parameter("SOURce1:VOLTage:OFFSet", value)
+                    .await
+            }
+            FloatIndex::Channel1Phase => {
+                self.scpi.set_float_parameter("SOURce1:PHASe", value).await
+            }
+            FloatIndex::Channel1SweepStart => {
+                self.scpi
+                    .set_float_parameter("SOURce1:FREQuency:STARt", value)
+                    .await
+            }
+            FloatIndex::Channel1SweepStop => {
+                self.scpi
+                    .set_float_parameter("SOURce1:FREQuency:STOP", value)
+                    .await
+            }
+            FloatIndex::Channel1SweepTime => {
+                self.scpi
+                    .set_float_parameter("SOURce1:SWEep:TIME", value)
+                    .await
+            }
+            FloatIndex::Channel1BurstCycles => {
+                self.scpi
+                    .set_float_parameter("SOURce1:BURSt:NCYCles", value)
+                    .await
+            }
+            FloatIndex::Channel1ModulationFrequency => {
+                self.scpi
+                    .set_float_parameter("SOURce1:MOD:FREQuency", value)
+                    .await
+            }
+            //
+            FloatIndex::Channel2Frequency => {
+                self.scpi
+                    .set_float_parameter("SOURce2:FREQuency", value)
+                    .await
+            }
+            FloatIndex::Channel2Amplitude => {
+                self.scpi
+                    .set_float_parameter("SOURce2:VOLTage", value)
+                    .await
+            }
+            FloatIndex::Channel2Offset => {
+                self.scpi
+                    .set_float_parameter("SOURce2:VOLTage:OFFSet", value)
+                    .await
+            }
+            FloatIndex::Channel2Phase => {
+                self.scpi.set_float_parameter("SOURce2:PHASe", value).await
+            }
+            FloatIndex::Channel2SweepStart => {
+                self.scpi
+                    .set_float_parameter("SOURce2:FREQuency:STARt", value)
+                    .await
+            }
+            FloatIndex::Channel2SweepStop => {
+                self.scpi
+                    .set_float_parameter("SOURce2:FREQuency:STOP", value)
+                    .await
+            }
+            FloatIndex::Channel2SweepTime => {
+                self.scpi
+                    .set_float_parameter("SOURce2:SWEep:TIME", value)
+                    .await
+            }
+            FloatIndex::Channel2BurstCycles => {
+                self.scpi
+                    .set_float_parameter("SOURce2:BURSt:NCYCles", value)
+                    .await
+            }
+            FloatIndex::Channel2ModulationFrequency => {
+                self.scpi
+                    .set_float_parameter("SOURce2:MOD:FREQuency", value)
+                    .await
+            }
+        }
+    }
+}
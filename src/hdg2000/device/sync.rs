@@ -0,0 +1,62 @@
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command,
+    BooleanAttServer, Container, Error,
+};
+use tokio::sync::Mutex;
+
+use std::sync::Arc;
+
+use super::interface::HDG2000Interface;
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<HDG2000Interface>>,
+) -> Result<(), Error> {
+    //
+    //
+    let att = parent
+        .create_attribute("sync")
+        .with_wo()
+        .with_info("Write true to align the phases of both channels")
+        .finish_as_boolean()
+        .await?;
+    let logger = att.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    spawn_on_command!(
+        "on_command => sync",
+        parent,
+        att,
+        on_command(att.clone(), interface.clone())
+    );
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+///
+///
+async fn on_command(
+    mut att: BooleanAttServer,
+    interface: Arc<Mutex<HDG2000Interface>>,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Log
+        log_debug!(att.logger(), "sync command received '{:?}'", command);
+
+        //
+        // A failed synchronization must not stop the attribute
+        if command {
+            if let Err(e) = interface.lock().await.synchronize_channels().await {
+                log_warn!(att.logger(), "sync failed '{:?}'", e);
+            }
+        }
+    }
+    Ok(())
+}
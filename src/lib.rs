@@ -3,13 +3,35 @@ use panduza_platform_core::{Producer, Scanner};
 #[cfg(feature = "plugin")]
 panduza_platform_core::plugin_interface!("hantek");
 
+pub mod bus;
 mod dso2c10;
+mod dso6022;
+pub mod export;
+mod hdg2000;
+mod pps2000;
+mod scpi;
+mod si;
+mod spectrum;
+mod stream;
+pub mod waveform;
 
 // Export the producers of the plugin
 //
 pub fn plugin_producers() -> Vec<Box<dyn Producer>> {
     let mut producers: Vec<Box<dyn Producer>> = vec![];
     producers.push(dso2c10::Package::default().boxed());
+    producers.push(dso2c10::Package::new("DSO2D10").boxed());
+    producers.push(dso2c10::Package::new("DSO2D15").boxed());
+    producers.push(dso2c10::Package::new("DSO4084C").boxed());
+    producers.push(dso2c10::Package::new("DSO4104C").boxed());
+    producers.push(dso2c10::Package::new("DSO4204C").boxed());
+    producers.push(dso2c10::Package::new("DSO4254C").boxed());
+    producers.push(dso6022::Package::new("DSO6022BE", 0x6022).boxed());
+    producers.push(dso6022::Package::new("DSO6022BL", 0x602a).boxed());
+    producers.push(hdg2000::Package::new("HDG2002").boxed());
+    producers.push(hdg2000::Package::new("HDG2012").boxed());
+    producers.push(pps2000::Package::new("PPS2116A").boxed());
+    producers.push(pps2000::Package::new("PPS2320A").boxed());
     // producers.push(kd3005p::producer::KD3005P::new());
     // producers.push(kd3005p_fake::producer::Kd3005pFake::new());
     return producers;
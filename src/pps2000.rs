@@ -0,0 +1,93 @@
+mod device;
+
+use device::Device;
+use panduza_platform_core::{DriverOperations, Producer};
+
+/// Capabilities of a PPS2000 model
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Model {
+    /// Number of output channels
+    ///
+    pub channel_count: usize,
+
+    /// Maximal voltage of a channel
+    ///
+    pub max_voltage: f64,
+
+    /// Maximal current of a channel
+    ///
+    pub max_current: f64,
+
+    /// True if the channels can be coupled in series or parallel
+    ///
+    pub has_tracking: bool,
+}
+
+impl Model {
+    /// Capabilities from the model name
+    ///
+    pub fn from_name(name: &str) -> Self {
+        match name.to_uppercase().as_str() {
+            "PPS2320A" | "PPS2320" => Self {
+                channel_count: 2,
+                max_voltage: 32.0,
+                max_current: 3.0,
+                has_tracking: true,
+            },
+            _ => Self {
+                channel_count: 1,
+                max_voltage: 32.0,
+                max_current: 5.0,
+                has_tracking: false,
+            },
+        }
+    }
+}
+
+/// Producer of the Hantek PPS2000 programmable power supplies
+///
+pub struct Package {
+    /// Model name (e.g. PPS2116A, PPS2320A)
+    ///
+    model: String,
+}
+
+impl Package {
+    pub fn new<A: Into<String>>(model: A) -> Self {
+        Self {
+            model: model.into(),
+        }
+    }
+
+    pub fn boxed(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Producer for Package {
+    fn manufacturer(&self) -> String {
+        "hantek".to_string()
+    }
+
+    fn model(&self) -> String {
+        self.model.clone()
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "{} channels programmable power supply",
+            Model::from_name(&self.model).channel_count
+        )
+    }
+
+    fn props(&self) -> panduza_platform_core::Props {
+        let props = panduza_platform_core::Props::default();
+
+        props
+    }
+
+    fn produce(&self) -> Result<Box<dyn DriverOperations>, panduza_platform_core::Error> {
+        return Ok(Box::new(Device::new(Model::from_name(&self.model))));
+    }
+}
@@ -0,0 +1,142 @@
+mod channel;
+mod interface;
+
+use interface::PPS2000Interface;
+
+use crate::pps2000::Model;
+use async_trait::async_trait;
+use panduza_platform_core::connector::serial::eol::Driver as SerialEolDriver;
+use panduza_platform_core::connector::serial::Settings as SerialSettings;
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::{log_debug, Container, DriverOperations, Error, Instance};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+use crate::si as std_att_si;
+use crate::si::SiFormat;
+use interface::{BooleanIndex, FloatIndex, StringIndex, TRACKING_MODE_CHOICES};
+
+/// Baudrate of the PPS2000 serial link
+///
+const BAUDRATE: u32 = 9600;
+
+///
+/// Device to control the Hantek PPS2000 power supplies
+///
+pub struct Device {
+    /// Capabilities of the model
+    ///
+    model: Model,
+}
+
+impl Device {
+    ///
+    ///
+    pub fn new(model: Model) -> Self {
+        Self { model }
+    }
+}
+
+#[async_trait]
+impl DriverOperations for Device {
+    ///
+    /// Mount the device instance
+    ///
+    async fn mount(&mut self, mut instance: Instance) -> Result<(), Error> {
+        //
+        //
+        let logger = instance.logger.clone();
+
+        //
+        // Serial settings
+        let settings = instance.settings().await.ok_or(Error::BadSettings(
+            "Serial Settings are required for this instance".to_string(),
+        ))?;
+
+        //
+        // Compose serial settings
+        let serial_settings = SerialSettings::from_json_settings(&settings).set_baudrate(BAUDRATE);
+        log_debug!(
+            logger,
+            "Try to open serial interface on {:?}",
+            &serial_settings
+        );
+
+        //
+        // Mount the driver
+        let driver = SerialEolDriver::open(&serial_settings, vec![b'\r', b'\n'])?.into_arc_mutex();
+
+        let interface: Arc<Mutex<PPS2000Interface>> =
+            Arc::new(Mutex::new(PPS2000Interface::new(driver, logger.clone())));
+
+        panduza_platform_core::std::class::repl::mount("repl", instance.clone(), interface.clone())
+            .await?;
+
+        //
+        //
+        std_att_boolean::mount(
+            instance.clone(),
+            interface.clone(),
+            BooleanIndex::Output as usize,
+            "output_enable",
+            "Enable or disable the outputs",
+        )
+        .await?;
+
+        //
+        // Protections are common to all the channels
+        std_att_si::mount(
+            instance.clone(),
+            interface.clone(),
+            FloatIndex::OverVoltageProtection as usize,
+            "ovp",
+            "Over voltage protection limit",
+            SiFormat::new("V", 0.0, self.model.max_voltage, 2),
+        )
+        .await?;
+
+        std_att_si::mount(
+            instance.clone(),
+            interface.clone(),
+            FloatIndex::OverCurrentProtection as usize,
+            "ocp",
+            "Over current protection limit",
+            SiFormat::new("A", 0.0, self.model.max_current, 3),
+        )
+        .await?;
+
+        //
+        // Series/parallel modes only on models with 2 channels
+        if self.model.has_tracking {
+            std_att_enum::mount(
+                instance.clone(),
+                interface.clone(),
+                StringIndex::TrackingMode as usize,
+                "tracking_mode",
+                "
+    * INDependent: channels are controlled independently
+    * SERies: channels are coupled in series, the channel 1 controls both
+    * PARallel: channels are coupled in parallel, the channel 1 controls both
+        ",
+                TRACKING_MODE_CHOICES.to_vec(),
+            )
+            .await?;
+        }
+
+        let class_channels = instance.create_class("channel").finish().await;
+        for i in 1..=self.model.channel_count {
+            channel::mount(class_channels.clone(), i, interface.clone(), &self.model).await?;
+        }
+
+        Ok(())
+    }
+    ///
+    /// Easiest way to implement the reboot event
+    ///
+    async fn wait_reboot_event(&mut self, mut _device: Instance) {
+        sleep(Duration::from_secs(5)).await;
+    }
+}
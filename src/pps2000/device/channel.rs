@@ -0,0 +1,129 @@
+use tokio::sync::Mutex;
+
+use panduza_platform_core::{log_debug_mount_end, log_debug_mount_start, Container, Error};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::interface::FloatIndex;
+use super::interface::PPS2000Interface;
+use crate::pps2000::Model;
+use crate::si as std_att_si;
+use crate::si::SiFormat;
+
+/// Refresh period of the measures
+///
+const MEASURE_PERIOD: Duration = Duration::from_millis(500);
+
+/// Select the index of the channel
+///
+fn by_channel(channel_id: usize, channel1: FloatIndex, channel2: FloatIndex) -> usize {
+    match channel_id {
+        2 => channel2 as usize,
+        _ => channel1 as usize,
+    }
+}
+
+///
+///
+pub async fn mount<C: Container + 'static>(
+    mut parent: C,
+    channel_id: usize,
+    interface: Arc<Mutex<PPS2000Interface>>,
+    model: &Model,
+) -> Result<(), Error> {
+    let class_chan = parent
+        .create_class(format!("{}", channel_id))
+        .finish()
+        .await;
+
+    let logger = class_chan.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    //
+    std_att_si::mount(
+        class_chan.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1Voltage,
+            FloatIndex::Channel2Voltage,
+        ),
+        "voltage",
+        "Voltage setpoint of the channel",
+        SiFormat::new("V", 0.0, model.max_voltage, 2),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_chan.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1Current,
+            FloatIndex::Channel2Current,
+        ),
+        "current",
+        "Current limit of the channel",
+        SiFormat::new("A", 0.0, model.max_current, 3),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_chan.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1MeasuredVoltage,
+            FloatIndex::Channel2MeasuredVoltage,
+        ),
+        "measured_voltage",
+        "Voltage measured on the output",
+        SiFormat::new("V", 0.0, model.max_voltage, 2),
+        MEASURE_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_chan.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1MeasuredCurrent,
+            FloatIndex::Channel2MeasuredCurrent,
+        ),
+        "measured_current",
+        "Current measured on the output",
+        SiFormat::new("A", 0.0, model.max_current, 3),
+        MEASURE_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_chan.clone(),
+        interface.clone(),
+        by_channel(
+            channel_id,
+            FloatIndex::Channel1MeasuredPower,
+            FloatIndex::Channel2MeasuredPower,
+        ),
+        "measured_power",
+        "Power delivered on the output, computed from the measures",
+        SiFormat::new("W", 0.0, model.max_voltage * model.max_current, 3),
+        MEASURE_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
@@ -0,0 +1,496 @@
+use async_trait::async_trait;
+use panduza_platform_core::connector::serial::eol::Driver as SerialEolInterface;
+
+use panduza_platform_core::std::attribute::boolean::BooleanAccessorModel;
+use panduza_platform_core::std::attribute::r#enum::StringAccessorModel;
+use panduza_platform_core::std::class::repl::ReplProtocol;
+use panduza_platform_core::{log_info, log_trace, Error, Logger};
+use strum_macros::FromRepr;
+use tokio::sync::Mutex;
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::scpi::ScpiString;
+use crate::si::FloatAccessorModel;
+
+/// Choices of the tracking mode, in the order of their protocol code
+///
+pub const TRACKING_MODE_CHOICES: [&str; 3] = ["INDependent", "SERies", "PARallel"];
+
+/// Resolution of the voltages in the protocol (10mV)
+///
+const VOLTAGE_RESOLUTION: f64 = 0.01;
+
+/// Resolution of the currents in the protocol (1mA)
+///
+const CURRENT_RESOLUTION: f64 = 0.001;
+
+/// Protocol commands of a channel
+///
+/// The PPS2000 protocol is made of 2 letters commands terminated by CR LF.
+/// Setters are followed by the value on 4 digits, in resolution units.
+/// Getters return the value on 4 digits, in resolution units.
+///
+struct ChannelCommands {
+    set_voltage: &'static str,
+    set_current: &'static str,
+    get_voltage: &'static str,
+    get_current: &'static str,
+    measure_voltage: &'static str,
+    measure_current: &'static str,
+}
+
+/// Commands of each channel
+///
+const CHANNEL_COMMANDS: [ChannelCommands; 2] = [
+    ChannelCommands {
+        set_voltage: "su",
+        set_current: "si",
+        get_voltage: "ru",
+        get_current: "ri",
+        measure_voltage: "rv",
+        measure_current: "ra",
+    },
+    ChannelCommands {
+        set_voltage: "sa",
+        set_current: "sd",
+        get_voltage: "rm",
+        get_current: "rn",
+        measure_voltage: "rk",
+        measure_current: "rq",
+    },
+];
+
+/// Protocol commands of a protection limit
+///
+/// The getter letters do not mirror the setters: the over voltage limit is read
+/// with `rp` because `ro` already reads the output state.
+///
+struct ProtectionCommands {
+    set: &'static str,
+    get: &'static str,
+    resolution: f64,
+}
+
+/// Commands of the over voltage protection
+///
+const OVER_VOLTAGE_PROTECTION: ProtectionCommands = ProtectionCommands {
+    set: "so",
+    get: "rp",
+    resolution: VOLTAGE_RESOLUTION,
+};
+
+/// Commands of the over current protection
+///
+const OVER_CURRENT_PROTECTION: ProtectionCommands = ProtectionCommands {
+    set: "sc",
+    get: "rc",
+    resolution: CURRENT_RESOLUTION,
+};
+
+#[derive(Clone)]
+/// Interface to communicate with the PPS2000 device
+///
+pub struct PPS2000Interface {
+    /// Lower level interface (serial)
+    ///
+    sub_interface: Arc<Mutex<SerialEolInterface>>,
+
+    /// Logger for the driver
+    ///
+    logger: Logger,
+}
+
+///
+///
+impl PPS2000Interface {
+    ///
+    ///
+    pub fn new(sub_interface: Arc<Mutex<SerialEolInterface>>, logger: Logger) -> Self {
+        //
+        // Log
+        log_info!(logger, "Create PPS2000Interface with serial sub_interface",);
+
+        //
+        // Build the object
+        PPS2000Interface {
+            sub_interface,
+            logger,
+        }
+    }
+
+    /// Send a query and return the normalized response of the device
+    ///
+    pub async fn ask(&self, cmd: &str) -> Result<String, Error> {
+        //
+        // Measure perfs
+        let start = Instant::now();
+
+        //
+        // Perform request
+        let mut response: Vec<u8> = Vec::new();
+        self.sub_interface
+            .lock()
+            .await
+            .execute_command(cmd.as_bytes(), &mut response)
+            .await?;
+
+        //
+        // Log
+        log_trace!(
+            self.logger,
+            "ASK <=> {:?} - {:?} - {:.2?}",
+            cmd,
+            response,
+            start.elapsed()
+        );
+
+        let v = ScpiString::from_vec_ascii(&response).map_err(|e| Error::DeserializeError(e))?;
+        Ok(v.into())
+    }
+
+    /// Send a command without response
+    ///
+    pub async fn send(&self, cmd: &str) -> Result<(), Error> {
+        self.sub_interface
+            .lock()
+            .await
+            .send_command(cmd.as_bytes())
+            .await
+    }
+
+    /// Get a value returned on 4 digits in resolution units
+    ///
+    pub async fn get_fixed_parameter(&self, cmd: &str, resolution: f64) -> Result<f64, Error> {
+        let response = self.ask(cmd).await?;
+        let raw = response.parse::<u32>().map_err(|_| {
+            Error::DeserializeError(format!("Invalid value {:?} for {:?}", response, cmd))
+        })?;
+        Ok(raw as f64 * resolution)
+    }
+
+    /// Set a value sent on 4 digits in resolution units
+    ///
+    pub async fn set_fixed_parameter(
+        &self,
+        cmd: &str,
+        value: f64,
+        resolution: f64,
+    ) -> Result<(), Error> {
+        let raw = (value / resolution).round();
+        if !(0.0..=9999.0).contains(&raw) {
+            return Err(Error::InvalidArgument(format!(
+                "Value {} out of range for {:?}",
+                value, cmd
+            )));
+        }
+        self.send(&format!("{}{:04}", cmd, raw as u32)).await
+    }
+
+    ///
+    ///
+    async fn get_protection(&self, commands: &ProtectionCommands) -> Result<f64, Error> {
+        self.get_fixed_parameter(commands.get, commands.resolution)
+            .await
+    }
+
+    /// Set a protection limit and check that the getter reads it back
+    ///
+    /// A different read back means that the limit was not applied.
+    ///
+    async fn set_protection(&self, commands: &ProtectionCommands, value: f64) -> Result<(), Error> {
+        self.set_fixed_parameter(commands.set, value, commands.resolution)
+            .await?;
+        let read_back = self.get_protection(commands).await?;
+        if (read_back - value).abs() > commands.resolution / 2.0 {
+            return Err(Error::Generic(format!(
+                "Protection limit {} not applied, read back as {}",
+                value, read_back
+            )));
+        }
+        Ok(())
+    }
+
+    ///
+    ///
+    fn channel_commands(channel_id: usize) -> Result<&'static ChannelCommands, Error> {
+        CHANNEL_COMMANDS
+            .get(channel_id.wrapping_sub(1))
+            .ok_or(Error::InvalidArgument(format!(
+                "Invalid channel {}",
+                channel_id
+            )))
+    }
+
+    ///
+    ///
+    pub async fn get_voltage(&self, channel_id: usize) -> Result<f64, Error> {
+        let cmds = Self::channel_commands(channel_id)?;
+        self.get_fixed_parameter(cmds.get_voltage, VOLTAGE_RESOLUTION)
+            .await
+    }
+
+    ///
+    ///
+    pub async fn set_voltage(&self, channel_id: usize, value: f64) -> Result<(), Error> {
+        let cmds = Self::channel_commands(channel_id)?;
+        self.set_fixed_parameter(cmds.set_voltage, value, VOLTAGE_RESOLUTION)
+            .await
+    }
+
+    ///
+    ///
+    pub async fn get_current(&self, channel_id: usize) -> Result<f64, Error> {
+        let cmds = Self::channel_commands(channel_id)?;
+        self.get_fixed_parameter(cmds.get_current, CURRENT_RESOLUTION)
+            .await
+    }
+
+    ///
+    ///
+    pub async fn set_current(&self, channel_id: usize, value: f64) -> Result<(), Error> {
+        let cmds = Self::channel_commands(channel_id)?;
+        self.set_fixed_parameter(cmds.set_current, value, CURRENT_RESOLUTION)
+            .await
+    }
+
+    ///
+    ///
+    pub async fn measure_voltage(&self, channel_id: usize) -> Result<f64, Error> {
+        let cmds = Self::channel_commands(channel_id)?;
+        self.get_fixed_parameter(cmds.measure_voltage, VOLTAGE_RESOLUTION)
+            .await
+    }
+
+    ///
+    ///
+    pub async fn measure_current(&self, channel_id: usize) -> Result<f64, Error> {
+        let cmds = Self::channel_commands(channel_id)?;
+        self.get_fixed_parameter(cmds.measure_current, CURRENT_RESOLUTION)
+            .await
+    }
+
+    /// Power is not measured by the device, it is computed from the measures
+    ///
+    pub async fn measure_power(&self, channel_id: usize) -> Result<f64, Error> {
+        let voltage = self.measure_voltage(channel_id).await?;
+        let current = self.measure_current(channel_id).await?;
+        Ok(voltage * current)
+    }
+}
+
+#[async_trait]
+impl ReplProtocol for PPS2000Interface {
+    ///
+    ///
+    async fn eval(&mut self, command: String) -> Result<String, Error> {
+        self.ask(&command).await
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum BooleanIndex {
+    //
+    Output,
+}
+
+#[async_trait]
+///
+///
+impl BooleanAccessorModel for PPS2000Interface {
+    ///
+    ///
+    async fn get_boolean_at(&mut self, index: usize) -> Result<bool, Error> {
+        //
+        // Get the index
+        let idx = BooleanIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            BooleanIndex::Output => Ok(self.ask("ro").await? == "1"),
+        }
+    }
+
+    ///
+    ///
+    async fn set_boolean_at(&mut self, index: usize, value: bool) -> Result<(), Error> {
+        //
+        // Get the index
+        let idx = BooleanIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            BooleanIndex::Output => self.send(if value { "o1" } else { "o0" }).await,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum StringIndex {
+    //
+    TrackingMode,
+}
+
+#[async_trait]
+///
+///
+impl StringAccessorModel for PPS2000Interface {
+    ///
+    ///
+    async fn get_string_at(&mut self, index: usize) -> Result<String, Error> {
+        //
+        // Get the index
+        let idx = StringIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            StringIndex::TrackingMode => {
+                let code = self.ask("rt").await?;
+                code.parse::<usize>()
+                    .ok()
+                    .and_then(|c| TRACKING_MODE_CHOICES.get(c))
+                    .map(|c| c.to_string())
+                    .ok_or(Error::DeserializeError(format!(
+                        "Invalid tracking mode {:?}",
+                        code
+                    )))
+            }
+        }
+    }
+
+    ///
+    ///
+    async fn set_string_at(&mut self, index: usize, value: &String) -> Result<(), Error> {
+        //
+        // Get the index
+        let idx = StringIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            StringIndex::TrackingMode => {
+                let code = TRACKING_MODE_CHOICES
+                    .iter()
+                    .position(|c| ScpiString::new(value).matches_mnemonic(c))
+                    .ok_or(Error::InvalidArgument(format!(
+                        "Invalid tracking mode {:?}",
+                        value
+                    )))?;
+                self.send(&format!("sm{}", code)).await
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum FloatIndex {
+    //
+    Channel1Voltage,
+    Channel1Current,
+    Channel1MeasuredVoltage,
+    Channel1MeasuredCurrent,
+    Channel1MeasuredPower,
+    //
+    Channel2Voltage,
+    Channel2Current,
+    Channel2MeasuredVoltage,
+    Channel2MeasuredCurrent,
+    Channel2MeasuredPower,
+    //
+    OverVoltageProtection,
+    OverCurrentProtection,
+}
+
+#[async_trait]
+///
+///
+impl FloatAccessorModel for PPS2000Interface {
+    ///
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error> {
+        //
+        // Get the index
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            //
+            FloatIndex::Channel1Voltage => self.get_voltage(1).await,
+            FloatIndex::Channel1Current => self.get_current(1).await,
+            FloatIndex::Channel1MeasuredVoltage => self.measure_voltage(1).await,
+            FloatIndex::Channel1MeasuredCurrent => self.measure_current(1).await,
+            FloatIndex::Channel1MeasuredPower => self.measure_power(1).await,
+            //
+            FloatIndex::Channel2Voltage => self.get_voltage(2).await,
+            FloatIndex::Channel2Current => self.get_current(2).await,
+            FloatIndex::Channel2MeasuredVoltage => self.measure_voltage(2).await,
+            FloatIndex::Channel2MeasuredCurrent => self.measure_current(2).await,
+            FloatIndex::Channel2MeasuredPower => self.measure_power(2).await,
+            //
+            FloatIndex::OverVoltageProtection => {
+                self.get_protection(&OVER_VOLTAGE_PROTECTION).await
+            }
+            FloatIndex::OverCurrentProtection => {
+                self.get_protection(&OVER_CURRENT_PROTECTION).await
+            }
+        }
+    }
+
+    ///
+    ///
+    async fn set_float_at(&mut self, index: usize, value: f64) -> Result<(), Error> {
+        //
+        // Get the index
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+
+        //
+        // Perform the request
+        match idx {
+            //
+            FloatIndex::Channel1Voltage => self.set_voltage(1, value).await,
+            FloatIndex::Channel1Current => self.set_current(1, value).await,
+            //
+            FloatIndex::Channel2Voltage => self.set_voltage(2, value).await,
+            FloatIndex::Channel2Current => self.set_current(2, value).await,
+            //
+            FloatIndex::OverVoltageProtection => {
+                self.set_protection(&OVER_VOLTAGE_PROTECTION, value).await
+            }
+            FloatIndex::OverCurrentProtection => {
+                self.set_protection(&OVER_CURRENT_PROTECTION, value).await
+            }
+            //
+            FloatIndex::Channel1MeasuredVoltage
+            | FloatIndex::Channel1MeasuredCurrent
+            | FloatIndex::Channel1MeasuredPower
+            | FloatIndex::Channel2MeasuredVoltage
+            | FloatIndex::Channel2MeasuredCurrent
+            | FloatIndex::Channel2MeasuredPower => {
+                Err(Error::InvalidArgument("Measures are read only".to_string()))
+            }
+        }
+    }
+}
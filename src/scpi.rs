@@ -0,0 +1,14 @@
+mod scpi_block;
+pub use scpi_block::ScpiBlock;
+
+mod scpi_boolean;
+pub use scpi_boolean::ScpiBoolean;
+
+mod scpi_numeric;
+pub use scpi_numeric::ScpiNumeric;
+
+mod scpi_string;
+pub use scpi_string::ScpiString;
+
+mod scpi_tmc;
+pub use scpi_tmc::ScpiTmc;
@@ -0,0 +1,82 @@
+/// SCPI definite length arbitrary block (IEEE 488.2)
+///
+/// Encoded as `#<n><length on n digits><data>`, e.g. `#14abcd`.
+///
+pub struct ScpiBlock {
+    /// Payload of the block
+    ///
+    data: Vec<u8>,
+}
+
+impl ScpiBlock {
+    /// Create a new ScpiBlock from its payload
+    ///
+    pub fn new(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+
+    /// Decode a block received from the instrument
+    ///
+    /// Bytes after the payload (terminator) are ignored.
+    ///
+    pub fn from_vec(v: &[u8]) -> Result<Self, String> {
+        //
+        // Skip anything before the block header
+        let start = v
+            .iter()
+            .position(|b| *b == b'#')
+            .ok_or("Invalid block: missing '#' header".to_string())?;
+        let header = &v[start..];
+
+        //
+        // Number of digits of the length
+        let digits = header
+            .get(1)
+            .and_then(|d| (*d as char).to_digit(10))
+            .ok_or("Invalid block: bad length digit count".to_string())?
+            as usize;
+        if digits == 0 {
+            return Err("Indefinite length blocks are not supported".to_string());
+        }
+
+        //
+        // Length of the payload
+        let length_str = header
+            .get(2..2 + digits)
+            .ok_or("Invalid block: truncated header".to_string())?;
+        let length = std::str::from_utf8(length_str)
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .ok_or(format!("Invalid block length: {:?}", length_str))?;
+
+        //
+        // Payload
+        let data = header.get(2 + digits..2 + digits + length).ok_or(format!(
+            "Invalid block: {} bytes expected, {} received",
+            length,
+            header.len().saturating_sub(2 + digits)
+        ))?;
+        Ok(Self::new(data.to_vec()))
+    }
+
+    /// Encode the block to be sent to the instrument
+    ///
+    pub fn to_vec(&self) -> Vec<u8> {
+        let length = self.data.len().to_string();
+        let mut v = format!("#{}{}", length.len(), length).into_bytes();
+        v.extend_from_slice(&self.data);
+        v
+    }
+
+    /// Get the payload
+    ///
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Take the payload
+    ///
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
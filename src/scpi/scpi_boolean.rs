@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use super::ScpiString;
+
 /// SCPI Boolean type
 ///
 pub struct ScpiBoolean {
@@ -17,8 +19,10 @@ impl ScpiBoolean {
 
     /// Create a new ScpiBoolean from a string
     ///
+    /// Terminators, whitespace and quotes around the value are ignored.
+    ///
     pub fn from_str_case_insensitive(s: &str) -> Result<Self, String> {
-        match s.to_lowercase().as_str() {
+        match ScpiString::new(s).as_str().to_lowercase().as_str() {
             "on" | "1" => Ok(Self::new(true)),
             "off" | "0" => Ok(Self::new(false)),
             _ => Err(format!("Invalid boolean value: {:?}", s)),
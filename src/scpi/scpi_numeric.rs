@@ -0,0 +1,329 @@
+use std::str::FromStr;
+
+/// Sentinel returned by SCPI instruments for "not a number"
+///
+const SENTINEL_NAN: f64 = 9.91e37;
+
+/// Sentinel returned by SCPI instruments for an overflow (+/- infinity)
+///
+const SENTINEL_INFINITY: f64 = 9.9e37;
+
+/// SCPI Numeric type
+///
+/// Handles NR1 (integer), NR2 (fixed point) and NR3 (exponent) values, the
+/// `MAXimum`/`MINimum`/`DEFault` keywords and the not-a-number/overflow sentinels.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScpiNumeric {
+    /// A finite numeric value
+    ///
+    Value(f64),
+    /// `MAXimum` keyword
+    ///
+    Maximum,
+    /// `MINimum` keyword
+    ///
+    Minimum,
+    /// `DEFault` keyword
+    ///
+    Default,
+    /// Not-a-number sentinel (9.91E37)
+    ///
+    NotANumber,
+    /// Positive overflow sentinel (9.9E37)
+    ///
+    PositiveInfinity,
+    /// Negative overflow sentinel (-9.9E37)
+    ///
+    NegativeInfinity,
+}
+
+impl ScpiNumeric {
+    /// Create a new ScpiNumeric from a f64
+    ///
+    pub fn new(value: f64) -> Self {
+        if value.is_nan() {
+            Self::NotANumber
+        } else if value == f64::INFINITY {
+            Self::PositiveInfinity
+        } else if value == f64::NEG_INFINITY {
+            Self::NegativeInfinity
+        } else {
+            Self::Value(value)
+        }
+    }
+
+    /// Create a new ScpiNumeric from a string
+    ///
+    pub fn from_str_case_insensitive(s: &str) -> Result<Self, String> {
+        //
+        // Remove terminators and every whitespace, instruments sometimes put spaces
+        // between the mantissa and the exponent or before the unit
+        let compact: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '\0' && *c != ';')
+            .collect::<String>()
+            .to_uppercase();
+
+        //
+        // Keywords
+        match compact.as_str() {
+            "MAX" | "MAXIMUM" => return Ok(Self::Maximum),
+            "MIN" | "MINIMUM" => return Ok(Self::Minimum),
+            "DEF" | "DEFAULT" => return Ok(Self::Default),
+            "NAN" => return Ok(Self::NotANumber),
+            "INF" | "INFINITY" => return Ok(Self::PositiveInfinity),
+            "NINF" | "NINFINITY" => return Ok(Self::NegativeInfinity),
+            _ => {}
+        }
+
+        //
+        // Split the number from its suffix
+        let split = numeric_prefix_len(&compact);
+        if split == 0 {
+            return Err(format!("Invalid numeric value: {:?}", s));
+        }
+        let (number, suffix) = compact.split_at(split);
+        let value = number
+            .parse::<f64>()
+            .map_err(|_| format!("Invalid numeric value: {:?}", s))?;
+        let multiplier =
+            suffix_multiplier(suffix).ok_or(format!("Invalid numeric suffix: {:?}", s))?;
+
+        //
+        // Map sentinels
+        if (value - SENTINEL_NAN).abs() < 5e34 {
+            Ok(Self::NotANumber)
+        } else if value >= SENTINEL_INFINITY {
+            Ok(Self::PositiveInfinity)
+        } else if value <= -SENTINEL_INFINITY {
+            Ok(Self::NegativeInfinity)
+        } else {
+            Ok(Self::Value(value * multiplier))
+        }
+    }
+
+    /// Create a new ScpiNumeric from a vector of u8
+    ///
+    pub fn from_vec_ascii(v: &[u8]) -> Result<Self, String> {
+        let s =
+            String::from_utf8(v.to_vec()).map_err(|e| format!("Invalid numeric value: {:?}", e))?;
+        Self::from_str_case_insensitive(s.as_str())
+    }
+
+    /// Convert into a f64, sentinels are mapped to NaN and infinities
+    ///
+    /// Keywords cannot be converted because their value depends on the instrument.
+    ///
+    pub fn to_f64(self) -> Result<f64, String> {
+        match self {
+            Self::Value(v) => Ok(v),
+            Self::NotANumber => Ok(f64::NAN),
+            Self::PositiveInfinity => Ok(f64::INFINITY),
+            Self::NegativeInfinity => Ok(f64::NEG_INFINITY),
+            Self::Maximum | Self::Minimum | Self::Default => {
+                Err(format!("Keyword {:?} has no numeric value", self))
+            }
+        }
+    }
+
+    /// Get the string representation to send to the instrument
+    ///
+    pub fn to_scpi_string(self) -> String {
+        match self {
+            Self::Value(v) => format_value(v),
+            Self::Maximum => "MAX".to_string(),
+            Self::Minimum => "MIN".to_string(),
+            Self::Default => "DEF".to_string(),
+            Self::NotANumber => "NAN".to_string(),
+            Self::PositiveInfinity => "INF".to_string(),
+            Self::NegativeInfinity => "NINF".to_string(),
+        }
+    }
+}
+
+/// Length of the leading part of `s` that looks like a NR1/NR2/NR3 number
+///
+fn numeric_prefix_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+
+    if i < bytes.len() && (bytes[i] == b'+' || bytes[i] == b'-') {
+        i += 1;
+    }
+    let mantissa_start = i;
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        i += 1;
+    }
+    if i < bytes.len() && bytes[i] == b'.' {
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+    }
+    if i == mantissa_start || (i == mantissa_start + 1 && bytes[mantissa_start] == b'.') {
+        return 0;
+    }
+
+    //
+    // The exponent is only consumed if digits follow, "1E" is not a number
+    if i < bytes.len() && bytes[i] == b'E' {
+        let mut j = i + 1;
+        if j < bytes.len() && (bytes[j] == b'+' || bytes[j] == b'-') {
+            j += 1;
+        }
+        let digits_start = j;
+        while j < bytes.len() && bytes[j].is_ascii_digit() {
+            j += 1;
+        }
+        if j > digits_start {
+            i = j;
+        }
+    }
+    i
+}
+
+/// Multiplier for a SCPI suffix (e.g. `MV`, `US`, `MHZ`), None if unknown
+///
+fn suffix_multiplier(suffix: &str) -> Option<f64> {
+    const UNITS: [&str; 11] = [
+        "HZ", "OHM", "DBV", "DB", "DIV", "PTS", "SA/S", "V", "A", "S", "W",
+    ];
+
+    let unit = UNITS.iter().find(|u| suffix.ends_with(*u)).unwrap_or(&"");
+    let prefix = &suffix[..suffix.len() - unit.len()];
+
+    //
+    // SCPI reads "M" as milli, except for frequencies, resistances, sample rates
+    // and point counts where "MHZ", "MOHM", "MSA/S" and "MPTS" are mega
+    match prefix {
+        "" => Some(1.0),
+        "EX" => Some(1e18),
+        "PE" => Some(1e15),
+        "T" => Some(1e12),
+        "G" => Some(1e9),
+        "MA" => Some(1e6),
+        "M" if ["HZ", "OHM", "SA/S", "PTS"].contains(unit) => Some(1e6),
+        "K" => Some(1e3),
+        "M" => Some(1e-3),
+        "U" => Some(1e-6),
+        "N" => Some(1e-9),
+        "P" => Some(1e-12),
+        "F" => Some(1e-15),
+        _ => None,
+    }
+}
+
+/// Format a finite value with the shortest representation that reads back exactly
+///
+fn format_value(v: f64) -> String {
+    //
+    // Integers are sent as NR1
+    if v.fract() == 0.0 && v.abs() < 1e9 {
+        return format!("{}", v as i64);
+    }
+
+    //
+    // Others as NR3, e.g. 1234.5678 is sent as 1.2345678E3
+    format!("{:E}", v)
+}
+
+/// Implicit conversion from f64 to ScpiNumeric
+///
+impl From<f64> for ScpiNumeric {
+    fn from(v: f64) -> Self {
+        Self::new(v)
+    }
+}
+
+/// Explicit conversion from &str to ScpiNumeric
+///
+impl FromStr for ScpiNumeric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ScpiNumeric::from_str_case_insensitive(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> ScpiNumeric {
+        ScpiNumeric::from_str_case_insensitive(s).unwrap()
+    }
+
+    #[test]
+    fn parse_numbers() {
+        assert_eq!(parse("12"), ScpiNumeric::Value(12.0));
+        assert_eq!(parse("-0.25"), ScpiNumeric::Value(-0.25));
+        assert_eq!(parse("+1.5E-3"), ScpiNumeric::Value(1.5e-3));
+        assert_eq!(parse("1.000000e+02\n"), ScpiNumeric::Value(100.0));
+        assert_eq!(parse(".5"), ScpiNumeric::Value(0.5));
+        assert!(ScpiNumeric::from_str_case_insensitive("").is_err());
+        assert!(ScpiNumeric::from_str_case_insensitive(".").is_err());
+        assert!(ScpiNumeric::from_str_case_insensitive("ABC").is_err());
+    }
+
+    #[test]
+    fn parse_nr3_with_spaces() {
+        assert_eq!(parse(" 1.0 E 3 "), ScpiNumeric::Value(1e3));
+        assert_eq!(parse("2.5E -2;"), ScpiNumeric::Value(2.5e-2));
+        assert_eq!(parse("3.3 V\0"), ScpiNumeric::Value(3.3));
+        //
+        // A dangling exponent is not a number
+        assert!(ScpiNumeric::from_str_case_insensitive("1E").is_err());
+    }
+
+    #[test]
+    fn parse_sentinels() {
+        assert_eq!(parse("9.91E37"), ScpiNumeric::NotANumber);
+        assert_eq!(parse("9.9E37"), ScpiNumeric::PositiveInfinity);
+        assert_eq!(parse("-9.9E+37"), ScpiNumeric::NegativeInfinity);
+        assert!(parse("9.91E37").to_f64().unwrap().is_nan());
+        assert_eq!(parse("9.9E37").to_f64().unwrap(), f64::INFINITY);
+    }
+
+    #[test]
+    fn parse_keywords() {
+        assert_eq!(parse("MAX"), ScpiNumeric::Maximum);
+        assert_eq!(parse("minimum"), ScpiNumeric::Minimum);
+        assert_eq!(parse("Def"), ScpiNumeric::Default);
+        assert_eq!(parse("NAN"), ScpiNumeric::NotANumber);
+        assert_eq!(parse("INF"), ScpiNumeric::PositiveInfinity);
+        assert_eq!(parse("NINF"), ScpiNumeric::NegativeInfinity);
+        assert!(parse("MAX").to_f64().is_err());
+    }
+
+    #[test]
+    fn parse_suffixes() {
+        assert_eq!(parse("500MV"), ScpiNumeric::Value(0.5));
+        let us = parse("20us").to_f64().unwrap();
+        assert!((us - 20e-6).abs() < 1e-18);
+        assert_eq!(parse("10MS"), ScpiNumeric::Value(10e-3));
+        assert_eq!(parse("5MA"), ScpiNumeric::Value(5e-3));
+        assert_eq!(parse("1KHZ"), ScpiNumeric::Value(1e3));
+        assert_eq!(parse("2MHZ"), ScpiNumeric::Value(2e6));
+        assert_eq!(parse("1MOHM"), ScpiNumeric::Value(1e6));
+        assert_eq!(parse("100MSa/s"), ScpiNumeric::Value(100e6));
+        assert_eq!(parse("10MPTS"), ScpiNumeric::Value(10e6));
+        assert_eq!(parse("1GSA/S"), ScpiNumeric::Value(1e9));
+        assert_eq!(parse("3MAHZ"), ScpiNumeric::Value(3e6));
+        assert_eq!(parse("-3DBV"), ScpiNumeric::Value(-3.0));
+        assert!(ScpiNumeric::from_str_case_insensitive("1XV").is_err());
+    }
+
+    #[test]
+    fn format_values() {
+        assert_eq!(ScpiNumeric::new(100.0).to_scpi_string(), "100");
+        assert_eq!(ScpiNumeric::new(1234.5678).to_scpi_string(), "1.2345678E3");
+        assert_eq!(ScpiNumeric::new(f64::NAN).to_scpi_string(), "NAN");
+        assert_eq!(ScpiNumeric::new(f64::NEG_INFINITY).to_scpi_string(), "NINF");
+        let v = 2.5e-7;
+        assert_eq!(
+            parse(&ScpiNumeric::new(v).to_scpi_string()),
+            ScpiNumeric::Value(v)
+        );
+    }
+}
@@ -0,0 +1,72 @@
+/// SCPI String type
+///
+/// Normalized form of a string response: terminators, surrounding whitespace and
+/// quotes are removed.
+///
+pub struct ScpiString {
+    /// Internal representation of the normalized string
+    ///
+    value: String,
+}
+
+impl ScpiString {
+    /// Create a new ScpiString from a raw response
+    ///
+    pub fn new(raw: &str) -> Self {
+        let trimmed = raw.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+        let unquoted = ['"', '\'']
+            .iter()
+            .find_map(|q| trimmed.strip_prefix(*q).and_then(|s| s.strip_suffix(*q)))
+            .unwrap_or(trimmed);
+        Self {
+            value: unquoted.to_string(),
+        }
+    }
+
+    /// Create a new ScpiString from a vector of u8
+    ///
+    pub fn from_vec_ascii(v: &Vec<u8>) -> Result<Self, String> {
+        let s =
+            String::from_utf8(v.clone()).map_err(|e| format!("Invalid string value: {:?}", e))?;
+        Ok(Self::new(s.as_str()))
+    }
+
+    /// Get the normalized string
+    ///
+    pub fn as_str(&self) -> &str {
+        self.value.as_str()
+    }
+
+    /// Check if the string matches a SCPI mnemonic
+    ///
+    /// The mnemonic is given in its mixed case form (e.g. `COUPling`), the string
+    /// matches either its long form (`COUPLING`) or its short form (`COUP`) whatever
+    /// the case.
+    ///
+    pub fn matches_mnemonic(&self, mnemonic: &str) -> bool {
+        let value = self.value.to_uppercase();
+        value == mnemonic.to_uppercase() || value == short_form(mnemonic)
+    }
+
+    /// Find the choice that matches this string
+    ///
+    pub fn resolve<'a>(&self, choices: &[&'a str]) -> Option<&'a str> {
+        choices.iter().copied().find(|c| self.matches_mnemonic(c))
+    }
+}
+
+/// Short form of a SCPI mnemonic: its uppercase letters and digits
+///
+/// A mnemonic without lowercase letters is its own short form.
+///
+fn short_form(mnemonic: &str) -> String {
+    mnemonic.chars().filter(|c| !c.is_lowercase()).collect()
+}
+
+/// Implicit conversion from ScpiString to String
+///
+impl From<ScpiString> for String {
+    fn from(s: ScpiString) -> Self {
+        s.value
+    }
+}
@@ -0,0 +1,175 @@
+use panduza_platform_core::connector::usb::tmc::Driver as UsbTmcInterface;
+use panduza_platform_core::{log_trace, Error, Logger};
+use tokio::sync::Mutex;
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::ScpiBoolean;
+use super::ScpiNumeric;
+use super::ScpiString;
+
+#[derive(Clone)]
+/// SCPI commands and queries over a USB TMC connection
+///
+/// Shared by the interfaces of the SCPI instruments of the plugin.
+///
+pub struct ScpiTmc {
+    /// Lower level interface (USB TMC)
+    ///
+    sub_interface: Arc<Mutex<UsbTmcInterface>>,
+
+    /// Logger for the driver
+    ///
+    logger: Logger,
+}
+
+impl ScpiTmc {
+    ///
+    ///
+    pub fn new(sub_interface: Arc<Mutex<UsbTmcInterface>>, logger: Logger) -> Self {
+        Self {
+            sub_interface,
+            logger,
+        }
+    }
+
+    /// Send a query and return the raw response of the device
+    ///
+    pub async fn ask(&self, cmd: &[u8]) -> Result<Vec<u8>, Error> {
+        //
+        // Measure perfs
+        let start = Instant::now();
+
+        //
+        // Perform request
+        let mut response: Vec<u8> = Vec::new();
+        self.sub_interface
+            .lock()
+            .await
+            .execute_command(cmd, &mut response)
+            .await?;
+
+        //
+        // Log
+        log_trace!(
+            self.logger,
+            "ASK <=> {:?} - {:?} - {:.2?}",
+            cmd,
+            response,
+            start.elapsed()
+        );
+
+        Ok(response)
+    }
+
+    /// Send a command without response
+    ///
+    pub async fn send(&self, cmd: &str) -> Result<(), Error> {
+        self.send_bytes(cmd.as_bytes()).await
+    }
+
+    /// Send a command with binary content (e.g. a definite length block)
+    ///
+    pub async fn send_bytes(&self, cmd: &[u8]) -> Result<(), Error> {
+        self.sub_interface.lock().await.send_command(cmd).await
+    }
+
+    /// Forward a command of the REPL class
+    ///
+    pub async fn eval(&self, command: String) -> Result<String, Error> {
+        self.sub_interface.lock().await.eval(command).await
+    }
+
+    /// Generic way to get boolean parameter from the device
+    ///
+    pub async fn get_boolean_parameter(&self, cmd: &[u8]) -> Result<bool, Error> {
+        let response = self.ask(cmd).await?;
+        let v = ScpiBoolean::from_vec_ascii(&response).map_err(|e| Error::DeserializeError(e))?;
+        Ok(v.into())
+    }
+
+    ///
+    ///
+    pub async fn set_boolean_parameter(&self, cmd: &str, value: bool) -> Result<(), Error> {
+        self.send(&format!(
+            "{} {}",
+            cmd,
+            ScpiBoolean::new(value).to_digital_str()
+        ))
+        .await
+    }
+
+    /// Generic way to get string parameter from the device
+    ///
+    /// Terminators and quotes around the response are removed.
+    ///
+    pub async fn get_string_parameter(&self, cmd: &[u8]) -> Result<String, Error> {
+        let response = self.ask(cmd).await?;
+        let v = ScpiString::from_vec_ascii(&response).map_err(|e| Error::DeserializeError(e))?;
+        Ok(v.into())
+    }
+
+    /// Generic way to get enum parameter from the device
+    ///
+    /// The response is matched against the long and short forms of the choices,
+    /// the matching choice is returned as given in `choices`.
+    ///
+    pub async fn get_enum_parameter(&self, cmd: &[u8], choices: &[&str]) -> Result<String, Error> {
+        let response = self.ask(cmd).await?;
+        let v = ScpiString::from_vec_ascii(&response).map_err(|e| Error::DeserializeError(e))?;
+        v.resolve(choices)
+            .map(|c| c.to_string())
+            .ok_or(Error::DeserializeError(format!(
+                "Invalid enum value: {:?} not in {:?}",
+                v.as_str(),
+                choices
+            )))
+    }
+
+    /// Generic way to get enum parameter with numeric choices (e.g. "1.5", "8")
+    ///
+    /// The response is compared as a number, the scope may answer "1.500000E+00".
+    ///
+    pub async fn get_numeric_enum_parameter(
+        &self,
+        cmd: &[u8],
+        choices: &[&str],
+    ) -> Result<String, Error> {
+        let value = self.get_float_parameter(cmd).await?;
+        choices
+            .iter()
+            .find(|c| c.parse::<f64>().map(|f| f == value).unwrap_or(false))
+            .map(|c| c.to_string())
+            .ok_or(Error::DeserializeError(format!(
+                "Invalid enum value: {} not in {:?}",
+                value, choices
+            )))
+    }
+
+    ///
+    ///
+    pub async fn set_string_parameter(&self, cmd: &str, value: &String) -> Result<(), Error> {
+        self.send(&format!("{} {}", cmd, value)).await
+    }
+
+    /// Generic way to get float parameter from the device
+    ///
+    pub async fn get_float_parameter(&self, cmd: &[u8]) -> Result<f64, Error> {
+        let response = self.ask(cmd).await?;
+        ScpiNumeric::from_vec_ascii(&response)
+            .and_then(|v| v.to_f64())
+            .map_err(|e| Error::DeserializeError(e))
+    }
+
+    ///
+    ///
+    pub async fn set_float_parameter(&self, cmd: &str, value: f64) -> Result<(), Error> {
+        self.send(&format!(
+            "{} {}",
+            cmd,
+            ScpiNumeric::new(value).to_scpi_string()
+        ))
+        .await
+    }
+}
@@ -0,0 +1,193 @@
+use async_trait::async_trait;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, SiAttServer,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Accessor to float parameters of an interface, indexed like the boolean and
+/// string accessors of the platform
+///
+#[async_trait]
+pub trait FloatAccessorModel: Send + Sync {
+    ///
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error>;
+
+    ///
+    ///
+    async fn set_float_at(&mut self, index: usize, value: f64) -> Result<(), Error>;
+}
+
+/// Format of a SI attribute
+///
+#[derive(Clone, Copy, Debug)]
+pub struct SiFormat {
+    /// Unit of the value
+    ///
+    pub unit: &'static str,
+
+    /// Minimal value
+    ///
+    pub min: f64,
+
+    /// Maximal value
+    ///
+    pub max: f64,
+
+    /// Number of decimals displayed
+    ///
+    pub decimals: usize,
+}
+
+impl SiFormat {
+    ///
+    ///
+    pub const fn new(unit: &'static str, min: f64, max: f64, decimals: usize) -> Self {
+        Self {
+            unit,
+            min,
+            max,
+            decimals,
+        }
+    }
+}
+
+/// Mount a read/write SI attribute bound to a float parameter of the interface
+///
+pub async fn mount<C: Container + 'static, I: FloatAccessorModel + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<I>>,
+    index: usize,
+    name: &str,
+    info: &str,
+    format: SiFormat,
+) -> Result<(), Error> {
+    //
+    //
+    let att = parent
+        .create_attribute(name)
+        .with_rw()
+        .with_info(info)
+        .finish_as_si(format.unit, format.min, format.max, format.decimals)
+        .await?;
+    let logger = att.logger().clone();
+    log_debug_mount_start!(logger);
+
+    // Set the value, an instrument error leaves it unset without failing the mount
+    match interface.lock().await.get_float_at(index).await {
+        Ok(v) => att.set_from_f32(v as f32).await?,
+        Err(e) => log_warn!(logger, "si initial read failed '{:?}'", e),
+    }
+
+    //
+    spawn_on_command!(
+        "on_command => si",
+        parent,
+        att,
+        on_command(att.clone(), interface.clone(), index)
+    );
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+///
+///
+async fn on_command<I: FloatAccessorModel + 'static>(
+    mut att: SiAttServer,
+    interface: Arc<Mutex<I>>,
+    index: usize,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd_as_f32().await {
+        match command {
+            Ok(c) => {
+                //
+                // Log
+                log_debug!(att.logger(), "si command received '{:?}'", c);
+
+                //
+                //
+                let result = interface.lock().await.set_float_at(index, c as f64).await;
+                if let Err(e) = result {
+                    log_warn!(att.logger(), "si command failed '{:?}'", e);
+                    continue;
+                }
+
+                //
+                // Set the read back value
+                let read_back = interface.lock().await.get_float_at(index).await;
+                match read_back {
+                    Ok(v) => att.set_from_f32(v as f32).await?,
+                    Err(e) => log_warn!(att.logger(), "si read back failed '{:?}'", e),
+                }
+            }
+            Err(e) => {
+                log_warn!(att.logger(), "invalid si command '{:?}'", e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Mount a read only SI attribute refreshed periodically from the interface
+///
+pub async fn mount_ro<C: Container + 'static, I: FloatAccessorModel + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<I>>,
+    index: usize,
+    name: &str,
+    info: &str,
+    format: SiFormat,
+    period: Duration,
+) -> Result<(), Error> {
+    //
+    //
+    let att = parent
+        .create_attribute(name)
+        .with_ro()
+        .with_info(info)
+        .finish_as_si(format.unit, format.min, format.max, format.decimals)
+        .await?;
+    let logger = att.logger().clone();
+    log_debug_mount_start!(logger);
+
+    // Set the value, an instrument error leaves it unset until the next refresh
+    match interface.lock().await.get_float_at(index).await {
+        Ok(v) => att.set_from_f32(v as f32).await?,
+        Err(e) => log_warn!(logger, "si initial read failed '{:?}'", e),
+    }
+
+    //
+    // Refresh the value, the task belongs to the instance and stops with it
+    parent
+        .spawn("refresh => si", refresh(att, interface, index, period))
+        .await;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+/// Refresh a read only attribute periodically
+///
+async fn refresh<I: FloatAccessorModel + 'static>(
+    mut att: SiAttServer,
+    interface: Arc<Mutex<I>>,
+    index: usize,
+    period: Duration,
+) -> Result<(), Error> {
+    loop {
+        tokio::time::sleep(period).await;
+        let value = interface.lock().await.get_float_at(index).await;
+        match value {
+            Ok(v) => att.set_from_f32(v as f32).await?,
+            Err(e) => log_warn!(att.logger(), "si refresh failed '{:?}'", e),
+        }
+    }
+}
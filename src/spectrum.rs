@@ -0,0 +1,255 @@
+mod analyzer;
+pub use analyzer::{Spectrum, SpectrumAnalyzer, SpectrumMetrics, THD_HARMONICS};
+
+mod fft;
+
+mod window;
+pub use window::{Window, WINDOW_CHOICES};
+
+use async_trait::async_trait;
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::std::attribute::r#enum::StringAccessorModel;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use strum_macros::FromRepr;
+use tokio::sync::Mutex;
+
+use crate::si as std_att_si;
+use crate::si::{FloatAccessorModel, SiFormat};
+use crate::waveform::WaveformAccessorModel;
+
+/// Refresh period of the metrics attributes
+///
+const METRICS_PERIOD: Duration = Duration::from_secs(1);
+
+/// Maximal number of averaged captures
+///
+pub const MAX_AVERAGES: usize = 256;
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum StringIndex {
+    //
+    Window,
+}
+
+#[async_trait]
+///
+///
+impl StringAccessorModel for SpectrumAnalyzer {
+    ///
+    ///
+    async fn get_string_at(&mut self, index: usize) -> Result<String, Error> {
+        match StringIndex::from_repr(index) {
+            Some(StringIndex::Window) => Ok(self.window().name().to_string()),
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+
+    ///
+    ///
+    async fn set_string_at(&mut self, index: usize, value: &String) -> Result<(), Error> {
+        match StringIndex::from_repr(index) {
+            Some(StringIndex::Window) => {
+                let window = Window::from_name(value).map_err(Error::InvalidArgument)?;
+                self.set_window(window);
+                Ok(())
+            }
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, PartialEq)]
+pub enum FloatIndex {
+    //
+    Averages,
+    //
+    Fundamental,
+    Thd,
+    Snr,
+}
+
+#[async_trait]
+///
+///
+impl FloatAccessorModel for SpectrumAnalyzer {
+    ///
+    /// Metrics are 0 until a spectrum with a fundamental has been computed
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error> {
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        let metrics = self.last().and_then(|s| s.metrics());
+        Ok(match idx {
+            FloatIndex::Averages => self.averages() as f64,
+            FloatIndex::Fundamental => metrics.map(|m| m.fundamental).unwrap_or(0.0),
+            FloatIndex::Thd => metrics.map(|m| m.thd).unwrap_or(0.0),
+            FloatIndex::Snr => metrics.map(|m| m.snr).unwrap_or(0.0),
+        })
+    }
+
+    ///
+    ///
+    async fn set_float_at(&mut self, index: usize, value: f64) -> Result<(), Error> {
+        match FloatIndex::from_repr(index) {
+            Some(FloatIndex::Averages) => {
+                self.set_averages((value.round().max(1.0) as usize).min(MAX_AVERAGES));
+                Ok(())
+            }
+            _ => Err(Error::InvalidArgument("Read only value".to_string())),
+        }
+    }
+}
+
+/// Mount a class that computes on the host the spectrum of a waveform
+///
+/// * window, averages: settings of the computation
+/// * data: write any value to capture the waveform and publish the spectrum
+/// * fundamental, thd, snr: measurements of the last spectrum
+///
+pub async fn mount<C: Container + 'static, I: WaveformAccessorModel + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<I>>,
+    index: usize,
+    name: &str,
+) -> Result<(), Error> {
+    let mut class_spectrum = parent.create_class(name).finish().await;
+
+    let logger = class_spectrum.logger().clone();
+    log_debug_mount_start!(logger);
+
+    let analyzer = Arc::new(Mutex::new(SpectrumAnalyzer::default()));
+
+    //
+    //
+    std_att_enum::mount(
+        class_spectrum.clone(),
+        analyzer.clone(),
+        StringIndex::Window as usize,
+        "window",
+        "
+    * HANN, HAMMING: good compromise for periodic signals
+    * BLACKMAN_HARRIS: low leakage, for small components near a large one
+    * FLAT_TOP: accurate amplitudes
+        ",
+        WINDOW_CHOICES.to_vec(),
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount(
+        class_spectrum.clone(),
+        analyzer.clone(),
+        FloatIndex::Averages as usize,
+        "averages",
+        "Number of captures averaged, the average restarts when a setting changes",
+        SiFormat::new("", 1.0, MAX_AVERAGES as f64, 0),
+    )
+    .await?;
+
+    //
+    //
+    let att = class_spectrum
+        .create_attribute("data")
+        .with_rw()
+        .with_info("Write any value to capture the waveform and publish the averaged spectrum")
+        .finish_as_json()
+        .await?;
+    spawn_on_command!(
+        "on_command => spectrum",
+        class_spectrum,
+        att,
+        on_command(att.clone(), interface.clone(), analyzer.clone(), index)
+    );
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_spectrum.clone(),
+        analyzer.clone(),
+        FloatIndex::Fundamental as usize,
+        "fundamental",
+        "Frequency of the highest peak of the spectrum",
+        SiFormat::new("Hz", 0.0, 10_000_000_000.0, 3),
+        METRICS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_spectrum.clone(),
+        analyzer.clone(),
+        FloatIndex::Thd as usize,
+        "thd",
+        "Total harmonic distortion, up to the 10th harmonic",
+        SiFormat::new("%", 0.0, 10_000.0, 4),
+        METRICS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_spectrum.clone(),
+        analyzer.clone(),
+        FloatIndex::Snr as usize,
+        "snr",
+        "Signal to noise ratio, harmonics excluded
+
+The leakage of the HAMMING window limits the ratio to about 40 dB, prefer
+HANN or BLACKMAN_HARRIS to measure a low noise.
+",
+        SiFormat::new("dB", -400.0, 400.0, 2),
+        METRICS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+///
+///
+async fn on_command<I: WaveformAccessorModel + 'static>(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<I>>,
+    analyzer: Arc<Mutex<SpectrumAnalyzer>>,
+    index: usize,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Log
+        log_debug!(att.logger(), "spectrum requested '{:?}'", command);
+
+        let waveform = interface.lock().await.get_waveform_at(index).await;
+        let spectrum = match waveform {
+            Ok(w) => analyzer
+                .lock()
+                .await
+                .push(&w)
+                .map_err(Error::InvalidArgument),
+            Err(e) => Err(e),
+        };
+        match spectrum {
+            Ok(s) => att.set(s.to_json()).await?,
+            Err(e) => log_warn!(att.logger(), "spectrum computation failed '{:?}'", e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,400 @@
+use std::collections::VecDeque;
+
+use super::fft::power_spectrum;
+use super::Window;
+use crate::waveform::Waveform;
+
+/// Number of harmonics included in the THD
+///
+pub const THD_HARMONICS: usize = 10;
+
+/// Lowest magnitude published, in dBV, instead of -inf on empty bins
+///
+const MAGNITUDE_FLOOR_DBV: f64 = -200.0;
+
+/// Averaged single sided spectrum
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrum {
+    /// Power of each bin, in Vrms²
+    ///
+    pub power: Vec<f64>,
+
+    /// Frequency between two bins, in Hz
+    ///
+    pub resolution: f64,
+
+    /// Half width of the window main lobe, in bins
+    ///
+    pub lobe: usize,
+
+    /// Half width of the window leakage around a peak, in bins
+    ///
+    pub leakage: usize,
+
+    /// Number of captures averaged
+    ///
+    pub averaged: usize,
+}
+
+/// Measurements derived from a spectrum
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectrumMetrics {
+    /// Frequency of the fundamental, in Hz
+    ///
+    pub fundamental: f64,
+
+    /// Total harmonic distortion, in %
+    ///
+    pub thd: f64,
+
+    /// Signal to noise ratio, in dB
+    ///
+    pub snr: f64,
+}
+
+impl Spectrum {
+    /// Frequency of the bin `k`, in Hz
+    ///
+    pub fn frequency_at(&self, k: usize) -> f64 {
+        k as f64 * self.resolution
+    }
+
+    /// Magnitudes of the bins, in dBV
+    ///
+    pub fn magnitudes_dbv(&self) -> Vec<f64> {
+        self.power
+            .iter()
+            .map(|p| (10.0 * p.log10()).max(MAGNITUDE_FLOOR_DBV))
+            .collect()
+    }
+
+    /// JSON representation published on the attributes
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "unit": "dBV",
+            "averaged": self.averaged,
+            "frequencies": (0..self.power.len()).map(|k| self.frequency_at(k)).collect::<Vec<f64>>(),
+            "magnitudes": self.magnitudes_dbv(),
+        })
+    }
+
+    /// Power of the peak around bin `k`, and the bins it covers
+    ///
+    fn lobe_around(&self, k: usize) -> (f64, std::ops::Range<usize>) {
+        let range = k.saturating_sub(self.lobe)..(k + self.lobe + 1).min(self.power.len());
+        (self.power[range.clone()].iter().sum(), range)
+    }
+
+    /// Highest bin within the main lobe width of bin `k`
+    ///
+    fn peak_near(&self, k: usize) -> usize {
+        let range = k.saturating_sub(self.lobe)..(k + self.lobe + 1).min(self.power.len());
+        range
+            .max_by(|a, b| self.power[*a].total_cmp(&self.power[*b]))
+            .unwrap_or(k)
+    }
+
+    /// Fundamental frequency, THD and SNR
+    ///
+    /// The fundamental is the highest peak above DC, its frequency is the power
+    /// weighted center of its main lobe. Harmonics are searched around the
+    /// multiples of the fundamental. The noise is measured on the remaining bins
+    /// above DC, out of the leakage of the fundamental, and extended to all of
+    /// them.
+    ///
+    pub fn metrics(&self) -> Option<SpectrumMetrics> {
+        let first = self.lobe + 1;
+        if self.power.len() <= first + self.lobe {
+            return None;
+        }
+
+        //
+        // Fundamental
+        let peak =
+            (first..self.power.len()).max_by(|a, b| self.power[*a].total_cmp(&self.power[*b]))?;
+        let (fundamental_power, range) = self.lobe_around(peak);
+        if fundamental_power <= 0.0 {
+            return None;
+        }
+        let center =
+            range.clone().map(|k| k as f64 * self.power[k]).sum::<f64>() / fundamental_power;
+        let mut used = vec![false; self.power.len()];
+        used[..first].iter_mut().for_each(|u| *u = true);
+        used[range].iter_mut().for_each(|u| *u = true);
+        let leakage =
+            peak.saturating_sub(self.leakage)..(peak + self.leakage + 1).min(self.power.len());
+
+        //
+        // Harmonics
+        let mut harmonics_power = 0.0;
+        for h in 2..=THD_HARMONICS {
+            let expected = (center * h as f64).round() as usize;
+            if expected + self.lobe >= self.power.len() {
+                break;
+            }
+            let (power, range) = self.lobe_around(self.peak_near(expected));
+            let fresh = range.clone().all(|k| !used[k]);
+            if fresh {
+                harmonics_power += power;
+                used[range].iter_mut().for_each(|u| *u = true);
+            }
+        }
+
+        //
+        // Noise
+        let noise_bins: Vec<f64> = self
+            .power
+            .iter()
+            .enumerate()
+            .filter(|(k, _)| !used[*k] && !leakage.contains(k))
+            .map(|(_, p)| *p)
+            .collect();
+        let noise_power = noise_bins.iter().sum::<f64>() * (self.power.len() - first) as f64
+            / noise_bins.len().max(1) as f64;
+
+        Some(SpectrumMetrics {
+            fundamental: center * self.resolution,
+            thd: 100.0 * (harmonics_power / fundamental_power).sqrt(),
+            snr: 10.0 * (fundamental_power / noise_power).log10(),
+        })
+    }
+}
+
+/// Spectrum computation over successive captures
+///
+/// The power spectra of the last `averages` captures are averaged, the history
+/// restarts when the settings or the capture format change.
+///
+#[derive(Debug, Clone)]
+pub struct SpectrumAnalyzer {
+    /// Window applied to the captures
+    ///
+    window: Window,
+
+    /// Number of captures averaged
+    ///
+    averages: usize,
+
+    /// Power spectra of the last captures
+    ///
+    history: VecDeque<Vec<f64>>,
+
+    /// Format of the captures in the history (points, x_increment)
+    ///
+    format: Option<(usize, f64)>,
+
+    /// Result of the last computation
+    ///
+    last: Option<Spectrum>,
+}
+
+impl Default for SpectrumAnalyzer {
+    fn default() -> Self {
+        Self {
+            window: Window::Hann,
+            averages: 1,
+            history: VecDeque::new(),
+            format: None,
+            last: None,
+        }
+    }
+}
+
+impl SpectrumAnalyzer {
+    ///
+    ///
+    pub fn window(&self) -> Window {
+        self.window
+    }
+
+    ///
+    ///
+    pub fn set_window(&mut self, window: Window) {
+        if window != self.window {
+            self.window = window;
+            self.reset();
+        }
+    }
+
+    ///
+    ///
+    pub fn averages(&self) -> usize {
+        self.averages
+    }
+
+    /// Set the number of averaged captures, at least 1
+    ///
+    pub fn set_averages(&mut self, averages: usize) {
+        self.averages = averages.max(1);
+        while self.history.len() > self.averages {
+            self.history.pop_front();
+        }
+    }
+
+    /// Forget the previous captures
+    ///
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.format = None;
+        self.last = None;
+    }
+
+    /// Result of the last computation
+    ///
+    pub fn last(&self) -> Option<&Spectrum> {
+        self.last.as_ref()
+    }
+
+    /// Add a capture and compute the averaged spectrum
+    ///
+    pub fn push(&mut self, waveform: &Waveform) -> Result<Spectrum, String> {
+        if waveform.len() < 2 {
+            return Err(format!(
+                "At least 2 samples are required, {} captured",
+                waveform.len()
+            ));
+        }
+
+        //
+        // Restart the average on a new format
+        let format = (waveform.len(), waveform.x_increment);
+        if self.format != Some(format) {
+            self.reset();
+            self.format = Some(format);
+        }
+
+        //
+        // Remove the DC before windowing, it would leak on the low bins
+        let mean = waveform.samples.iter().sum::<f64>() / waveform.len() as f64;
+        let samples: Vec<f64> = waveform.samples.iter().map(|s| s - mean).collect();
+        let window = self.window.coefficients(samples.len());
+        let mut power = power_spectrum(&samples, &window);
+        power[0] = mean * mean;
+        self.history.push_back(power);
+        while self.history.len() > self.averages {
+            self.history.pop_front();
+        }
+
+        //
+        // Average
+        let bins = self.history[0].len();
+        let count = self.history.len();
+        let mut power = vec![0.0; bins];
+        for spectrum in self.history.iter() {
+            for (acc, p) in power.iter_mut().zip(spectrum.iter()) {
+                *acc += p / count as f64;
+            }
+        }
+
+        let size = waveform.len().next_power_of_two();
+        let padding = size as f64 / waveform.len() as f64;
+        let spectrum = Spectrum {
+            power,
+            resolution: waveform.sample_rate() / size as f64,
+            lobe: (self.window.main_lobe_half_width() as f64 * padding).ceil() as usize,
+            leakage: (self.window.leakage_half_width() as f64 * padding).ceil() as usize,
+            averaged: count,
+        };
+        self.last = Some(spectrum.clone());
+        Ok(spectrum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    const SAMPLE_RATE: f64 = 1e6;
+
+    /// Gaussian noise of standard deviation `sigma`, from a fixed seed
+    ///
+    fn noise(n: usize, sigma: f64) -> Vec<f64> {
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut uniform = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            ((state >> 11) as f64 + 1.0) / (1u64 << 53) as f64
+        };
+        (0..n)
+            .map(|_| sigma * (-2.0 * uniform().ln()).sqrt() * (2.0 * PI * uniform()).cos())
+            .collect()
+    }
+
+    /// Sine of 1 Vpk at `frequency`, with a 2nd harmonic and an offset
+    ///
+    fn capture(n: usize, frequency: f64, harmonic: f64, offset: f64) -> Waveform {
+        let samples = (0..n)
+            .map(|i| {
+                let t = i as f64 / SAMPLE_RATE;
+                offset
+                    + (2.0 * PI * frequency * t).sin()
+                    + harmonic * (4.0 * PI * frequency * t).sin()
+            })
+            .collect();
+        Waveform::new(samples, 0.0, 1.0 / SAMPLE_RATE, "V")
+    }
+
+    fn spectrum(window: Window, waveform: &Waveform) -> Spectrum {
+        let mut analyzer = SpectrumAnalyzer::default();
+        analyzer.set_window(window);
+        analyzer.push(waveform).unwrap()
+    }
+
+    #[test]
+    fn measure_fundamental_and_thd() {
+        let waveform = capture(4000, 12_345.6, 0.01, 0.0);
+        for window in [Window::Hann, Window::BlackmanHarris, Window::FlatTop] {
+            let metrics = spectrum(window, &waveform).metrics().unwrap();
+            assert!((metrics.fundamental - 12_345.6).abs() < 5.0, "{:?}", window);
+            assert!((metrics.thd - 1.0).abs() < 0.05, "{:?}", window);
+        }
+    }
+
+    #[test]
+    fn measure_snr_out_of_the_leakage() {
+        //
+        // Noise 68 dB below a 1 Vpk sine, between two bins
+        let n = 4000;
+        let sigma = 0.5f64.sqrt() / 10f64.powf(68.0 / 20.0);
+        let mut waveform = capture(n, 18_311.0, 0.0, 0.0);
+        for (s, e) in waveform.samples.iter_mut().zip(noise(n, sigma)) {
+            *s += e;
+        }
+        for window in [Window::Hann, Window::BlackmanHarris, Window::FlatTop] {
+            let metrics = spectrum(window, &waveform).metrics().unwrap();
+            assert!(
+                (metrics.snr - 68.0).abs() < 1.0,
+                "{:?} {}",
+                window,
+                metrics.snr
+            );
+        }
+    }
+
+    #[test]
+    fn average_every_bin() {
+        let mut analyzer = SpectrumAnalyzer::default();
+        analyzer.set_averages(2);
+        analyzer.push(&capture(1024, 15_625.0, 0.0, 1.0)).unwrap();
+        let spectrum = analyzer.push(&capture(1024, 15_625.0, 0.0, 3.0)).unwrap();
+        assert_eq!(spectrum.averaged, 2);
+        assert!((spectrum.power[0] - 5.0).abs() < 1e-9);
+        assert!((spectrum.power[16] - 0.5).abs() < 1e-9);
+        assert!((spectrum.resolution - 976.5625).abs() < 1e-9);
+    }
+
+    #[test]
+    fn restart_average_on_new_format() {
+        let mut analyzer = SpectrumAnalyzer::default();
+        analyzer.set_averages(4);
+        analyzer.push(&capture(1024, 15_625.0, 0.0, 0.0)).unwrap();
+        let spectrum = analyzer.push(&capture(2048, 15_625.0, 0.0, 0.0)).unwrap();
+        assert_eq!(spectrum.averaged, 1);
+        assert!(analyzer.push(&capture(1, 15_625.0, 0.0, 0.0)).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use std::f64::consts::PI;
+
+/// In place radix-2 FFT of complex points given as (re, im)
+///
+/// The length must be a power of two.
+///
+pub fn fft(points: &mut [(f64, f64)]) {
+    let n = points.len();
+    if n < 2 {
+        return;
+    }
+
+    //
+    // Bit reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            points.swap(i, j);
+        }
+    }
+
+    //
+    // Butterflies
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (wr, wi) = ((angle * k as f64).cos(), (angle * k as f64).sin());
+                let (ar, ai) = points[start + k];
+                let (br, bi) = points[start + k + len / 2];
+                let (tr, ti) = (br * wr - bi * wi, br * wi + bi * wr);
+                points[start + k] = (ar + tr, ai + ti);
+                points[start + k + len / 2] = (ar - tr, ai - ti);
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Single sided power spectrum of real samples, in units²rms per bin
+///
+/// The samples are windowed and zero padded to a power of two. The amplitude is
+/// corrected by the coherent gain of the window, so a sine of amplitude A gives
+/// a peak of A²/2.
+///
+pub fn power_spectrum(samples: &[f64], window: &[f64]) -> Vec<f64> {
+    let size = samples.len().next_power_of_two();
+    let gain: f64 = window.iter().sum();
+
+    let mut points: Vec<(f64, f64)> = samples
+        .iter()
+        .zip(window.iter())
+        .map(|(s, w)| (s * w, 0.0))
+        .collect();
+    points.resize(size, (0.0, 0.0));
+    fft(&mut points);
+
+    (0..=size / 2)
+        .map(|k| {
+            let (re, im) = points[k];
+            let amplitude = (re * re + im * im).sqrt() / gain;
+            if k == 0 || k == size / 2 {
+                amplitude * amplitude
+            } else {
+                2.0 * amplitude * amplitude
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibrate_sine_peak() {
+        //
+        // 1 Vpk sine centered on the bin 64, the peak reads 0.5 Vrms²
+        let n = 1024;
+        let samples: Vec<f64> = (0..n)
+            .map(|i| (2.0 * PI * 64.0 * i as f64 / n as f64).sin())
+            .collect();
+        for window in [vec![1.0; n], crate::spectrum::Window::Hann.coefficients(n)] {
+            let power = power_spectrum(&samples, &window);
+            assert_eq!(power.len(), n / 2 + 1);
+            assert!((power[64] - 0.5).abs() < 1e-9);
+            assert!(power[0] < 1e-20);
+        }
+    }
+
+    #[test]
+    fn calibrate_dc_and_padding() {
+        //
+        // A constant is all DC, 1000 points are padded to 1024
+        let power = power_spectrum(&[2.0; 1000], &[1.0; 1000]);
+        assert_eq!(power.len(), 513);
+        assert!((power[0] - 4.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,105 @@
+use std::f64::consts::PI;
+
+/// Window function applied to the samples before the FFT
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Window {
+    Hann,
+    Hamming,
+    BlackmanHarris,
+    FlatTop,
+}
+
+/// Names of the windows, as published on the attributes
+///
+pub const WINDOW_CHOICES: [&str; 4] = ["HANN", "HAMMING", "BLACKMAN_HARRIS", "FLAT_TOP"];
+
+impl Window {
+    /// Window from its name, case insensitive
+    ///
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_uppercase().as_str() {
+            "HANN" => Ok(Self::Hann),
+            "HAMMING" => Ok(Self::Hamming),
+            "BLACKMAN_HARRIS" => Ok(Self::BlackmanHarris),
+            "FLAT_TOP" => Ok(Self::FlatTop),
+            _ => Err(format!(
+                "Invalid window {:?}, expected one of {:?}",
+                name, WINDOW_CHOICES
+            )),
+        }
+    }
+
+    /// Name of the window
+    ///
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Hann => WINDOW_CHOICES[0],
+            Self::Hamming => WINDOW_CHOICES[1],
+            Self::BlackmanHarris => WINDOW_CHOICES[2],
+            Self::FlatTop => WINDOW_CHOICES[3],
+        }
+    }
+
+    /// Cosine sum coefficients a0, a1, ... of the window
+    ///
+    fn terms(&self) -> &'static [f64] {
+        match self {
+            Self::Hann => &[0.5, 0.5],
+            Self::Hamming => &[0.54, 0.46],
+            Self::BlackmanHarris => &[0.35875, 0.48829, 0.14128, 0.01168],
+            Self::FlatTop => &[
+                0.21557895,
+                0.41663158,
+                0.277263158,
+                0.083578947,
+                0.006947368,
+            ],
+        }
+    }
+
+    /// Half width of the main lobe, in bins
+    ///
+    /// Power within this distance of a peak belongs to the peak.
+    ///
+    pub fn main_lobe_half_width(&self) -> usize {
+        match self {
+            Self::Hann | Self::Hamming => 2,
+            Self::BlackmanHarris => 4,
+            Self::FlatTop => 5,
+        }
+    }
+
+    /// Half width of the leakage around a peak, in bins
+    ///
+    /// Beyond this distance the side lobes of a full scale sine fall below the
+    /// noise of an 8 bits capture. The side lobes of HAMMING decay too slowly to
+    /// get there, they limit the SNR it measures to about 40 dB.
+    ///
+    pub fn leakage_half_width(&self) -> usize {
+        match self {
+            Self::Hann | Self::Hamming => 24,
+            Self::BlackmanHarris => 6,
+            Self::FlatTop => 8,
+        }
+    }
+
+    /// Periodic window of `n` points
+    ///
+    pub fn coefficients(&self, n: usize) -> Vec<f64> {
+        let terms = self.terms();
+        (0..n)
+            .map(|i| {
+                let x = 2.0 * PI * i as f64 / n as f64;
+                terms
+                    .iter()
+                    .enumerate()
+                    .map(|(k, a)| {
+                        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                        sign * a * (k as f64 * x).cos()
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+}
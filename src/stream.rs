@@ -0,0 +1,488 @@
+use async_trait::async_trait;
+use panduza_platform_core::std::attribute::boolean as std_att_boolean;
+use panduza_platform_core::std::attribute::boolean::BooleanAccessorModel;
+use panduza_platform_core::std::attribute::r#enum as std_att_enum;
+use panduza_platform_core::std::attribute::r#enum::StringAccessorModel;
+use panduza_platform_core::{
+    log_debug_mount_end, log_debug_mount_start, log_warn, Container, Error, JsonAttServer, Logger,
+};
+use serde_json::json;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use strum_macros::FromRepr;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, watch, Mutex};
+
+use crate::si as std_att_si;
+use crate::si::{FloatAccessorModel, SiFormat};
+use crate::waveform::{Waveform, WaveformAccessorModel};
+
+/// Frames kept for a subscriber that falls behind, the oldest are dropped first
+///
+pub const STREAM_CAPACITY: usize = 8;
+
+/// Duration over which the frame rate is measured
+///
+const RATE_WINDOW: Duration = Duration::from_secs(2);
+
+/// Refresh period of the counters attributes
+///
+const COUNTERS_PERIOD: Duration = Duration::from_secs(1);
+
+/// Pause after a failed capture, before the next attempt
+///
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Capture pushed to the subscribers of a stream
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamFrame {
+    /// Number of the frame since the stream was enabled, starting at 1
+    ///
+    pub sequence: u64,
+
+    /// Time of the end of the capture, in seconds since the Unix epoch
+    ///
+    pub timestamp: f64,
+
+    /// Name of the captured source
+    ///
+    pub source: &'static str,
+
+    ///
+    ///
+    pub waveform: Waveform,
+}
+
+impl StreamFrame {
+    /// JSON representation published on the attributes
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "sequence": self.sequence,
+            "timestamp": self.timestamp,
+            "source": self.source,
+            "waveform": self.waveform.to_json(),
+        })
+    }
+}
+
+/// Settings and counters of a stream
+///
+pub struct StreamState {
+    /// Names of the sources and their waveform indexes
+    ///
+    sources: Vec<(&'static str, usize)>,
+
+    /// Position of the streamed source in `sources`
+    ///
+    source: usize,
+
+    /// Wakes up the capture task when the stream is enabled
+    ///
+    enable: watch::Sender<bool>,
+
+    /// Frames captured since the stream was enabled
+    ///
+    frames: u64,
+
+    /// Frames lost by the subscribers since the stream was enabled
+    ///
+    dropped: u64,
+
+    /// Capture times within the rate window
+    ///
+    recent: VecDeque<Instant>,
+}
+
+impl StreamState {
+    ///
+    ///
+    fn new(sources: Vec<(&'static str, usize)>) -> Self {
+        Self {
+            sources,
+            source: 0,
+            enable: watch::channel(false).0,
+            frames: 0,
+            dropped: 0,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Name and waveform index of the streamed source
+    ///
+    fn selected(&self) -> (&'static str, usize) {
+        self.sources[self.source]
+    }
+
+    /// Count a new capture, returns its sequence number
+    ///
+    fn record_frame(&mut self, now: Instant) -> u64 {
+        self.frames += 1;
+        self.recent.push_back(now);
+        self.prune(now);
+        self.frames
+    }
+
+    /// Forget the captures older than the rate window
+    ///
+    fn prune(&mut self, now: Instant) {
+        while let Some(oldest) = self.recent.front() {
+            if now.duration_since(*oldest) <= RATE_WINDOW {
+                break;
+            }
+            self.recent.pop_front();
+        }
+    }
+
+    /// Frames per second over the rate window
+    ///
+    fn rate(&mut self) -> f64 {
+        self.prune(Instant::now());
+        self.recent.len() as f64 / RATE_WINDOW.as_secs_f64()
+    }
+}
+
+/// Background capture of a waveform, pushed to the subscribers as fast as the
+/// interface allows
+///
+/// Each subscriber has its own bounded queue, when it falls behind the oldest
+/// frames are dropped and counted.
+///
+#[derive(Clone)]
+pub struct WaveformStream {
+    ///
+    ///
+    state: Arc<Mutex<StreamState>>,
+
+    ///
+    ///
+    sender: broadcast::Sender<Arc<StreamFrame>>,
+}
+
+impl WaveformStream {
+    /// Create a disabled stream on the first source
+    ///
+    pub fn new(sources: Vec<(&'static str, usize)>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(StreamState::new(sources))),
+            sender: broadcast::channel(STREAM_CAPACITY).0,
+        }
+    }
+
+    /// Receive the frames captured from now on
+    ///
+    pub fn subscribe(&self) -> StreamSubscriber {
+        StreamSubscriber {
+            receiver: self.sender.subscribe(),
+            state: self.state.clone(),
+        }
+    }
+
+    /// Capture loop, runs while the stream is enabled and waits otherwise
+    ///
+    async fn run<I: WaveformAccessorModel + 'static>(
+        self,
+        interface: Arc<Mutex<I>>,
+        logger: Logger,
+    ) -> Result<(), Error> {
+        let mut enable = self.state.lock().await.enable.subscribe();
+        loop {
+            while !*enable.borrow_and_update() {
+                if enable.changed().await.is_err() {
+                    return Ok(());
+                }
+            }
+
+            let (source, index) = self.state.lock().await.selected();
+            let waveform = interface.lock().await.get_waveform_at(index).await;
+            match waveform {
+                Ok(waveform) => {
+                    let sequence = self.state.lock().await.record_frame(Instant::now());
+                    let timestamp = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|d| d.as_secs_f64())
+                        .unwrap_or(0.0);
+                    // No subscriber is not an error, the frame is just not kept
+                    let _ = self.sender.send(Arc::new(StreamFrame {
+                        sequence,
+                        timestamp,
+                        source,
+                        waveform,
+                    }));
+                }
+                Err(e) => {
+                    log_warn!(logger, "stream capture failed '{:?}'", e);
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+            }
+        }
+    }
+}
+
+/// Receiver of the frames of a stream
+///
+pub struct StreamSubscriber {
+    ///
+    ///
+    receiver: broadcast::Receiver<Arc<StreamFrame>>,
+
+    ///
+    ///
+    state: Arc<Mutex<StreamState>>,
+}
+
+impl StreamSubscriber {
+    /// Wait for the next frame
+    ///
+    /// Frames dropped because the subscriber fell behind are added to the
+    /// counter of the stream. None once the stream is gone.
+    ///
+    pub async fn next(&mut self) -> Option<Arc<StreamFrame>> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(frame) => return Some(frame),
+                Err(RecvError::Lagged(count)) => self.state.lock().await.dropped += count,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum BooleanIndex {
+    //
+    Enable,
+}
+
+#[async_trait]
+///
+///
+impl BooleanAccessorModel for StreamState {
+    ///
+    ///
+    async fn get_boolean_at(&mut self, index: usize) -> Result<bool, Error> {
+        match BooleanIndex::from_repr(index) {
+            Some(BooleanIndex::Enable) => Ok(*self.enable.borrow()),
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+
+    ///
+    /// Counters restart when the stream is enabled
+    ///
+    async fn set_boolean_at(&mut self, index: usize, value: bool) -> Result<(), Error> {
+        match BooleanIndex::from_repr(index) {
+            Some(BooleanIndex::Enable) => {
+                if value && !*self.enable.borrow() {
+                    self.frames = 0;
+                    self.dropped = 0;
+                    self.recent.clear();
+                }
+                self.enable.send_replace(value);
+                Ok(())
+            }
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum StringIndex {
+    //
+    Source,
+}
+
+#[async_trait]
+///
+///
+impl StringAccessorModel for StreamState {
+    ///
+    ///
+    async fn get_string_at(&mut self, index: usize) -> Result<String, Error> {
+        match StringIndex::from_repr(index) {
+            Some(StringIndex::Source) => Ok(self.selected().0.to_string()),
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+
+    ///
+    ///
+    async fn set_string_at(&mut self, index: usize, value: &String) -> Result<(), Error> {
+        match StringIndex::from_repr(index) {
+            Some(StringIndex::Source) => {
+                self.source = self
+                    .sources
+                    .iter()
+                    .position(|(name, _)| name.eq_ignore_ascii_case(value))
+                    .ok_or(Error::InvalidArgument(format!(
+                        "Unknown source {:?}, expected one of {:?}",
+                        value,
+                        self.sources.iter().map(|s| s.0).collect::<Vec<_>>()
+                    )))?;
+                Ok(())
+            }
+            None => Err(Error::InvalidArgument("Invalid Index".to_string())),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
+pub enum FloatIndex {
+    //
+    FrameRate,
+    Frames,
+    Dropped,
+}
+
+#[async_trait]
+///
+///
+impl FloatAccessorModel for StreamState {
+    ///
+    ///
+    async fn get_float_at(&mut self, index: usize) -> Result<f64, Error> {
+        let idx = FloatIndex::from_repr(index)
+            .ok_or(Error::InvalidArgument("Invalid Index".to_string()))?;
+        Ok(match idx {
+            FloatIndex::FrameRate => self.rate(),
+            FloatIndex::Frames => self.frames as f64,
+            FloatIndex::Dropped => self.dropped as f64,
+        })
+    }
+
+    ///
+    ///
+    async fn set_float_at(&mut self, _index: usize, _value: f64) -> Result<(), Error> {
+        Err(Error::InvalidArgument("Read only value".to_string()))
+    }
+}
+
+/// Mount a class that streams the captures of a source
+///
+/// * enable, source: settings of the stream
+/// * frames: each capture, published as soon as the interface returns it
+/// * fps, count, dropped: counters since the stream was enabled
+///
+pub async fn mount<C: Container + 'static, I: WaveformAccessorModel + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<I>>,
+    sources: Vec<(&'static str, usize)>,
+) -> Result<(), Error> {
+    let mut class_stream = parent.create_class("stream").finish().await;
+
+    let logger = class_stream.logger().clone();
+    log_debug_mount_start!(logger);
+
+    let choices: Vec<&str> = sources.iter().map(|(name, _)| *name).collect();
+    let stream = WaveformStream::new(sources);
+
+    //
+    //
+    std_att_boolean::mount(
+        class_stream.clone(),
+        stream.state.clone(),
+        BooleanIndex::Enable as usize,
+        "enable",
+        "Capture continuously in the background and publish each capture on 'frames'",
+    )
+    .await?;
+
+    //
+    //
+    std_att_enum::mount(
+        class_stream.clone(),
+        stream.state.clone(),
+        StringIndex::Source as usize,
+        "source",
+        "Streamed source, can be changed while streaming",
+        choices,
+    )
+    .await?;
+
+    //
+    //
+    let att = class_stream
+        .create_attribute("frames")
+        .with_ro()
+        .with_info("Captures of the stream with their sequence number and timestamp")
+        .finish_as_json()
+        .await?;
+    //
+    // The tasks belong to the instance and stop with it
+    class_stream
+        .spawn("publish => stream frames", publish(att, stream.subscribe()))
+        .await;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_stream.clone(),
+        stream.state.clone(),
+        FloatIndex::FrameRate as usize,
+        "fps",
+        "Captures per second, measured over the last 2 seconds",
+        SiFormat::new("fps", 0.0, 10_000.0, 2),
+        COUNTERS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_stream.clone(),
+        stream.state.clone(),
+        FloatIndex::Frames as usize,
+        "count",
+        "Captures since the stream was enabled",
+        SiFormat::new("", 0.0, u64::MAX as f64, 0),
+        COUNTERS_PERIOD,
+    )
+    .await?;
+
+    //
+    //
+    std_att_si::mount_ro(
+        class_stream.clone(),
+        stream.state.clone(),
+        FloatIndex::Dropped as usize,
+        "dropped",
+        "Captures dropped because 'frames' could not be published fast enough",
+        SiFormat::new("", 0.0, u64::MAX as f64, 0),
+        COUNTERS_PERIOD,
+    )
+    .await?;
+
+    class_stream
+        .spawn("run => stream", stream.run(interface, logger.clone()))
+        .await;
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+/// Publish the frames of the stream on the attribute
+///
+async fn publish(mut att: JsonAttServer, mut subscriber: StreamSubscriber) -> Result<(), Error> {
+    while let Some(frame) = subscriber.next().await {
+        if let Err(e) = att.set(frame.to_json()).await {
+            log_warn!(att.logger(), "stream publication failed '{:?}'", e);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,290 @@
+use async_trait::async_trait;
+use panduza_platform_core::{
+    log_debug, log_debug_mount_end, log_debug_mount_start, log_warn, spawn_on_command, Container,
+    Error, JsonAttServer,
+};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Accessor to the waveforms of an interface, indexed like the other accessors
+///
+#[async_trait]
+pub trait WaveformAccessorModel: Send + Sync {
+    /// Capture the waveform of the given index
+    ///
+    async fn get_waveform_at(&mut self, index: usize) -> Result<Waveform, Error>;
+
+    /// Capture several waveforms from the same acquisition
+    ///
+    /// The default reads them one after the other, interfaces able to capture
+    /// them together override it.
+    ///
+    async fn get_waveforms_at(&mut self, indexes: &[usize]) -> Result<Vec<Waveform>, Error> {
+        let mut waveforms = Vec::with_capacity(indexes.len());
+        for index in indexes {
+            waveforms.push(self.get_waveform_at(*index).await?);
+        }
+        Ok(waveforms)
+    }
+}
+
+/// Waveform captured on a channel
+///
+/// Samples are evenly spaced, the time of the sample `i` is
+/// `x_origin + i * x_increment`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Waveform {
+    /// Samples in `unit`
+    ///
+    pub samples: Vec<f64>,
+
+    /// Time of the first sample, in seconds
+    ///
+    pub x_origin: f64,
+
+    /// Time between two samples, in seconds
+    ///
+    pub x_increment: f64,
+
+    /// Unit of the samples (e.g. V)
+    ///
+    pub unit: String,
+}
+
+impl Waveform {
+    /// Create a new waveform
+    ///
+    pub fn new<A: Into<String>>(
+        samples: Vec<f64>,
+        x_origin: f64,
+        x_increment: f64,
+        unit: A,
+    ) -> Self {
+        Self {
+            samples,
+            x_origin,
+            x_increment,
+            unit: unit.into(),
+        }
+    }
+
+    /// Number of samples
+    ///
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// True if the waveform has no sample
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Sample rate in samples per second
+    ///
+    pub fn sample_rate(&self) -> f64 {
+        1.0 / self.x_increment
+    }
+
+    /// Time of the sample `i`, in seconds
+    ///
+    pub fn time_at(&self, i: usize) -> f64 {
+        self.x_origin + i as f64 * self.x_increment
+    }
+
+    /// Duration covered by the samples, in seconds
+    ///
+    pub fn duration(&self) -> f64 {
+        self.samples.len() as f64 * self.x_increment
+    }
+
+    /// JSON representation published on the attributes
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "unit": self.unit,
+            "x_origin": self.x_origin,
+            "x_increment": self.x_increment,
+            "samples": self.samples,
+        })
+    }
+
+    /// Build a waveform from its JSON representation
+    ///
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, String> {
+        let samples = value
+            .get("samples")
+            .and_then(|s| s.as_array())
+            .ok_or("Waveform requires a 'samples' array".to_string())?
+            .iter()
+            .enumerate()
+            .map(|(i, v)| v.as_f64().ok_or(format!("Sample {} is not a number", i)))
+            .collect::<Result<Vec<f64>, String>>()?;
+        let x_increment = value
+            .get("x_increment")
+            .and_then(|v| v.as_f64())
+            .ok_or("Waveform requires a 'x_increment' number".to_string())?;
+        if !x_increment.is_finite() || x_increment <= 0.0 {
+            return Err(format!("Invalid x_increment {}", x_increment));
+        }
+        Ok(Self::new(
+            samples,
+            value
+                .get("x_origin")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0),
+            x_increment,
+            value.get("unit").and_then(|v| v.as_str()).unwrap_or("V"),
+        ))
+    }
+}
+
+/// Difference between a waveform and a reference waveform
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaveformComparison {
+    /// Number of samples compared
+    ///
+    pub points: usize,
+
+    /// RMS of the difference, in the unit of the waveforms
+    ///
+    pub rms_difference: f64,
+
+    /// Largest absolute difference, in the unit of the waveforms
+    ///
+    pub max_deviation: f64,
+
+    /// Time of the largest difference, in seconds
+    ///
+    pub max_deviation_time: f64,
+}
+
+impl WaveformComparison {
+    /// JSON representation published on the attributes
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "points": self.points,
+            "rms_difference": self.rms_difference,
+            "max_deviation": self.max_deviation,
+            "max_deviation_time": self.max_deviation_time,
+        })
+    }
+}
+
+impl Waveform {
+    /// Value at the time `t`, linearly interpolated between the samples
+    ///
+    /// None outside of the waveform.
+    ///
+    pub fn value_at(&self, t: f64) -> Option<f64> {
+        let position = (t - self.x_origin) / self.x_increment;
+        if self.samples.is_empty() || position < 0.0 || position > (self.len() - 1) as f64 {
+            return None;
+        }
+        let index = position.floor() as usize;
+        let a = self.samples[index];
+        let b = *self.samples.get(index + 1).unwrap_or(&a);
+        Some(a + (b - a) * (position - index as f64))
+    }
+
+    /// Compare the waveform to a reference
+    ///
+    /// The reference is interpolated at the time of each sample, only the samples
+    /// covered by the reference are compared.
+    ///
+    pub fn compare(&self, reference: &Waveform) -> Result<WaveformComparison, String> {
+        if self.unit != reference.unit {
+            return Err(format!(
+                "Cannot compare {} with a reference in {}",
+                self.unit, reference.unit
+            ));
+        }
+
+        let mut comparison = WaveformComparison {
+            points: 0,
+            rms_difference: 0.0,
+            max_deviation: 0.0,
+            max_deviation_time: 0.0,
+        };
+        let mut sum_squares = 0.0;
+        for (i, sample) in self.samples.iter().enumerate() {
+            let t = self.time_at(i);
+            if let Some(expected) = reference.value_at(t) {
+                let deviation = (sample - expected).abs();
+                sum_squares += deviation * deviation;
+                comparison.points += 1;
+                if deviation > comparison.max_deviation || comparison.points == 1 {
+                    comparison.max_deviation = deviation;
+                    comparison.max_deviation_time = t;
+                }
+            }
+        }
+        if comparison.points == 0 {
+            return Err("The waveform and the reference do not overlap in time".to_string());
+        }
+        comparison.rms_difference = (sum_squares / comparison.points as f64).sqrt();
+        Ok(comparison)
+    }
+}
+
+/// Mount a JSON attribute that publishes a waveform on demand
+///
+/// Writing any value to the attribute triggers a capture, the attribute is then
+/// updated with the waveform.
+///
+pub async fn mount<C: Container + 'static, I: WaveformAccessorModel + 'static>(
+    mut parent: C,
+    interface: Arc<Mutex<I>>,
+    index: usize,
+    name: &str,
+    info: &str,
+) -> Result<(), Error> {
+    //
+    //
+    let att = parent
+        .create_attribute(name)
+        .with_rw()
+        .with_info(info)
+        .finish_as_json()
+        .await?;
+    let logger = att.logger().clone();
+    log_debug_mount_start!(logger);
+
+    //
+    spawn_on_command!(
+        "on_command => waveform",
+        parent,
+        att,
+        on_command(att.clone(), interface.clone(), index)
+    );
+
+    //
+    //
+    log_debug_mount_end!(logger);
+    Ok(())
+}
+
+///
+///
+async fn on_command<I: WaveformAccessorModel + 'static>(
+    mut att: JsonAttServer,
+    interface: Arc<Mutex<I>>,
+    index: usize,
+) -> Result<(), Error> {
+    while let Some(command) = att.pop_cmd().await {
+        //
+        // Log
+        log_debug!(att.logger(), "waveform capture requested '{:?}'", command);
+
+        let waveform = interface.lock().await.get_waveform_at(index).await;
+        match waveform {
+            Ok(w) => att.set(w.to_json()).await?,
+            Err(e) => log_warn!(att.logger(), "waveform capture failed '{:?}'", e),
+        }
+    }
+    Ok(())
+}